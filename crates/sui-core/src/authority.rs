@@ -15,7 +15,7 @@ use fastcrypto::traits::KeyPair;
 use itertools::Itertools;
 use move_binary_format::compatibility::Compatibility;
 use move_binary_format::CompiledModule;
-use move_core_types::language_storage::ModuleId;
+use move_core_types::language_storage::{ModuleId, StructTag};
 use parking_lot::Mutex;
 use prometheus::{
     register_histogram_with_registry, register_int_counter_vec_with_registry,
@@ -60,7 +60,7 @@ use sui_types::crypto::{
 };
 use sui_types::digests::TransactionEventsDigest;
 use sui_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName, DynamicFieldType, Field};
-use sui_types::error::UserInputError;
+use sui_types::error::{UserInputError, CHECKPOINT_NOT_FOUND_MSG_PREFIX};
 use sui_types::event::{Event, EventID};
 use sui_types::gas::{GasCostSummary, GasPrice, SuiCostTable, SuiGasStatus};
 use sui_types::message_envelope::Message;
@@ -581,10 +581,13 @@ impl AuthorityState {
         }
 
         // Checks to see if the transaction has expired
-        if match &transaction.inner().data().transaction_data().expiration() {
-            TransactionExpiration::None => false,
-            TransactionExpiration::Epoch(epoch) => *epoch < epoch_store.epoch(),
-        } {
+        if transaction
+            .inner()
+            .data()
+            .transaction_data()
+            .expiration()
+            .is_expired(epoch_store.epoch())
+        {
             return Err(SuiError::TransactionExpired);
         }
 
@@ -2205,6 +2208,23 @@ impl AuthorityState {
             .collect())
     }
 
+    pub fn get_objects_by_type(
+        &self,
+        type_: StructTag,
+        // exclusive cursor if `Some`, otherwise start from the beginning
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> SuiResult<Vec<ObjectInfo>> {
+        if let Some(indexes) = &self.indexes {
+            Ok(indexes
+                .get_objects_by_type_iterator(type_, cursor)?
+                .take(limit)
+                .collect())
+        } else {
+            Err(SuiError::IndexStoreNotAvailable)
+        }
+    }
+
     pub fn get_dynamic_fields_iterator(
         &self,
         owner: ObjectID,
@@ -2366,7 +2386,7 @@ impl AuthorityState {
         match verified_checkpoint {
             Some(verified_checkpoint) => Ok(verified_checkpoint.into_inner().into_data()),
             None => Err(anyhow!(
-                "Verified checkpoint not found for sequence number {}",
+                "{CHECKPOINT_NOT_FOUND_MSG_PREFIX} for sequence number {}",
                 sequence_number
             )),
         }
@@ -2382,7 +2402,7 @@ impl AuthorityState {
         match verified_checkpoint {
             Some(verified_checkpoint) => Ok(verified_checkpoint.into_inner().into_data()),
             None => Err(anyhow!(
-                "Verified checkpoint not found for digest: {}",
+                "{CHECKPOINT_NOT_FOUND_MSG_PREFIX} for digest: {}",
                 Base58::encode(digest)
             )),
         }
@@ -2410,7 +2430,7 @@ impl AuthorityState {
                 self.get_checkpoint_contents(content_digest)
             }
             None => Err(anyhow!(
-                "Verified checkpoint not found for sequence number {}",
+                "{CHECKPOINT_NOT_FOUND_MSG_PREFIX} for sequence number {}",
                 sequence_number
             )),
         }
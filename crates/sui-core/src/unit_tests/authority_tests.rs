@@ -840,6 +840,39 @@ fn check_coin_value(actual_value: &[u8], actual_type: &SuiTypeTag, expected_valu
     assert_eq!(actual_coin.value(), expected_value);
 }
 
+#[tokio::test]
+async fn test_dev_inspect_gas_price() {
+    let (_validator, fullnode, _object_basics) =
+        init_state_with_ids_and_object_basics_with_fullnode(vec![]).await;
+
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let kind = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.pay_sui(vec![recipient], vec![500]).unwrap();
+        TransactionKind::programmable(builder.finish())
+    };
+
+    // Simulate the same transaction at two hypothetical gas prices.
+    let mut gas_used = vec![];
+    for gas_price in [1, 10] {
+        let DevInspectResults { effects, error, .. } = fullnode
+            .dev_inspect_transaction(sender, kind.clone(), Some(gas_price))
+            .await
+            .unwrap();
+        assert!(error.is_none());
+        gas_used.push(effects.gas_used().clone());
+    }
+
+    // Computation cost scales with the gas price, storage cost does not.
+    assert!(gas_used[0].computation_cost > 0);
+    assert_eq!(
+        gas_used[0].computation_cost * 10,
+        gas_used[1].computation_cost
+    );
+    assert_eq!(gas_used[0].storage_cost, gas_used[1].storage_cost);
+}
+
 #[tokio::test]
 async fn test_dev_inspect_uses_unbound_object() {
     let (sender, _sender_key): (_, AccountKeyPair) = get_key_pair();
@@ -3111,6 +3144,124 @@ async fn test_valid_immutable_clock_parameter() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn test_clock_arg_programmable_transaction() {
+    // The clock argument produced by the builder is accepted by functions taking `&Clock`.
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let gas_object_id = ObjectID::random();
+    let (authority_state, package_object_ref) =
+        init_state_with_ids_and_object_basics(vec![(sender, gas_object_id)]).await;
+
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let clock = builder.clock_arg().unwrap();
+        builder.programmable_move_call(
+            package_object_ref.0,
+            ident_str!("object_basics").to_owned(),
+            ident_str!("use_clock").to_owned(),
+            /* type_args */ vec![],
+            vec![clock],
+        );
+        assert_eq!(vec![SUI_CLOCK_OBJECT_ID], builder.shared_object_inputs());
+        builder.finish()
+    };
+
+    let effects = execute_programmable_transaction_(
+        &authority_state,
+        None,
+        &gas_object_id,
+        &sender,
+        &sender_key,
+        pt,
+        /* with_shared */ true,
+    )
+    .await
+    .unwrap();
+    assert!(effects.status().is_ok());
+}
+
+#[tokio::test]
+async fn test_read_only_shared_object_arg_programmable_transaction() {
+    // A shared object that is only borrowed immutably can be passed as a read-only input.
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let gas_object_id = ObjectID::random();
+    let (authority_state, package_object_ref) =
+        init_state_with_ids_and_object_basics(vec![(sender, gas_object_id)]).await;
+    let package = package_object_ref.0;
+
+    let effects = call_move_(
+        &authority_state,
+        None,
+        &gas_object_id,
+        &sender,
+        &sender_key,
+        &package,
+        "object_basics",
+        "share",
+        vec![],
+        vec![],
+        true,
+    )
+    .await
+    .unwrap();
+    let (shared_object_id, initial_shared_version, _) = effects.created()[0].0;
+    let effects = call_move(
+        &authority_state,
+        &gas_object_id,
+        &sender,
+        &sender_key,
+        &package,
+        "object_basics",
+        "create",
+        vec![],
+        vec![
+            TestCallArg::Pure(bcs::to_bytes(&(16_u64)).unwrap()),
+            TestCallArg::Pure(bcs::to_bytes(&sender).unwrap()),
+        ],
+    )
+    .await
+    .unwrap();
+    let owned_object_ref = effects.created()[0].0;
+
+    // `update` takes its first object by `&mut` and its second by `&`.
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let owned = builder
+            .obj(ObjectArg::ImmOrOwnedObject(owned_object_ref))
+            .unwrap();
+        let shared = builder
+            .shared_object_arg(shared_object_id, initial_shared_version, false)
+            .unwrap();
+        builder.programmable_move_call(
+            package,
+            ident_str!("object_basics").to_owned(),
+            ident_str!("update").to_owned(),
+            /* type_args */ vec![],
+            vec![owned, shared],
+        );
+        builder.finish()
+    };
+    let read_only = CallArg::Object(ObjectArg::SharedObject {
+        id: shared_object_id,
+        initial_shared_version,
+        mutable: false,
+    });
+    assert!(pt.inputs.contains(&read_only));
+
+    let effects = execute_programmable_transaction_(
+        &authority_state,
+        None,
+        &gas_object_id,
+        &sender,
+        &sender_key,
+        pt,
+        /* with_shared */ true,
+    )
+    .await
+    .unwrap();
+    assert!(effects.status().is_ok());
+}
+
 #[tokio::test]
 async fn test_genesis_sui_system_state_object() {
     // This test verifies that we can read the genesis SuiSystemState object.
@@ -5,6 +5,7 @@ module entry_point_vector::entry_point_vector {
     use sui::object::{Self, UID};
     use sui::transfer;
     use sui::tx_context::{Self, TxContext};
+    use std::option::{Self, Option};
     use std::vector;
 
     struct Obj has key, store {
@@ -73,6 +74,22 @@ module entry_point_vector::entry_point_vector {
         assert!(vector::length(&v) == 2, 0);
     }
 
+    public entry fun addr_vec_len(v: vector<address>, _: &mut TxContext) {
+        assert!(vector::length(&v) == 2, 0);
+        assert!(*vector::borrow(&v, 0) != *vector::borrow(&v, 1), 0);
+    }
+
+    public entry fun bytes_vec(v: vector<u8>, _: &mut TxContext) {
+        assert!(v == b"hello", 0);
+    }
+
+    public entry fun option_u64(o: Option<u64>, is_some: bool, _: &mut TxContext) {
+        assert!(option::is_some(&o) == is_some, 0);
+        if (is_some) {
+            assert!(option::destroy_some(o) == 42, 0);
+        }
+    }
+
     public entry fun obj_vec_empty(v: vector<Obj>, _: &mut TxContext) {
         vector::destroy_empty(v);
     }
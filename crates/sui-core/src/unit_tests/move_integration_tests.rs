@@ -959,6 +959,200 @@ async fn test_entry_point_vector_primitive() {
     );
 }
 
+#[tokio::test]
+#[cfg_attr(msim, ignore)]
+async fn test_entry_point_vector_pure_vec() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let gas = ObjectID::random();
+    let authority = init_state_with_ids(vec![(sender, gas)]).await;
+
+    let package = build_and_publish_test_package(
+        &authority,
+        &sender,
+        &sender_key,
+        &gas,
+        "entry_point_vector",
+        /* with_unpublished_deps */ false,
+    )
+    .await;
+
+    // vector<address> argument
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let addresses = builder
+            .pure_vec(vec![
+                SuiAddress::random_for_testing_only(),
+                SuiAddress::random_for_testing_only(),
+            ])
+            .unwrap();
+        builder.programmable_move_call(
+            package.0,
+            Identifier::new("entry_point_vector").unwrap(),
+            Identifier::new("addr_vec_len").unwrap(),
+            vec![],
+            vec![addresses],
+        );
+        builder.finish()
+    };
+    let effects = execute_programmable_transaction(&authority, &gas, &sender, &sender_key, pt)
+        .await
+        .unwrap();
+    assert!(
+        matches!(effects.status(), ExecutionStatus::Success { .. }),
+        "{:?}",
+        effects.status()
+    );
+
+    // vector<u8> argument
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let bytes = builder.pure_vec(b"hello".to_vec()).unwrap();
+        builder.programmable_move_call(
+            package.0,
+            Identifier::new("entry_point_vector").unwrap(),
+            Identifier::new("bytes_vec").unwrap(),
+            vec![],
+            vec![bytes],
+        );
+        builder.finish()
+    };
+    let effects = execute_programmable_transaction(&authority, &gas, &sender, &sender_key, pt)
+        .await
+        .unwrap();
+    assert!(
+        matches!(effects.status(), ExecutionStatus::Success { .. }),
+        "{:?}",
+        effects.status()
+    );
+}
+
+#[tokio::test]
+#[cfg_attr(msim, ignore)]
+async fn test_entry_point_pure_option() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let gas = ObjectID::random();
+    let authority = init_state_with_ids(vec![(sender, gas)]).await;
+
+    let package = build_and_publish_test_package(
+        &authority,
+        &sender,
+        &sender_key,
+        &gas,
+        "entry_point_vector",
+        /* with_unpublished_deps */ false,
+    )
+    .await;
+
+    // Options are encoded as vectors of zero or one element.
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder.pure_option(None::<u64>).unwrap();
+    builder.pure_option(Some(42_u64)).unwrap();
+    assert_eq!(
+        vec![
+            CallArg::Pure(vec![0]),
+            CallArg::Pure(bcs::to_bytes(&vec![42_u64]).unwrap()),
+        ],
+        builder.finish().inputs
+    );
+
+    for value in [None, Some(42_u64)] {
+        let pt = {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            let args = vec![
+                builder.pure_option(value).unwrap(),
+                builder.pure(value.is_some()).unwrap(),
+            ];
+            builder.programmable_move_call(
+                package.0,
+                Identifier::new("entry_point_vector").unwrap(),
+                Identifier::new("option_u64").unwrap(),
+                vec![],
+                args,
+            );
+            builder.finish()
+        };
+        let effects = execute_programmable_transaction(&authority, &gas, &sender, &sender_key, pt)
+            .await
+            .unwrap();
+        assert!(
+            matches!(effects.status(), ExecutionStatus::Success { .. }),
+            "{:?}",
+            effects.status()
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_make_move_vec() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let gas = ObjectID::random();
+    let authority = init_state_with_ids(vec![(sender, gas)]).await;
+
+    let package = build_and_publish_test_package(
+        &authority,
+        &sender,
+        &sender_key,
+        &gas,
+        "entry_point_vector",
+        /* with_unpublished_deps */ false,
+    )
+    .await;
+
+    // A vector of pure values needs its element type.
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let elements = vec![builder.pure(1_u64).unwrap(), builder.pure(2_u64).unwrap()];
+        let values = builder.make_move_vec(Some(TypeTag::U64), elements);
+        builder.programmable_move_call(
+            package.0,
+            Identifier::new("entry_point_vector").unwrap(),
+            Identifier::new("prim_vec_len").unwrap(),
+            vec![],
+            vec![values],
+        );
+        builder.finish()
+    };
+    let effects = execute_programmable_transaction(&authority, &gas, &sender, &sender_key, pt)
+        .await
+        .unwrap();
+    assert!(
+        matches!(effects.status(), ExecutionStatus::Success { .. }),
+        "{:?}",
+        effects.status()
+    );
+
+    // A vector<Coin<SUI>> built from two coins split off the gas, joined back into it.
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let amount = builder.pure(100_u64).unwrap();
+        let coins = vec![
+            builder.command(Command::SplitCoin(Argument::GasCoin, amount)),
+            builder.command(Command::SplitCoin(Argument::GasCoin, amount)),
+        ];
+        let coins = builder.make_move_vec(
+            Some(TypeTag::from_str("0x2::coin::Coin<0x2::sui::SUI>").unwrap()),
+            coins,
+        );
+        builder.programmable_move_call(
+            SUI_FRAMEWORK_OBJECT_ID,
+            Identifier::new("pay").unwrap(),
+            Identifier::new("join_vec").unwrap(),
+            vec![TypeTag::from_str("0x2::sui::SUI").unwrap()],
+            vec![Argument::GasCoin, coins],
+        );
+        builder.finish()
+    };
+    let effects = execute_programmable_transaction(&authority, &gas, &sender, &sender_key, pt)
+        .await
+        .unwrap();
+    assert!(
+        matches!(effects.status(), ExecutionStatus::Success { .. }),
+        "{:?}",
+        effects.status()
+    );
+    assert!(effects.created().is_empty());
+}
+
 #[tokio::test]
 #[cfg_attr(msim, ignore)]
 async fn test_entry_point_vector() {
@@ -378,6 +378,65 @@ async fn test_pay_all_sui_success_multiple_input_coins() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_pay_all_sui_except_keeps_reserve() -> anyhow::Result<()> {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let object_id = ObjectID::random();
+    let coin_obj = Object::with_id_owner_gas_for_testing(object_id, sender, 10_000_000);
+    let recipient = dbg_addr(2);
+    let (gas_budget, gas_price) = (2000, 1000);
+
+    // The reserve and the gas budget at the gas price together can't exceed the gas coin.
+    let mut builder = ProgrammableTransactionBuilder::new();
+    let err = builder
+        .pay_all_sui_except(
+            sender, recipient, 9_000_000, 10_000_000, gas_budget, gas_price,
+        )
+        .unwrap_err();
+    assert_eq!(
+        Some(&UserInputError::GasBalanceTooLow {
+            gas_balance: 10_000_000,
+            needed_gas_amount: 11_000_000,
+        }),
+        err.downcast_ref::<UserInputError>()
+    );
+
+    let authority_state = init_state().await;
+    let coin_ref = coin_obj.compute_object_reference();
+    authority_state.insert_genesis_object(coin_obj).await;
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder.pay_all_sui_except(
+        sender, recipient, 1_000_000, 10_000_000, gas_budget, gas_price,
+    )?;
+    let pt = builder.finish();
+    let data = TransactionData::new_programmable(sender, vec![coin_ref], pt, gas_budget, gas_price);
+    let tx = to_sender_signed_transaction(data, &sender_key);
+    let (_, effects) = send_and_confirm_transaction(&authority_state, tx).await?;
+    let effects = effects.into_data();
+    assert_eq!(*effects.status(), ExecutionStatus::Success);
+
+    // The sender keeps the reserve in a new coin.
+    assert_eq!(effects.created().len(), 1);
+    assert_eq!(effects.created()[0].1, sender);
+    let reserved = authority_state
+        .get_object(&effects.created()[0].0 .0)
+        .await?
+        .unwrap();
+    assert_eq!(GasCoin::try_from(&reserved)?.value(), 1_000_000);
+
+    // The recipient gets the rest, after gas is paid.
+    assert_eq!(effects.mutated()[0].0 .0, object_id);
+    assert_eq!(effects.mutated()[0].1, recipient);
+    let gas_used = effects.gas_cost_summary().gas_used();
+    let gas_object = authority_state.get_object(&object_id).await?.unwrap();
+    assert_eq!(
+        GasCoin::try_from(&gas_object)?.value(),
+        10_000_000 - 1_000_000 - gas_used
+    );
+    Ok(())
+}
+
 struct PaySuiTransactionExecutionResult {
     pub authority_state: Arc<AuthorityState>,
     pub txn_result: Result<SignedTransactionEffects, SuiError>,
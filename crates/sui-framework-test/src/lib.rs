@@ -7,7 +7,7 @@ mod test {
     use std::path::{Path, PathBuf};
     use sui_framework::build_move_package;
     use sui_framework_build::compiled_package::BuildConfig;
-    use sui_move::unit_test::run_move_unit_tests;
+    use sui_move::unit_test::{check_coverage, run_move_unit_tests};
 
     #[test]
     #[cfg_attr(msim, ignore)]
@@ -50,6 +50,36 @@ mod test {
         });
     }
 
+    #[test]
+    #[cfg_attr(msim, ignore)]
+    fn run_min_coverage_check() {
+        let path = {
+            let mut buf = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+            buf.extend(["data", "partial_coverage"]);
+            buf
+        };
+        let mut config = BuildConfig::new_for_testing();
+        config.config.dev_mode = true;
+        config.config.test_mode = true;
+        let move_config = config.config.clone();
+        build_move_package(&path, config).unwrap();
+
+        assert_eq!(
+            run_move_unit_tests(&path, move_config.clone(), None, true).unwrap(),
+            UnitTestResult::Success
+        );
+
+        // Only part of the package is tested, so the gate fails for a full coverage requirement.
+        assert_eq!(
+            check_coverage(&path, move_config.clone(), 100.0).unwrap(),
+            UnitTestResult::Failure
+        );
+        assert_eq!(
+            check_coverage(&path, move_config, 10.0).unwrap(),
+            UnitTestResult::Success
+        );
+    }
+
     fn check_move_unit_tests(path: &Path) {
         let mut config = BuildConfig::new_for_testing();
         // Make sure to verify tests
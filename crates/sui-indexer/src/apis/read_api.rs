@@ -159,6 +159,10 @@ impl<S: IndexerStore> ReadApi<S> {
                     is_descending,
                 )
             }
+            Some(TransactionFilter::FromOrToAddress(_)) => Err(IndexerError::NotImplementedError(
+                "FromOrToAddress filter has not been implemented on indexer for queryTransactions"
+                    .to_string(),
+            )),
         }?;
 
         // digests here are of size (limit + 1), where the last one is the cursor for the next page
@@ -257,6 +261,18 @@ where
             .await;
     }
 
+    async fn get_objects_by_type(
+        &self,
+        struct_type: String,
+        options: Option<SuiObjectDataOptions>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectsPage> {
+        self.fullnode
+            .get_objects_by_type(struct_type, options, cursor, limit)
+            .await
+    }
+
     async fn get_dynamic_fields(
         &self,
         parent_object_id: ObjectID,
@@ -30,6 +30,8 @@ impl TryFrom<SuiTransactionResponse> for SuiTransactionFullResponse {
             digest,
             transaction,
             effects,
+            raw_effects: _,
+            raw_transaction: _,
             events,
             object_changes,
             balance_changes,
@@ -109,6 +111,8 @@ impl From<SuiTransactionFullResponse> for SuiTransactionResponse {
             digest,
             transaction: Some(transaction),
             effects: Some(effects),
+            raw_effects: None,
+            raw_transaction: None,
             events: Some(events),
             object_changes,
             balance_changes,
@@ -6,10 +6,12 @@ use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use serde_with::DisplayFromStr;
 
+use sui_types::base_types::SuiAddress;
 use sui_types::object::Owner;
 
 #[serde_as]
-#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+// Field order matters: balance changes are ordered by owner, then coin type, then amount.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BalanceChange {
     /// Owner of the balance change
@@ -21,3 +23,24 @@ pub struct BalanceChange {
     /// negative amount means spending coin value and positive means receiving coin value.
     pub amount: i128,
 }
+
+impl BalanceChange {
+    /// Returns the owner address if the balance change is owned by an address.
+    pub fn address_owner(&self) -> Option<SuiAddress> {
+        if let Owner::AddressOwner(address) = self.owner {
+            Some(address)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_shared(&self) -> bool {
+        matches!(self.owner, Owner::Shared { .. })
+    }
+}
+
+/// Sorts balance changes into a stable order (by owner, then coin type), so responses can be
+/// compared across nodes and runs.
+pub fn sort_balance_changes(balance_changes: &mut [BalanceChange]) {
+    balance_changes.sort();
+}
@@ -7,7 +7,7 @@ use serde_with::DisplayFromStr;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
-use sui_types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
+use sui_types::base_types::{ObjectDigest, ObjectID, ObjectRef, SequenceNumber, SuiAddress};
 use sui_types::object::Owner;
 
 /// ObjectChange are derived from the object mutations in the TransactionEffect to provide richer object information.
@@ -80,3 +80,97 @@ pub enum ObjectChange {
         digest: ObjectDigest,
     },
 }
+
+impl ObjectChange {
+    /// Reference and modules of a published package.
+    pub fn as_published(&self) -> Option<(ObjectRef, &[String])> {
+        match self {
+            ObjectChange::Published {
+                package_id,
+                version,
+                digest,
+                modules,
+            } => Some(((*package_id, *version, *digest), modules)),
+            _ => None,
+        }
+    }
+
+    /// Recipient, type and reference of a transferred object.
+    pub fn as_transferred(&self) -> Option<(&Owner, &StructTag, ObjectRef)> {
+        match self {
+            ObjectChange::Transferred {
+                recipient,
+                object_type,
+                object_id,
+                version,
+                digest,
+                ..
+            } => Some((recipient, object_type, (*object_id, *version, *digest))),
+            _ => None,
+        }
+    }
+
+    /// Owner, type and reference of a mutated object.
+    pub fn as_mutated(&self) -> Option<(&Owner, &StructTag, ObjectRef)> {
+        match self {
+            ObjectChange::Mutated {
+                owner,
+                object_type,
+                object_id,
+                version,
+                digest,
+                ..
+            } => Some((owner, object_type, (*object_id, *version, *digest))),
+            _ => None,
+        }
+    }
+
+    /// Type, id and version of a deleted object.
+    pub fn as_deleted(&self) -> Option<(&StructTag, ObjectID, SequenceNumber)> {
+        match self {
+            ObjectChange::Deleted {
+                object_type,
+                object_id,
+                version,
+                ..
+            } => Some((object_type, *object_id, *version)),
+            _ => None,
+        }
+    }
+
+    /// Type, id and version of a wrapped object.
+    pub fn as_wrapped(&self) -> Option<(&StructTag, ObjectID, SequenceNumber)> {
+        match self {
+            ObjectChange::Wrapped {
+                object_type,
+                object_id,
+                version,
+                ..
+            } => Some((object_type, *object_id, *version)),
+            _ => None,
+        }
+    }
+
+    /// Owner, type and reference of a created object.
+    pub fn as_created(&self) -> Option<(&Owner, &StructTag, ObjectRef)> {
+        match self {
+            ObjectChange::Created {
+                owner,
+                object_type,
+                object_id,
+                version,
+                digest,
+                ..
+            } => Some((owner, object_type, (*object_id, *version, *digest))),
+            _ => None,
+        }
+    }
+}
+
+/// Owner, type and reference of the objects created among `changes`.
+pub fn filter_created(changes: &[ObjectChange]) -> Vec<(&Owner, &StructTag, ObjectRef)> {
+    changes
+        .iter()
+        .filter_map(ObjectChange::as_created)
+        .collect()
+}
@@ -20,7 +20,7 @@ use move_binary_format::normalized::{
 };
 use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::StructTag;
-use move_core_types::value::{MoveStruct, MoveValue};
+use move_core_types::value::{MoveStruct, MoveTypeLayout, MoveValue};
 
 pub type SuiMoveTypeParameterIndex = u16;
 
@@ -211,6 +211,29 @@ impl From<NormalizedField> for SuiMoveNormalizedField {
     }
 }
 
+impl SuiMoveNormalizedType {
+    /// Layout of the values of this type that can be passed as pure arguments: booleans,
+    /// integers, addresses and vectors of those.
+    pub(crate) fn pure_layout(&self) -> Option<MoveTypeLayout> {
+        Some(match self {
+            Self::Bool => MoveTypeLayout::Bool,
+            Self::U8 => MoveTypeLayout::U8,
+            Self::U16 => MoveTypeLayout::U16,
+            Self::U32 => MoveTypeLayout::U32,
+            Self::U64 => MoveTypeLayout::U64,
+            Self::U128 => MoveTypeLayout::U128,
+            Self::U256 => MoveTypeLayout::U256,
+            Self::Address => MoveTypeLayout::Address,
+            Self::Vector(inner) => MoveTypeLayout::Vector(Box::new(inner.pure_layout()?)),
+            Self::Signer
+            | Self::Struct { .. }
+            | Self::TypeParameter(_)
+            | Self::Reference(_)
+            | Self::MutableReference(_) => return None,
+        })
+    }
+}
+
 impl From<NormalizedType> for SuiMoveNormalizedType {
     fn from(type_: NormalizedType) -> Self {
         match type_ {
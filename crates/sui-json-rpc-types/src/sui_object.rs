@@ -96,6 +96,22 @@ impl SuiObjectData {
             None => false,
         }
     }
+
+    /// Decode the object as a SUI coin, from its BCS bytes if they were requested, or else from
+    /// its parsed content. Fails if the object is not a SUI coin.
+    pub fn as_gas_coin(&self) -> anyhow::Result<GasCoin> {
+        match &self.bcs {
+            Some(SuiRawData::MoveObject(o)) if GasCoin::is_gas_coin(&o.type_) => {
+                Ok(bcs::from_bytes(&o.bcs_bytes)?)
+            }
+            Some(_) => Err(anyhow!(
+                "Object {} is not a gas coin, its type is {:?}",
+                self.object_id,
+                self.type_
+            )),
+            None => GasCoin::try_from(self),
+        }
+    }
 }
 
 impl Display for SuiObjectData {
@@ -269,6 +285,11 @@ impl SuiObjectDataOptions {
         self.show_previous_transaction = true;
         self
     }
+
+    pub fn with_storage_rebate(mut self) -> Self {
+        self.show_storage_rebate = true;
+        self
+    }
 }
 
 impl TryFrom<(ObjectRead, SuiObjectDataOptions)> for SuiObjectResponse {
@@ -1,14 +1,19 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{self, Display, Formatter, Write};
 
 use enum_dispatch::enum_dispatch;
 use fastcrypto::encoding::Base64;
 use move_bytecode_utils::module_cache::GetModule;
-use move_core_types::language_storage::TypeTag;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use move_core_types::value::{MoveTypeLayout, MoveValue};
 use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use serde_with::{serde_as, DisplayFromStr};
 
 use sui_json::SuiJsonValue;
@@ -18,6 +23,8 @@ use sui_types::base_types::{
 use sui_types::digests::TransactionEventsDigest;
 use sui_types::error::{ExecutionError, SuiError};
 use sui_types::gas::GasCostSummary;
+use sui_types::gas_coin::{mist_to_sui, GAS};
+use sui_types::governance::{StakedSui, ADD_STAKE_FUN_NAME, ADD_STAKE_MUL_COIN_FUN_NAME};
 use sui_types::messages::{
     Argument, Command, ExecuteTransactionRequestType, ExecutionStatus, GenesisObject,
     InputObjectKind, ProgrammableMoveCall, ProgrammableTransaction, SenderSignedData,
@@ -30,10 +37,12 @@ use sui_types::object::Owner;
 use sui_types::parse_sui_type_tag;
 use sui_types::query::TransactionFilter;
 use sui_types::signature::GenericSignature;
+use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
+use sui_types::SUI_FRAMEWORK_OBJECT_ID;
 
 use crate::balance_changes::BalanceChange;
 use crate::object_changes::ObjectChange;
-use crate::{Page, SuiEvent, SuiMovePackage, SuiObjectRef};
+use crate::{Page, SuiEvent, SuiMoveNormalizedModule, SuiMovePackage, SuiMoveValue, SuiObjectRef};
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, PartialEq, Eq, Copy)]
@@ -105,6 +114,10 @@ pub struct SuiTransactionResponseOptions {
     pub show_object_changes: bool,
     /// Whether to show balance_changes. Default to be False
     pub show_balance_changes: bool,
+    /// Whether to show the bcs-encoded transaction effects. Default to be False
+    pub show_raw_effects: bool,
+    /// Whether to show the bcs-encoded transaction data. Default to be False
+    pub show_raw_input: bool,
 }
 
 impl SuiTransactionResponseOptions {
@@ -119,6 +132,8 @@ impl SuiTransactionResponseOptions {
             show_events: true,
             show_object_changes: true,
             show_balance_changes: true,
+            show_raw_effects: false,
+            show_raw_input: false,
         }
     }
 
@@ -147,6 +162,16 @@ impl SuiTransactionResponseOptions {
         self
     }
 
+    pub fn with_raw_effects(mut self) -> Self {
+        self.show_raw_effects = true;
+        self
+    }
+
+    pub fn with_raw_input(mut self) -> Self {
+        self.show_raw_input = true;
+        self
+    }
+
     /// default to return `WaitForEffectsCert` unless some options require
     /// local execution
     pub fn default_execution_request_type(&self) -> ExecuteTransactionRequestType {
@@ -162,8 +187,13 @@ impl SuiTransactionResponseOptions {
         self.show_balance_changes || self.show_object_changes
     }
 
+    pub fn require_input(&self) -> bool {
+        self.show_input || self.show_raw_input
+    }
+
     pub fn require_effects(&self) -> bool {
         self.show_effects
+            || self.show_raw_effects
             || self.show_events
             || self.show_balance_changes
             || self.show_object_changes
@@ -174,6 +204,7 @@ impl SuiTransactionResponseOptions {
     }
 }
 
+#[serde_as]
 #[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, Default)]
 #[serde(rename_all = "camelCase", rename = "TransactionResponse")]
 pub struct SuiTransactionResponse {
@@ -183,6 +214,16 @@ pub struct SuiTransactionResponse {
     pub transaction: Option<SuiTransaction>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub effects: Option<SuiTransactionEffects>,
+    /// BCS encoded TransactionEffects, so that they can be verified independently
+    #[serde_as(as = "Option<Base64>")]
+    #[schemars(with = "Option<Base64>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_effects: Option<Vec<u8>>,
+    /// BCS encoded TransactionData, exactly as it was submitted
+    #[serde_as(as = "Option<Base64>")]
+    #[schemars(with = "Option<Base64>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_transaction: Option<Vec<u8>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub events: Option<SuiTransactionEvents>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -208,6 +249,258 @@ impl SuiTransactionResponse {
             ..Default::default()
         }
     }
+
+    /// Decode the bcs contents of all events of type `event_type` into `T`, skipping events of
+    /// other types. Fails if the response was fetched without events.
+    pub fn events_of_type<T: DeserializeOwned>(
+        &self,
+        event_type: &StructTag,
+    ) -> Result<Vec<T>, anyhow::Error> {
+        let events = self
+            .events
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Events are not included in the response"))?;
+        events
+            .data
+            .iter()
+            .filter(|event| &event.type_ == event_type)
+            .map(|event| Ok(bcs::from_bytes(&event.bcs)?))
+            .collect()
+    }
+
+    /// Addresses touched by the transaction: its sender and gas owner, the address owners of
+    /// the objects it created, mutated or unwrapped, e.g. the recipients of a transfer, and the
+    /// owners of its balance changes. Only the parts included in the response are considered.
+    pub fn affected_addresses(&self) -> BTreeSet<SuiAddress> {
+        let mut addresses = BTreeSet::new();
+        if let Some(transaction) = &self.transaction {
+            addresses.insert(*transaction.data.sender());
+            addresses.insert(transaction.data.gas_data().owner);
+        }
+        if let Some(effects) = &self.effects {
+            let objects = effects
+                .created()
+                .iter()
+                .chain(effects.mutated())
+                .chain(effects.unwrapped());
+            addresses.extend(objects.filter_map(|obj| match obj.owner {
+                Owner::AddressOwner(address) => Some(address),
+                _ => None,
+            }));
+        }
+        if let Some(balance_changes) = &self.balance_changes {
+            addresses.extend(
+                balance_changes
+                    .iter()
+                    .filter_map(BalanceChange::address_owner),
+            );
+        }
+        addresses
+    }
+
+    /// Id of the StakedSui created by a stake transaction, None if it created none or the
+    /// response was fetched without object changes.
+    pub fn extract_staked_sui_id(&self) -> Option<ObjectID> {
+        self.object_changes
+            .as_ref()?
+            .iter()
+            .filter_map(ObjectChange::as_created)
+            .find(|(_, object_type, _)| StakedSui::is_staked_sui(object_type))
+            .map(|(_, _, (id, ..))| id)
+    }
+
+    /// Id of the package published by the transaction, None if it published none or the
+    /// response was fetched without object changes. A transaction publishes at most one
+    /// package, so finding several means the response is malformed and is an error.
+    pub fn published_package_id(&self) -> Result<Option<ObjectID>, anyhow::Error> {
+        let Some(object_changes) = &self.object_changes else {
+            return Ok(None);
+        };
+        let mut packages = object_changes
+            .iter()
+            .filter_map(ObjectChange::as_published)
+            .map(|((id, ..), _)| id);
+        let package = packages.next();
+        if packages.next().is_some() {
+            anyhow::bail!(
+                "Transaction {} published more than one package",
+                self.digest
+            );
+        }
+        Ok(package)
+    }
+
+    /// One line description of the transaction for display, e.g. `Transfer 0.05 SUI to 0x..`.
+    /// Publishes and Move calls are described from the input of the transaction, stakes and
+    /// transfers from its SUI balance changes, net of gas, so the response should be fetched with
+    /// its input, effects and balance changes.
+    pub fn summarize(&self) -> String {
+        let Some(transaction) = &self.transaction else {
+            return format!("Transaction {}", self.digest);
+        };
+        let pt = match transaction.data.transaction() {
+            SuiTransactionKind::ProgrammableTransaction(pt) => pt,
+            SuiTransactionKind::ChangeEpoch(change) => {
+                return format!("Change to epoch {}", change.epoch)
+            }
+            SuiTransactionKind::Genesis(_) => return "Genesis".to_string(),
+            SuiTransactionKind::ConsensusCommitPrologue(_) => {
+                return "Consensus commit prologue".to_string()
+            }
+        };
+        if pt
+            .commands
+            .iter()
+            .any(|command| matches!(command, SuiCommand::Publish(_)))
+        {
+            return "Publish a package".to_string();
+        }
+
+        let sender = *transaction.data.sender();
+        let sui_changes = self
+            .balance_changes
+            .iter()
+            .flatten()
+            .filter(|change| change.coin_type == GAS::type_tag())
+            .filter_map(|change| Some((change.address_owner()?, change.amount)));
+        let gas = self.effects.as_ref().map_or(0, |effects| {
+            let gas_used = effects.gas_used();
+            gas_used.computation_cost as i128 + gas_used.storage_cost as i128
+                - gas_used.storage_rebate as i128
+        });
+        let spent = sui_changes
+            .clone()
+            .filter(|(owner, _)| *owner == sender)
+            .map(|(_, amount)| -amount)
+            .sum::<i128>()
+            - gas;
+        let received = sui_changes
+            .filter(|(owner, amount)| *owner != sender && *amount > 0)
+            .collect::<Vec<_>>();
+        let sui = |amount: i128| mist_to_sui(amount.try_into().unwrap_or_default());
+
+        let mut move_calls = pt.commands.iter().filter_map(|command| match command {
+            SuiCommand::MoveCall(call) => Some(call),
+            _ => None,
+        });
+        if let Some(call) = move_calls.next() {
+            let is_stake = call.package == SUI_FRAMEWORK_OBJECT_ID
+                && call.module == SUI_SYSTEM_MODULE_NAME.as_str()
+                && [ADD_STAKE_FUN_NAME, ADD_STAKE_MUL_COIN_FUN_NAME]
+                    .iter()
+                    .any(|function| call.function == function.as_str());
+            return if is_stake {
+                format!("Stake {} SUI", sui(spent))
+            } else {
+                format!("Call {}::{}::{}", call.package, call.module, call.function)
+            };
+        }
+        match received.as_slice() {
+            [] => format!("Transaction with {} commands", pt.commands.len()),
+            [(recipient, amount)] => format!("Transfer {} SUI to {recipient}", sui(*amount)),
+            recipients => format!(
+                "Transfer {} SUI to {} recipients",
+                sui(recipients.iter().map(|(_, amount)| amount).sum()),
+                recipients.len()
+            ),
+        }
+    }
+
+    /// Decode the pure arguments of the move calls to the package whose normalized modules are
+    /// `package_abi`, using the types of the parameters they are passed to. Object arguments,
+    /// results of other commands and calls to other packages are skipped. Fails if the response
+    /// was fetched without its input.
+    pub fn decode_move_call_args(
+        &self,
+        package_abi: &BTreeMap<String, SuiMoveNormalizedModule>,
+    ) -> Result<Vec<DecodedArg>, anyhow::Error> {
+        let transaction = self
+            .transaction
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Transaction input is not included in the response"))?;
+        let SuiTransactionKind::ProgrammableTransaction(pt) = transaction.data.transaction() else {
+            return Ok(vec![]);
+        };
+        let mut decoded = vec![];
+        for (command, call) in pt.commands.iter().enumerate() {
+            let SuiCommand::MoveCall(call) = call else {
+                continue;
+            };
+            let Some(function) = package_abi
+                .get(&call.module)
+                .filter(|module| {
+                    AccountAddress::from_hex_literal(&module.address)
+                        .map_or(false, |address| ObjectID::from(address) == call.package)
+                })
+                .and_then(|module| module.exposed_functions.get(&call.function))
+            else {
+                continue;
+            };
+            for (parameter, (argument, type_)) in
+                call.arguments.iter().zip(&function.parameters).enumerate()
+            {
+                let (SuiArgument::Input(i), Some(layout)) = (argument, type_.pure_layout()) else {
+                    continue;
+                };
+                let input = pt.inputs.get(*i as usize).ok_or_else(|| {
+                    anyhow::anyhow!("Input {i} of move call {command} does not exist")
+                })?;
+                let value = decode_pure_input(input, &layout)?;
+                decoded.push(DecodedArg {
+                    command,
+                    module: call.module.clone(),
+                    function: call.function.clone(),
+                    parameter,
+                    value: SuiMoveValue::from(value).to_json_value(),
+                });
+            }
+        }
+        Ok(decoded)
+    }
+}
+
+/// A pure argument of a move call, decoded with the type of the parameter it is passed to.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DecodedArg {
+    /// Index of the move call in the commands of the transaction.
+    pub command: usize,
+    pub module: String,
+    pub function: String,
+    /// Index of the function parameter the argument is passed to.
+    pub parameter: usize,
+    pub value: Value,
+}
+
+/// Pure inputs are shown with a guess of their type, the original bytes are recovered from the
+/// guess by re-encoding it: the candidate encodings must be guessed the same way and be a valid
+/// value of `layout`.
+fn decode_pure_input(
+    input: &SuiJsonValue,
+    layout: &MoveTypeLayout,
+) -> Result<MoveValue, anyhow::Error> {
+    let byte_array = MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8));
+    let mut candidates = vec![
+        input.to_bcs_bytes(layout).ok(),
+        // Values that could not be guessed are shown as raw bytes.
+        input
+            .to_bcs_bytes(&byte_array)
+            .ok()
+            .and_then(|bytes| bcs::from_bytes::<Vec<u8>>(&bytes).ok()),
+    ];
+    if let Value::String(s) = input.to_json_value() {
+        candidates.push(bcs::to_bytes(&s).ok());
+    }
+    candidates
+        .into_iter()
+        .flatten()
+        .filter(|bytes| SuiJsonValue::from_bcs_bytes(bytes).map_or(false, |guess| &guess == input))
+        .find_map(|bytes| MoveValue::simple_deserialize(&bytes, layout).ok())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Cannot decode {} as a value of type {layout}",
+                input.to_json_value()
+            )
+        })
 }
 
 /// We are specifically ignoring events for now until events become more stable.
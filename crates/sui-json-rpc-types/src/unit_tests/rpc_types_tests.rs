@@ -10,12 +10,17 @@ use move_core_types::language_storage::{StructTag, TypeTag};
 use move_core_types::value::{MoveStruct, MoveValue};
 
 use sui_types::base_types::SequenceNumber;
-use sui_types::base_types::{ObjectID, SuiAddress};
-use sui_types::gas_coin::GasCoin;
-use sui_types::object::MoveObject;
+use sui_types::base_types::{MoveObjectType, ObjectID, SuiAddress, TransactionDigest};
+use sui_types::coin::Coin;
+use sui_types::digests::ObjectDigest;
+use sui_types::gas_coin::{GasCoin, GAS};
+use sui_types::object::{MoveObject, Object, Owner};
 use sui_types::{MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS};
 
-use crate::{SuiMoveStruct, SuiMoveValue};
+use crate::{
+    BalanceChange, ObjectChange, SuiMoveStruct, SuiMoveValue, SuiObjectData, SuiObjectDataOptions,
+    SuiTransactionResponse,
+};
 
 #[test]
 fn test_move_value_to_sui_coin() {
@@ -143,3 +148,127 @@ fn test_serde() {
         )
     }
 }
+
+#[test]
+fn test_balance_change_owner() {
+    let address = SuiAddress::random_for_testing_only();
+    let balance_change = |owner| BalanceChange {
+        owner,
+        coin_type: GAS::type_tag(),
+        amount: 100,
+    };
+
+    let change = balance_change(Owner::AddressOwner(address));
+    assert_eq!(Some(address), change.address_owner());
+    assert!(!change.is_shared());
+
+    let change = balance_change(Owner::ObjectOwner(address));
+    assert_eq!(None, change.address_owner());
+    assert!(!change.is_shared());
+
+    let change = balance_change(Owner::Shared {
+        initial_shared_version: SequenceNumber::from_u64(1),
+    });
+    assert_eq!(None, change.address_owner());
+    assert!(change.is_shared());
+
+    let change = balance_change(Owner::Immutable);
+    assert_eq!(None, change.address_owner());
+    assert!(!change.is_shared());
+}
+
+#[test]
+fn test_sort_balance_changes() {
+    let addresses = [
+        SuiAddress::random_for_testing_only(),
+        SuiAddress::random_for_testing_only(),
+    ];
+    let coin_types = [
+        GAS::type_tag(),
+        TypeTag::from_str("0x2::coin_a::COIN_A").unwrap(),
+    ];
+    let changes = addresses
+        .iter()
+        .flat_map(|address| {
+            coin_types.iter().map(|coin_type| BalanceChange {
+                owner: Owner::AddressOwner(*address),
+                coin_type: coin_type.clone(),
+                amount: -100,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut reversed = changes.iter().rev().cloned().collect::<Vec<_>>();
+    let mut rotated = changes.clone();
+    rotated.rotate_left(1);
+    crate::sort_balance_changes(&mut reversed);
+    crate::sort_balance_changes(&mut rotated);
+
+    assert_eq!(reversed, rotated);
+    for pair in reversed.windows(2) {
+        assert!((&pair[0].owner, &pair[0].coin_type) < (&pair[1].owner, &pair[1].coin_type));
+    }
+}
+
+#[test]
+fn test_as_gas_coin() {
+    let owner = SuiAddress::random_for_testing_only();
+    let gas = Object::with_id_owner_gas_for_testing(ObjectID::random(), owner, 12345);
+    for options in [
+        SuiObjectDataOptions::bcs_lossless(),
+        SuiObjectDataOptions::full_content(),
+    ] {
+        let data = SuiObjectData::try_from((
+            gas.compute_object_reference(),
+            gas.clone(),
+            Some(GasCoin::layout()),
+            options,
+        ))
+        .unwrap();
+        let coin = data.as_gas_coin().unwrap();
+        assert_eq!(12345, coin.value());
+        assert_eq!(gas.id(), *coin.id());
+    }
+
+    // A coin of another currency is not a gas coin.
+    let coin_type = TypeTag::from_str("0x2::coin_a::COIN_A").unwrap();
+    let coin = Object::new_move(
+        MoveObject::new_coin(
+            MoveObjectType::Coin(coin_type.clone()),
+            SequenceNumber::new(),
+            ObjectID::random(),
+            12345,
+        ),
+        Owner::AddressOwner(owner),
+        TransactionDigest::genesis(),
+    );
+    let data = SuiObjectData::try_from((
+        coin.compute_object_reference(),
+        coin,
+        Some(Coin::layout(coin_type)),
+        SuiObjectDataOptions::bcs_lossless(),
+    ))
+    .unwrap();
+    assert!(data.as_gas_coin().is_err());
+}
+
+#[test]
+fn test_published_package_id() {
+    let published = |package_id| ObjectChange::Published {
+        package_id,
+        version: SequenceNumber::from_u64(1),
+        digest: ObjectDigest::random(),
+        modules: vec!["m".to_string()],
+    };
+    let package_id = ObjectID::random();
+    let mut response = SuiTransactionResponse::new(TransactionDigest::random());
+
+    // Without object changes, the package can't be found.
+    assert_eq!(None, response.published_package_id().unwrap());
+
+    response.object_changes = Some(vec![published(package_id)]);
+    assert_eq!(Some(package_id), response.published_package_id().unwrap());
+
+    response.object_changes = Some(vec![published(package_id), published(ObjectID::random())]);
+    assert!(response.published_package_id().is_err());
+}
@@ -37,6 +37,20 @@ pub trait ReadApi {
         at_checkpoint: Option<CheckpointId>,
     ) -> RpcResult<ObjectsPage>;
 
+    /// Return the list of address owned objects of a Move struct type, across all owners.
+    #[method(name = "getObjectsByType")]
+    async fn get_objects_by_type(
+        &self,
+        /// the Move struct type of the objects, e.g. `0x2::staking_pool::StakedSui`
+        struct_type: String,
+        /// options for specifying the content to be returned
+        options: Option<SuiObjectDataOptions>,
+        /// Optional paging cursor
+        cursor: Option<ObjectID>,
+        /// Max number of items returned per page, default to [MAX_GET_OWNED_OBJECT_SIZE] if not specified.
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectsPage>;
+
     /// Return the list of dynamic field objects owned by an object.
     #[method(name = "getDynamicFields")]
     async fn get_dynamic_fields(
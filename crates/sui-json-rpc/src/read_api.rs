@@ -48,6 +48,7 @@ use sui_types::messages::{
 use sui_types::messages_checkpoint::{CheckpointSequenceNumber, CheckpointTimestamp};
 use sui_types::move_package::normalize_modules;
 use sui_types::object::{Data, Object, ObjectRead, PastObjectRead};
+use sui_types::parse_sui_struct_tag;
 
 use crate::api::ReadApiServer;
 use crate::api::QUERY_MAX_RESULT_LIMIT;
@@ -156,6 +157,43 @@ impl ReadApiServer for ReadApi {
         })
     }
 
+    async fn get_objects_by_type(
+        &self,
+        struct_type: String,
+        options: Option<SuiObjectDataOptions>,
+        // exclusive cursor if `Some`, otherwise start from the beginning
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectsPage> {
+        let type_ = parse_sui_struct_tag(&struct_type)?;
+        let limit = cap_page_objects_limit(limit)?;
+        let options = options.unwrap_or_default();
+
+        let mut objects = self
+            .state
+            .get_objects_by_type(type_, cursor, limit + 1)
+            .map_err(|e| anyhow!("{e}"))?;
+
+        let has_next_page = objects.len() > limit;
+        objects.truncate(limit);
+        let next_cursor = objects
+            .last()
+            .cloned()
+            .map_or(cursor, |o_info| Some(o_info.object_id));
+
+        let data = objects.into_iter().try_fold(vec![], |mut acc, o_info| {
+            let o_resp = SuiObjectResponse::try_from((o_info, options.clone()))?;
+            acc.push(o_resp);
+            Ok::<Vec<SuiObjectResponse>, Error>(acc)
+        })?;
+
+        Ok(Page {
+            data,
+            next_cursor,
+            has_next_page,
+        })
+    }
+
     async fn get_dynamic_fields(
         &self,
         parent_object_id: ObjectID,
@@ -361,7 +399,7 @@ impl ReadApiServer for ReadApi {
         let mut temp_response = IntermediateTransactionResponse::new(digest);
 
         // the input is needed for object_changes to retrieve the sender address.
-        if opts.show_input || opts.show_object_changes {
+        if opts.require_input() || opts.show_object_changes {
             temp_response.transaction =
                 Some(self.state.get_executed_transaction(digest).await.tap_err(
                     |err| debug!(tx_digest=?digest, "Failed to get transaction: {:?}", err),
@@ -473,7 +511,7 @@ impl ReadApiServer for ReadApi {
             return Err(anyhow!("The list of digests in the input contain duplicates").into());
         }
 
-        if opts.show_input {
+        if opts.require_input() {
             let transactions = self
                 .state
                 .multi_get_executed_transactions(&digests)
@@ -490,7 +528,7 @@ impl ReadApiServer for ReadApi {
         }
 
         // Fetch effects when `show_events` is true because events relies on effects
-        if opts.show_effects || opts.show_events {
+        if opts.show_effects || opts.show_raw_effects || opts.show_events {
             let effects_list = self
                 .state
                 .multi_get_executed_effects(&digests)
@@ -1066,6 +1104,19 @@ fn convert_to_response(
     let mut response = SuiTransactionResponse::new(cache.digest);
     response.errors = cache.errors;
 
+    if opts.show_raw_input {
+        if let Some(transaction) = &cache.transaction {
+            match bcs::to_bytes(&transaction.data().intent_message().value) {
+                Ok(raw_transaction) => {
+                    response.raw_transaction = Some(raw_transaction);
+                }
+                Err(e) => {
+                    response.errors.push(e.to_string());
+                }
+            }
+        }
+    }
+
     if opts.show_input && cache.transaction.is_some() {
         match cache.transaction.unwrap().into_message().try_into() {
             Ok(t) => {
@@ -1077,6 +1128,19 @@ fn convert_to_response(
         }
     }
 
+    if opts.show_raw_effects {
+        if let Some(effects) = &cache.effects {
+            match bcs::to_bytes(effects) {
+                Ok(raw_effects) => {
+                    response.raw_effects = Some(raw_effects);
+                }
+                Err(e) => {
+                    response.errors.push(e.to_string());
+                }
+            }
+        }
+    }
+
     if opts.show_effects && cache.effects.is_some() {
         match cache.effects.unwrap().try_into() {
             Ok(effects) => {
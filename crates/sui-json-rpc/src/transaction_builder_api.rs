@@ -339,7 +339,7 @@ impl TransactionBuilderServer for TransactionBuilderApi {
     ) -> RpcResult<TransactionBytes> {
         Ok(TransactionBytes::from_data(
             self.builder
-                .request_add_stake(signer, coins, amount, validator, gas, gas_budget)
+                .request_add_stake(signer, coins, amount, validator, None, gas, gas_budget)
                 .await?,
         )?)
     }
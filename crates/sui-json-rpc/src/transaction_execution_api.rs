@@ -68,7 +68,8 @@ impl TransactionExecutionApi {
             }
             (t, _) => t.unwrap_or_else(|| opts.default_execution_request_type()),
         };
-        let tx_data: TransactionData = bcs::from_bytes(&tx_bytes.to_vec()?)?;
+        let raw_transaction = tx_bytes.to_vec()?;
+        let tx_data: TransactionData = bcs::from_bytes(&raw_transaction)?;
         let sender = tx_data.sender();
 
         let mut sigs = Vec::new();
@@ -125,6 +126,11 @@ impl TransactionExecutionApi {
                 Ok(SuiTransactionResponse {
                     digest,
                     transaction: opts.show_input.then_some(tx),
+                    raw_effects: opts
+                        .show_raw_effects
+                        .then(|| bcs::to_bytes(&effects.effects))
+                        .transpose()?,
+                    raw_transaction: opts.show_raw_input.then_some(raw_transaction),
                     effects: opts.show_effects.then_some(effects.effects.try_into()?),
                     events,
                     object_changes,
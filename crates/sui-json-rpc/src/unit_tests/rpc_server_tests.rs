@@ -5,9 +5,12 @@ use std::path::Path;
 #[cfg(not(msim))]
 use std::str::FromStr;
 
+use jsonrpsee::core::Error as RpcError;
+use jsonrpsee::types::error::{CallError, CALL_EXECUTION_FAILED_CODE};
 use sui_config::SUI_KEYSTORE_FILENAME;
 use sui_framework_build::compiled_package::BuildConfig;
 use sui_json::SuiJsonValue;
+use sui_json_rpc_types::CheckpointId;
 use sui_json_rpc_types::ObjectChange;
 use sui_json_rpc_types::ObjectsPage;
 use sui_json_rpc_types::SuiTransactionResponseQuery;
@@ -22,7 +25,8 @@ use sui_types::balance::Supply;
 use sui_types::base_types::ObjectID;
 use sui_types::base_types::TransactionDigest;
 use sui_types::coin::{TreasuryCap, COIN_MODULE_NAME, LOCKED_COIN_MODULE_NAME};
-use sui_types::gas_coin::GAS;
+use sui_types::error::CHECKPOINT_NOT_FOUND_MSG_PREFIX;
+use sui_types::gas_coin::{GAS, TOTAL_SUPPLY_MIST};
 use sui_types::messages::ExecuteTransactionRequestType;
 use sui_types::query::TransactionFilter;
 use sui_types::utils::to_sender_signed_transaction;
@@ -502,6 +506,11 @@ async fn test_get_total_supply() -> Result<(), anyhow::Error> {
     let result: Supply = http_client.get_total_supply(coin_name.clone()).await?;
     assert_eq!(100000, result.value);
 
+    let result: Supply = http_client
+        .get_total_supply(GAS::type_().to_string())
+        .await?;
+    assert_eq!(TOTAL_SUPPLY_MIST, result.value);
+
     Ok(())
 }
 
@@ -586,6 +595,29 @@ async fn test_get_transaction() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_get_checkpoint_not_found() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let http_client = cluster.rpc_client();
+
+    // Clients tell missing, e.g. pruned, checkpoints from other failures by this error.
+    let error = http_client
+        .get_checkpoint(CheckpointId::SequenceNumber(u64::MAX))
+        .await
+        .unwrap_err();
+    let RpcError::Call(CallError::Custom(error)) = error else {
+        panic!("Expected a call error, got {error:?}");
+    };
+    assert_eq!(CALL_EXECUTION_FAILED_CODE, error.code());
+    assert!(
+        error.message().starts_with(CHECKPOINT_NOT_FOUND_MSG_PREFIX),
+        "{}",
+        error.message()
+    );
+
+    Ok(())
+}
+
 #[sim_test]
 async fn test_get_fullnode_transaction() -> Result<(), anyhow::Error> {
     let mut cluster = TestClusterBuilder::new().build().await.unwrap();
@@ -95,6 +95,17 @@ impl Display for Keystore {
     }
 }
 
+impl Keystore {
+    /// Load the file based keystore at `path`. Unlike [FileBasedKeystore::new], a missing file is
+    /// an error rather than an empty keystore.
+    pub fn load(path: &Path) -> Result<Self, anyhow::Error> {
+        if !path.exists() {
+            return Err(anyhow!("Keystore file {path:?} does not exist"));
+        }
+        Ok(Keystore::File(FileBasedKeystore::new(&path.to_path_buf())?))
+    }
+}
+
 #[derive(Default)]
 pub struct FileBasedKeystore {
     keys: BTreeMap<SuiAddress, SuiKeyPair>,
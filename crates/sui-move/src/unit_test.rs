@@ -2,18 +2,32 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::build;
+use anyhow::{anyhow, bail};
 use clap::Parser;
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use move_binary_format::errors::{Location, VMError};
 use move_cli::base::{
     self,
     test::{self, UnitTestResult},
 };
-use move_package::BuildConfig;
+use move_compiler::{
+    compiled_unit::{CompiledUnit, NamedCompiledModule},
+    diagnostics,
+    unit_test::{plan_builder::construct_test_plan, TestPlan},
+    PASS_CFGIR,
+};
+use move_core_types::identifier::IdentStr;
+use move_coverage::{coverage_map::CoverageMap, summary};
+use move_package::{compilation::build_plan::BuildPlan, BuildConfig};
 use move_unit_test::{extensions::set_extension_hook, UnitTestingConfig};
-use move_vm_runtime::native_extensions::NativeContextExtensions;
+use move_vm_runtime::{move_vm::MoveVM, native_extensions::NativeContextExtensions};
+use move_vm_test_utils::gas_schedule::{Gas, GasStatus};
 use once_cell::sync::Lazy;
 use std::{
     collections::BTreeMap,
+    fmt,
     path::{Path, PathBuf},
+    sync::{Mutex, RwLock, RwLockWriteGuard},
 };
 use sui_core::authority::TemporaryStore;
 use sui_cost_tables::bytecode_tables::INITIAL_COST_SCHEDULE;
@@ -31,6 +45,15 @@ const MAX_UNIT_TEST_INSTRUCTIONS: u64 = 100_000;
 pub struct Test {
     #[clap(flatten)]
     pub test: test::Test,
+    /// Fail if the total coverage of the package is below this percentage, requires the
+    /// coverage to be computed
+    #[clap(long = "min-coverage")]
+    pub min_coverage: Option<f64>,
+    /// Print the gas used by each test function. The gas is measured by compiling and running
+    /// the tests a second time after the test run, which roughly doubles the time taken, and
+    /// may differ from the runner's metering if a test behaves differently across runs
+    #[clap(long = "report-gas")]
+    pub report_gas: bool,
 }
 
 impl Test {
@@ -40,6 +63,9 @@ impl Test {
         build_config: BuildConfig,
         unit_test_config: UnitTestingConfig,
     ) -> anyhow::Result<UnitTestResult> {
+        if self.min_coverage.is_some() && !self.test.compute_coverage {
+            bail!("--min-coverage requires the coverage to be computed, pass --coverage");
+        }
         // find manifest file directory from a given path or (if missing) from current dir
         let rerooted_path = base::reroot_path(path)?;
         // pre build for Sui-specific verifications
@@ -56,18 +82,108 @@ impl Test {
             dump_bytecode_as_base64,
             generate_struct_layouts,
         )?;
-        run_move_unit_tests(
-            &rerooted_path,
-            build_config,
-            Some(unit_test_config),
-            self.test.compute_coverage,
-        )
+        let gas_limit = unit_test_config.gas_limit;
+        let (result, abort_locations) = if self.report_gas {
+            let report = run_move_unit_tests_with_report(
+                &rerooted_path,
+                build_config.clone(),
+                Some(unit_test_config),
+                self.test.compute_coverage,
+            )?;
+            println!("Gas used per test:");
+            for (test_name, gas) in report.gas_used {
+                println!("  {test_name}: {gas}");
+            }
+            (report.result, report.abort_locations)
+        } else {
+            let result = run_move_unit_tests(
+                &rerooted_path,
+                build_config.clone(),
+                Some(unit_test_config),
+                self.test.compute_coverage,
+            )?;
+            let abort_locations = match result {
+                UnitTestResult::Failure => {
+                    locate_aborts(&rerooted_path, build_config.clone(), gas_limit)?
+                }
+                UnitTestResult::Success => BTreeMap::new(),
+            };
+            (result, abort_locations)
+        };
+        if result == UnitTestResult::Failure && !abort_locations.is_empty() {
+            println!("Aborts:");
+            for (test_name, location) in abort_locations {
+                println!("  {test_name}: {location}");
+            }
+        }
+        match (result, self.min_coverage) {
+            (UnitTestResult::Success, Some(min_coverage)) => {
+                check_coverage(&rerooted_path, build_config, min_coverage)
+            }
+            (result, _) => Ok(result),
+        }
     }
 }
 
+/// Compute the total coverage of the root modules of the package at `path` from the coverage
+/// map written by a previous run of the unit tests with coverage enabled, and fail if it is
+/// below `min_coverage` percent.
+pub fn check_coverage(
+    path: &Path,
+    build_config: BuildConfig,
+    min_coverage: f64,
+) -> anyhow::Result<UnitTestResult> {
+    let coverage_map =
+        CoverageMap::from_binary_file(path.join(".coverage_map.mvcov"))?.to_unified_exec_map();
+    let package = build_config.compile_package(path, &mut Vec::new())?;
+
+    let (mut total, mut covered) = (0u64, 0u64);
+    for unit in package.root_modules() {
+        let CompiledUnit::Module(NamedCompiledModule { module, .. }) = &unit.unit else {
+            continue;
+        };
+        let summary = summary::summarize_inst_cov(module, &coverage_map);
+        for function in summary.function_summaries.values() {
+            if !function.fn_is_native {
+                total += function.total;
+                covered += function.covered;
+            }
+        }
+    }
+
+    let coverage = if total == 0 {
+        100.0
+    } else {
+        covered as f64 / total as f64 * 100.0
+    };
+    println!("Total coverage: {coverage:.2}%, minimum required: {min_coverage:.2}%");
+    Ok(if coverage < min_coverage {
+        UnitTestResult::Failure
+    } else {
+        UnitTestResult::Success
+    })
+}
+
 static SET_EXTENSION_HOOK: Lazy<()> =
     Lazy::new(|| set_extension_hook(Box::new(new_testing_object_and_natives_cost_runtime)));
 
+// Seed of the digest of the test transactions, read by the extension hook. The tests run on the
+// worker threads of the test runner, so the seed is shared by the whole process: a seeded run
+// holds `SEEDED_RUN` exclusively while other runs share it, so that no run sees another's seed.
+static DIGEST_SEED: Mutex<Option<u64>> = Mutex::new(None);
+static SEEDED_RUN: RwLock<()> = RwLock::new(());
+
+// Clears the digest seed before releasing `SEEDED_RUN`.
+struct SeededRun {
+    _exclusive: RwLockWriteGuard<'static, ()>,
+}
+
+impl Drop for SeededRun {
+    fn drop(&mut self) {
+        *DIGEST_SEED.lock().unwrap() = None;
+    }
+}
+
 /// This function returns a result of UnitTestResult. The outer result indicates whether it
 /// successfully started running the test, and the inner result indicatests whether all tests pass.
 pub fn run_move_unit_tests(
@@ -75,12 +191,235 @@ pub fn run_move_unit_tests(
     build_config: BuildConfig,
     config: Option<UnitTestingConfig>,
     compute_coverage: bool,
+) -> anyhow::Result<UnitTestResult> {
+    run_move_unit_tests_impl(
+        path,
+        build_config,
+        config,
+        compute_coverage,
+        /* digest_seed */ None,
+    )
+}
+
+/// Like [run_move_unit_tests], but the tests run in a transaction whose digest is derived from
+/// `digest_seed` instead of being random, which makes their object runtime reproducible.
+pub fn run_move_unit_tests_with_seed(
+    path: &Path,
+    build_config: BuildConfig,
+    config: Option<UnitTestingConfig>,
+    compute_coverage: bool,
+    digest_seed: u64,
+) -> anyhow::Result<UnitTestResult> {
+    run_move_unit_tests_impl(
+        path,
+        build_config,
+        config,
+        compute_coverage,
+        Some(digest_seed),
+    )
+}
+
+/// Outcome of a run of the unit tests of a package, along with measurements of each test, keyed
+/// by the fully qualified name of the test function.
+pub struct UnitTestReport {
+    pub result: UnitTestResult,
+    /// Gas used by each test
+    pub gas_used: BTreeMap<String, u64>,
+    /// Where each test that was not expected to fail aborted, when the source maps of the
+    /// package locate the failing instruction
+    pub abort_locations: BTreeMap<String, AbortLocation>,
+}
+
+/// Like [run_move_unit_tests], but also reports the gas used by each test, and where the tests
+/// that failed aborted, in source coordinates. The move-cli runner only returns the outcome of
+/// the run, so the tests of the package are measured by executing its test plan once more, in a
+/// Move VM set up like the runner's.
+pub fn run_move_unit_tests_with_report(
+    path: &Path,
+    build_config: BuildConfig,
+    config: Option<UnitTestingConfig>,
+    compute_coverage: bool,
+) -> anyhow::Result<UnitTestReport> {
+    let config = config.unwrap_or_else(default_unit_testing_config);
+    let gas_limit = config.gas_limit.unwrap_or(MAX_UNIT_TEST_INSTRUCTIONS);
+    let result = run_move_unit_tests(path, build_config.clone(), Some(config), compute_coverage)?;
+
+    let test_plan = build_test_plan(path, build_config)?;
+    let mut gas_used = BTreeMap::new();
+    let mut abort_locations = BTreeMap::new();
+    for run in execute_test_plan(&test_plan, gas_limit)? {
+        if let Some(location) = run.abort_location {
+            abort_locations.insert(run.name.clone(), location);
+        }
+        gas_used.insert(run.name, run.gas_used);
+    }
+    Ok(UnitTestReport {
+        result,
+        gas_used,
+        abort_locations,
+    })
+}
+
+/// Where the tests of the package at `path` that were not expected to fail aborted, in source
+/// coordinates, keyed by the fully qualified name of the test function. The tests are executed
+/// once more to locate their aborts, bounded by `gas_limit` as in [UnitTestingConfig].
+pub fn locate_aborts(
+    path: &Path,
+    build_config: BuildConfig,
+    gas_limit: Option<u64>,
+) -> anyhow::Result<BTreeMap<String, AbortLocation>> {
+    let test_plan = build_test_plan(path, build_config)?;
+    let gas_limit = gas_limit.unwrap_or(MAX_UNIT_TEST_INSTRUCTIONS);
+    Ok(execute_test_plan(&test_plan, gas_limit)?
+        .into_iter()
+        .filter_map(|run| Some((run.name, run.abort_location?)))
+        .collect())
+}
+
+// Outcome of the execution of a single test, see [execute_test_plan].
+struct TestRun {
+    name: String,
+    gas_used: u64,
+    abort_location: Option<AbortLocation>,
+}
+
+// Compile the package at `path` in test mode and collect its tests, as the move-cli runner does.
+fn build_test_plan(path: &Path, build_config: BuildConfig) -> anyhow::Result<TestPlan> {
+    let build_config = BuildConfig {
+        test_mode: true,
+        ..build_config
+    };
+    let resolution_graph = build_config.resolution_graph_for_package(path, &mut Vec::new())?;
+    let root_package = resolution_graph.root_package.package.name;
+    let mut test_plan = None;
+    BuildPlan::create(resolution_graph)?.compile_with_driver(&mut Vec::new(), |compiler| {
+        let (files, compiler_res) = compiler.run::<PASS_CFGIR>()?;
+        let (_, compiler) = diagnostics::unwrap_or_report_diagnostics(&files, compiler_res);
+        let (mut compiler, cfgir) = compiler.into_ast();
+        let tests = construct_test_plan(compiler.compilation_env(), Some(root_package), &cfgir);
+        let (units, _) =
+            diagnostics::unwrap_or_report_diagnostics(&files, compiler.at_cfgir(cfgir).build());
+        test_plan = Some(TestPlan::new(
+            tests.unwrap_or_default(),
+            files.clone(),
+            units.clone(),
+        ));
+        Ok((files, units))
+    })?;
+    test_plan.ok_or_else(|| anyhow!("Cannot build the test plan of {}", path.display()))
+}
+
+// Execute each test of `test_plan` as the test runner does: in a fresh session over the modules
+// of the package, metered with the unit test cost schedule and bounded by `gas_limit`.
+fn execute_test_plan(test_plan: &TestPlan, gas_limit: u64) -> anyhow::Result<Vec<TestRun>> {
+    // the extension hook reads the digest seed, which must not be set by a seeded run meanwhile
+    let _shared = SEEDED_RUN.read().unwrap();
+
+    let mut storage = move_vm_test_utils::InMemoryStorage::new();
+    for module in test_plan.module_info.values() {
+        let mut bytes = vec![];
+        module.module.serialize(&mut bytes)?;
+        storage.publish_or_overwrite_module(module.module.self_id(), bytes);
+    }
+    let natives = natives::all_natives(MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS);
+    let vm = MoveVM::new(natives).map_err(|e| anyhow!("Cannot create the Move VM: {e:?}"))?;
+    let cost_table = initial_cost_schedule();
+
+    let mut runs = vec![];
+    for (module_id, module_tests) in &test_plan.module_tests {
+        for (function, test) in &module_tests.tests {
+            let args = test
+                .arguments
+                .iter()
+                .map(|arg| {
+                    arg.simple_serialize()
+                        .ok_or_else(|| anyhow!("Cannot serialize the arguments of {function}"))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let mut extensions = NativeContextExtensions::default();
+            new_testing_object_and_natives_cost_runtime(&mut extensions);
+            let mut session = vm.new_session_with_extensions(&storage, extensions);
+            let mut gas_status = GasStatus::new(&cost_table, Gas::new(gas_limit));
+            let result = session.execute_function_bypass_visibility(
+                module_id,
+                IdentStr::new(function)?,
+                vec![],
+                args,
+                &mut gas_status,
+            );
+            // tests expected to fail pass by aborting, the runner reports the other outcomes
+            let location = match result {
+                Err(error) if test.expected_failure.is_none() => abort_location(test_plan, &error),
+                _ => None,
+            };
+            runs.push(TestRun {
+                name: format!("{}::{function}", module_id.short_str_lossless()),
+                gas_used: gas_limit - u64::from(gas_status.remaining_gas()),
+                abort_location: location,
+            });
+        }
+    }
+    Ok(runs)
+}
+
+/// Source coordinates of the abort of a failing test.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AbortLocation {
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for AbortLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file.display(), self.line, self.column)
+    }
+}
+
+// Resolve the instruction at which `error` stopped the execution to source coordinates, with
+// the source map of its module.
+fn abort_location(test_plan: &TestPlan, error: &VMError) -> Option<AbortLocation> {
+    let module_id = match error.location() {
+        Location::Module(module_id) => module_id,
+        _ => return None,
+    };
+    let (function, offset) = error.offsets().first()?;
+    let loc = test_plan
+        .module_info
+        .get(module_id)?
+        .source_map
+        .get_function_source_map(*function)
+        .ok()?
+        .get_code_location(*offset)?;
+    let (file, source) = test_plan.files.get(&loc.file_hash())?;
+    let preceding = source.get(..loc.start() as usize)?;
+    let line_start = preceding.rfind('\n').map_or(0, |i| i + 1);
+    Some(AbortLocation {
+        file: PathBuf::from(file.as_str()),
+        line: preceding.matches('\n').count() + 1,
+        column: preceding[line_start..].chars().count() + 1,
+    })
+}
+
+fn run_move_unit_tests_impl(
+    path: &Path,
+    build_config: BuildConfig,
+    config: Option<UnitTestingConfig>,
+    compute_coverage: bool,
+    digest_seed: Option<u64>,
 ) -> anyhow::Result<UnitTestResult> {
     // bind the extension hook if it has not yet been done
     Lazy::force(&SET_EXTENSION_HOOK);
+    let _shared = digest_seed.is_none().then(|| SEEDED_RUN.read().unwrap());
+    let _seeded = digest_seed.map(|seed| {
+        let run = SeededRun {
+            _exclusive: SEEDED_RUN.write().unwrap(),
+        };
+        *DIGEST_SEED.lock().unwrap() = Some(seed);
+        run
+    });
 
-    let config = config
-        .unwrap_or_else(|| UnitTestingConfig::default_with_bound(Some(MAX_UNIT_TEST_INSTRUCTIONS)));
+    let config = config.unwrap_or_else(default_unit_testing_config);
 
     move_cli::base::test::run_move_unit_tests(
         path,
@@ -96,6 +435,10 @@ pub fn run_move_unit_tests(
     )
 }
 
+fn default_unit_testing_config() -> UnitTestingConfig {
+    UnitTestingConfig::default_with_bound(Some(MAX_UNIT_TEST_INSTRUCTIONS))
+}
+
 // Convert from our representation of gas costs to the type that the MoveVM expects.
 // We don't want our gas depending on the MoveVM test utils and we don't want to fix our
 // representation to whatever is there, so instead we perform this translation from our gas units
@@ -117,15 +460,9 @@ fn initial_cost_schedule() -> move_vm_test_utils::gas_schedule::CostTable {
 }
 
 fn new_testing_object_and_natives_cost_runtime(ext: &mut NativeContextExtensions) {
-    let store = InMemoryStorage::new(vec![]);
-    let state_view = TemporaryStore::new(
-        store,
-        InputObjects::new(vec![]),
-        TransactionDigest::random(),
-        &ProtocolConfig::get_for_min_version(),
-    );
+    let digest_seed = *DIGEST_SEED.lock().unwrap();
     ext.add(ObjectRuntime::new(
-        Box::new(state_view),
+        Box::new(new_testing_state_view(digest_seed)),
         BTreeMap::new(),
         false,
         &ProtocolConfig::get_for_min_version(),
@@ -134,3 +471,129 @@ fn new_testing_object_and_natives_cost_runtime(ext: &mut NativeContextExtensions
         &ProtocolConfig::get_for_min_version(),
     ));
 }
+
+fn new_testing_state_view(digest_seed: Option<u64>) -> TemporaryStore<InMemoryStorage> {
+    let tx_digest = match digest_seed {
+        Some(seed) => TransactionDigest::new(Blake2b256::digest(seed.to_le_bytes()).digest),
+        None => TransactionDigest::random(),
+    };
+    TemporaryStore::new(
+        InMemoryStorage::new(vec![]),
+        InputObjects::new(vec![]),
+        tx_digest,
+        &ProtocolConfig::get_for_min_version(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{locate_aborts, new_testing_state_view, run_move_unit_tests_with_report};
+    use move_cli::base::test::UnitTestResult;
+    use move_package::BuildConfig;
+    use sui_types::base_types::{ObjectID, SuiAddress};
+    use sui_types::object::Object;
+    use sui_types::storage::WriteKind;
+
+    #[test]
+    fn test_seeded_state_view_is_reproducible() {
+        let object = Object::with_id_owner_for_testing(ObjectID::ZERO, SuiAddress::ZERO);
+        let run = |seed| {
+            let mut state_view = new_testing_state_view(seed);
+            state_view.write_object(object.clone(), WriteKind::Mutate);
+            state_view.into_inner()
+        };
+
+        assert_eq!(run(Some(7)), run(Some(7)));
+        assert_ne!(run(Some(7)), run(Some(8)));
+        assert_ne!(run(None), run(None));
+    }
+
+    #[test]
+    fn test_report_gas_per_test() {
+        let package = tempfile::tempdir().unwrap();
+        std::fs::create_dir(package.path().join("sources")).unwrap();
+        std::fs::write(
+            package.path().join("Move.toml"),
+            "[package]\nname = \"Gas\"\nversion = \"0.0.1\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            package.path().join("sources/gas.move"),
+            r#"module 0x42::gas {
+                #[test]
+                fun trivial() {}
+
+                #[test]
+                fun heavy() {
+                    let i = 0;
+                    while (i < 1000) {
+                        i = i + 1;
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let report =
+            run_move_unit_tests_with_report(package.path(), BuildConfig::default(), None, false)
+                .unwrap();
+        assert_eq!(UnitTestResult::Success, report.result);
+
+        let gas_of = |test: &str| {
+            report
+                .gas_used
+                .iter()
+                .find_map(|(name, gas)| name.ends_with(test).then_some(*gas))
+                .unwrap()
+        };
+        assert!(gas_of("::heavy") > gas_of("::trivial"));
+    }
+
+    #[test]
+    fn test_report_abort_location() {
+        let package = tempfile::tempdir().unwrap();
+        std::fs::create_dir(package.path().join("sources")).unwrap();
+        std::fs::write(
+            package.path().join("Move.toml"),
+            "[package]\nname = \"Failing\"\nversion = \"0.0.1\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            package.path().join("sources/failing.move"),
+            r#"module 0x42::failing {
+                #[test]
+                fun aborts() {
+                    abort 42
+                }
+
+                #[test]
+                fun passes() {}
+
+                #[test]
+                #[expected_failure(abort_code = 7)]
+                fun expected() {
+                    abort 7
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let report =
+            run_move_unit_tests_with_report(package.path(), BuildConfig::default(), None, false)
+                .unwrap();
+        assert_eq!(UnitTestResult::Failure, report.result);
+        // Gas is reported for all the tests in the same run.
+        assert_eq!(3, report.gas_used.len());
+
+        // Only the unexpected abort is located.
+        assert_eq!(1, report.abort_locations.len());
+        let (test_name, location) = report.abort_locations.iter().next().unwrap();
+        assert!(test_name.ends_with("::aborts"));
+        assert!(location.file.ends_with("sources/failing.move"));
+        assert_eq!((4, 21), (location.line, location.column));
+
+        // The aborts are located the same way without measuring the gas.
+        let abort_locations = locate_aborts(package.path(), BuildConfig::default(), None).unwrap();
+        assert_eq!(report.abort_locations, abort_locations);
+    }
+}
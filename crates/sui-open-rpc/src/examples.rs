@@ -490,6 +490,8 @@ impl RpcExampleProvider {
                 events_digest: Some(TransactionEventsDigest::new(self.rng.gen())),
                 dependencies: vec![],
             })),
+            raw_effects: None,
+            raw_transaction: None,
             events: None,
             object_changes: Some(vec![object_change]),
             balance_changes: None,
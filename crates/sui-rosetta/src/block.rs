@@ -6,6 +6,7 @@ use axum::{Extension, Json};
 use axum_extra::extract::WithRejection;
 use tracing::debug;
 
+use crate::operations::Operations;
 use crate::types::{
     BlockRequest, BlockResponse, BlockTransactionRequest, BlockTransactionResponse, Transaction,
     TransactionIdentifier,
@@ -57,13 +58,13 @@ pub async fn transaction(
         .await?;
     let hash = response.digest;
 
-    let operations = response.try_into()?;
+    let operations: Operations = response.try_into()?;
 
     let transaction = Transaction {
         transaction_identifier: TransactionIdentifier { hash },
+        metadata: operations.metadata(),
         operations,
         related_transactions: vec![],
-        metadata: None,
     };
 
     Ok(BlockTransactionResponse { transaction })
@@ -1,6 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::BTreeMap;
+
 use axum::extract::State;
 use axum::{Extension, Json};
 use axum_extra::extract::WithRejection;
@@ -9,16 +11,19 @@ use futures::StreamExt;
 
 use shared_crypto::intent::{Intent, IntentMessage};
 use sui_json_rpc_types::{
-    StakeStatus, SuiObjectDataOptions, SuiTransactionEffectsAPI, SuiTransactionResponseOptions,
+    Coin, StakeStatus, SuiObjectDataOptions, SuiTransactionEffectsAPI,
+    SuiTransactionResponseOptions,
 };
 use sui_sdk::rpc_types::SuiExecutionStatus;
-use sui_types::base_types::SuiAddress;
+use sui_sdk::SuiClient;
+use sui_types::base_types::{SuiAddress, TransactionDigest};
 use sui_types::crypto::{SignatureScheme, ToFromBytes};
 use sui_types::error::SuiError;
 use sui_types::messages::{Transaction, TransactionData, TransactionDataAPI};
 use sui_types::signature::GenericSignature;
 
 use crate::errors::Error;
+use crate::operations::{MoveCallArg, Operations};
 use crate::types::{
     Amount, ConstructionCombineRequest, ConstructionCombineResponse, ConstructionDeriveRequest,
     ConstructionDeriveResponse, ConstructionHashRequest, ConstructionMetadata,
@@ -57,22 +62,25 @@ pub async fn payloads(
 ) -> Result<ConstructionPayloadsResponse, Error> {
     env.check_network_identifier(&request.network_identifier)?;
     let metadata = request.metadata.ok_or(Error::MissingMetadata)?;
-    let address = metadata.sender;
 
     let data = request
         .operations
         .into_internal()?
         .try_into_data(metadata)?;
+    let signers = signers(&data);
     let intent_msg = IntentMessage::new(Intent::default(), data);
     let intent_msg_bytes = bcs::to_bytes(&intent_msg)?;
 
     Ok(ConstructionPayloadsResponse {
         unsigned_transaction: Hex::from_bytes(&intent_msg_bytes),
-        payloads: vec![SigningPayload {
-            account_identifier: address.into(),
-            hex_bytes: Hex::encode(bcs::to_bytes(&intent_msg)?),
-            signature_type: Some(SignatureType::Ed25519),
-        }],
+        payloads: signers
+            .into_iter()
+            .map(|address| SigningPayload {
+                account_identifier: address.into(),
+                hex_bytes: Hex::encode(&intent_msg_bytes),
+                signature_type: Some(SignatureType::Ed25519),
+            })
+            .collect(),
     })
 }
 
@@ -87,25 +95,29 @@ pub async fn combine(
     env.check_network_identifier(&request.network_identifier)?;
     let unsigned_tx = request.unsigned_transaction.to_vec()?;
     let intent_msg: IntentMessage<TransactionData> = bcs::from_bytes(&unsigned_tx)?;
-    let sig = request
-        .signatures
-        .first()
-        .ok_or_else(|| Error::MissingInput("Signature".to_string()))?;
-    let sig_bytes = sig.hex_bytes.to_vec()?;
-    let pub_key = sig.public_key.hex_bytes.to_vec()?;
-    let flag = vec![match sig.signature_type {
-        SignatureType::Ed25519 => SignatureScheme::ED25519,
-        SignatureType::Ecdsa => SignatureScheme::Secp256k1,
+    if request.signatures.is_empty() {
+        return Err(Error::MissingInput("Signature".to_string()));
     }
-    .flag()];
-
-    let signed_tx = Transaction::from_generic_sig_data(
-        intent_msg.value,
-        Intent::default(),
-        vec![GenericSignature::from_bytes(
-            &[&*flag, &*sig_bytes, &*pub_key].concat(),
-        )?],
-    );
+    // Sponsored transactions carry a signature from the gas owner as well as the sender.
+    let signatures = request
+        .signatures
+        .iter()
+        .map(|sig| {
+            let sig_bytes = sig.hex_bytes.to_vec()?;
+            let pub_key = sig.public_key.hex_bytes.to_vec()?;
+            let flag = vec![match sig.signature_type {
+                SignatureType::Ed25519 => SignatureScheme::ED25519,
+                SignatureType::Ecdsa => SignatureScheme::Secp256k1,
+            }
+            .flag()];
+            Ok(GenericSignature::from_bytes(
+                &[&*flag, &*sig_bytes, &*pub_key].concat(),
+            )?)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let signed_tx =
+        Transaction::from_generic_sig_data(intent_msg.value, Intent::default(), signatures);
     signed_tx.verify_signature()?;
     let signed_tx_bytes = bcs::to_bytes(&signed_tx)?;
 
@@ -155,6 +167,30 @@ pub async fn submit(
     })
 }
 
+/// Validate a signed transaction end-to-end without broadcasting it, the signatures are verified
+/// and the transaction is run in dev-inspect mode. Returns the digest the transaction would have
+/// once submitted. This is separate from [submit], which does not dry-run transactions.
+pub async fn submit_dry_run(
+    client: &SuiClient,
+    signed_tx: Transaction,
+) -> Result<TransactionDigest, Error> {
+    let signed_tx = signed_tx.verify()?;
+    let data = signed_tx.data().transaction_data();
+    let results = client
+        .read_api()
+        .dev_inspect_transaction(
+            data.sender(),
+            data.kind().clone(),
+            Some(data.gas_price()),
+            None,
+        )
+        .await?;
+    if let SuiExecutionStatus::Failure { error } = results.effects.status() {
+        return Err(Error::TransactionDryRunError(error.to_string()));
+    }
+    Ok(*signed_tx.digest())
+}
+
 /// Preprocess is called prior to /construction/payloads to construct a request for any metadata
 /// that is needed for transaction construction given (i.e. account nonce).
 ///
@@ -166,11 +202,17 @@ pub async fn preprocess(
     env.check_network_identifier(&request.network_identifier)?;
 
     let internal_operation = request.operations.into_internal()?;
-    let sender = internal_operation.sender();
+    let mut required_public_keys = vec![internal_operation.sender().into()];
+    if let Some(sponsor) = internal_operation.sponsor() {
+        required_public_keys.push(sponsor.into());
+    }
 
     Ok(ConstructionPreprocessResponse {
-        options: Some(MetadataOptions { internal_operation }),
-        required_public_keys: vec![sender.into()],
+        options: Some(MetadataOptions {
+            internal_operation,
+            gas_preference: Default::default(),
+        }),
+        required_public_keys,
     })
 }
 
@@ -204,6 +246,7 @@ pub async fn metadata(
     env.check_network_identifier(&request.network_identifier)?;
     let option = request.options.ok_or(Error::MissingMetadata)?;
     let sender = option.internal_operation.sender();
+    let sponsor = option.internal_operation.sponsor();
     let gas_price = context
         .client
         .governance_api()
@@ -257,39 +300,91 @@ pub async fn metadata(
 
             (Some(0), stake_refs, 10000)
         }
-    };
+        InternalOperation::Publish { .. } => (Some(0), vec![], 10000),
+        InternalOperation::MoveCall { arguments, .. } => {
+            let object_ids = arguments
+                .iter()
+                .filter_map(|arg| match arg {
+                    MoveCallArg::Object(id) => Some(*id),
+                    MoveCallArg::Pure(_) => None,
+                })
+                .collect::<Vec<_>>();
+            let responses = context
+                .client
+                .read_api()
+                .multi_get_object_with_options(object_ids, SuiObjectDataOptions::default())
+                .await?;
+            let object_refs = responses
+                .into_iter()
+                .map(|object| object.into_object().map(|o| o.object_ref()))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(SuiError::from)?;
 
-    // Try select coins for required amounts
-    let coins = if let Some(amount) = total_required_amount {
-        let total_amount = amount + (budget * gas_price);
-        context
-            .client
-            .coin_read_api()
-            .select_coins(sender, None, total_amount.into(), None, vec![])
-            .await
-            .ok()
-    } else {
-        None
+            (Some(0), object_refs, 10000)
+        }
+        InternalOperation::Mint { treasury_cap, .. } => {
+            let treasury_cap = context
+                .client
+                .read_api()
+                .get_object_with_options(*treasury_cap, SuiObjectDataOptions::default())
+                .await?
+                .into_object()
+                .map_err(SuiError::from)?
+                .object_ref();
+            (Some(0), vec![treasury_cap], 10000)
+        }
     };
-    // If required amount is None (all SUI) or failed to select coin (might not have enough SUI), select all coins.
-    let coins = if let Some(coins) = coins {
-        coins
+
+    let (coins, objects, total_coin_value) = if let Some(sponsor) = sponsor {
+        // The sponsor only covers gas, the required amount comes out of the sender's coins.
+        let gas_coins = select_coins(&context, sponsor, Some(budget * gas_price)).await;
+        let coins = select_coins(&context, sender, total_required_amount).await;
+        let total_coin_value = coins.iter().fold(0, |sum, coin| sum + coin.balance);
+        let objects = objects
+            .into_iter()
+            .chain(coins.into_iter().map(|c| c.object_ref()))
+            .collect::<Vec<_>>();
+        (gas_coins, objects, total_coin_value)
     } else {
-        context
-            .client
-            .coin_read_api()
-            .get_coins_stream(sender, None)
-            .collect::<Vec<_>>()
-            .await
+        let total_required_amount =
+            total_required_amount.map(|amount| amount + (budget * gas_price));
+        let coins = select_coins(&context, sender, total_required_amount).await;
+        let total_coin_value = coins.iter().fold(0, |sum, coin| sum + coin.balance);
+        (coins, objects, total_coin_value)
     };
-
-    let total_coin_value = coins.iter().fold(0, |sum, coin| sum + coin.balance);
-
+    let coin_balances = coins
+        .iter()
+        .map(|c| (c.coin_object_id, c.balance))
+        .collect::<BTreeMap<_, _>>();
     let coins = coins
         .into_iter()
         .map(|c| c.object_ref())
         .collect::<Vec<_>>();
 
+    // Payments in other currencies come out of the sender's coins of that type.
+    let mut currency_coins = BTreeMap::new();
+    if let InternalOperation::PaySui { coin_payments, .. } = &option.internal_operation {
+        for payment in coin_payments {
+            payment.check_decimals(&context.client).await?;
+            let amount = payment.amounts.iter().map(|amount| *amount as u128).sum();
+            let coins = context
+                .client
+                .coin_read_api()
+                .select_coins(
+                    sender,
+                    Some(payment.coin_type.clone()),
+                    amount,
+                    None,
+                    vec![],
+                )
+                .await?;
+            currency_coins.insert(
+                payment.coin_type.clone(),
+                coins.iter().map(|c| c.object_ref()).collect(),
+            );
+        }
+    }
+
     // get gas estimation from dry-run, this will also return any tx error.
     let data = option
         .internal_operation
@@ -300,6 +395,9 @@ pub async fn metadata(
             total_coin_value,
             gas_price: 1,
             budget,
+            currency_coins: currency_coins.clone(),
+            coin_balances: coin_balances.clone(),
+            gas_preference: Default::default(),
         })?;
 
     let dry_run = context.client.read_api().dry_run_transaction(data).await?;
@@ -319,11 +417,53 @@ pub async fn metadata(
             total_coin_value,
             gas_price,
             budget,
+            currency_coins,
+            coin_balances,
+            gas_preference: option.gas_preference,
         },
         suggested_fee: vec![Amount::new(budget as i128)],
     })
 }
 
+/// Select `owner`'s SUI coins covering `amount`.
+/// If amount is None (all SUI) or selection fails (might not have enough SUI), select all coins.
+async fn select_coins(
+    context: &OnlineServerContext,
+    owner: SuiAddress,
+    amount: Option<u64>,
+) -> Vec<Coin> {
+    let coins = if let Some(amount) = amount {
+        context
+            .client
+            .coin_read_api()
+            .select_coins(owner, None, amount.into(), None, vec![])
+            .await
+            .ok()
+    } else {
+        None
+    };
+    if let Some(coins) = coins {
+        coins
+    } else {
+        context
+            .client
+            .coin_read_api()
+            .get_coins_stream(owner, None)
+            .collect::<Vec<_>>()
+            .await
+    }
+}
+
+/// Addresses expected to sign the transaction, the gas owner signs as well if it is not the
+/// sender.
+fn signers(data: &TransactionData) -> Vec<SuiAddress> {
+    let mut signers = vec![data.sender()];
+    if data.gas_owner() != data.sender() {
+        signers.push(data.gas_owner());
+    }
+    signers
+}
+
 ///  This is run as a sanity check before signing (after /construction/payloads)
 /// and before broadcast (after /construction/combine).
 ///
@@ -343,14 +483,14 @@ pub async fn parse(
         intent.value
     };
     let account_identifier_signers = if request.signed {
-        vec![data.sender().into()]
+        signers(&data).into_iter().map(|s| s.into()).collect()
     } else {
         vec![]
     };
-    let operations = data.try_into()?;
+    let operations: Operations = data.try_into()?;
     Ok(ConstructionParseResponse {
+        metadata: operations.metadata(),
         operations,
         account_identifier_signers,
-        metadata: None,
     })
 }
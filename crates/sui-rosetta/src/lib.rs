@@ -31,6 +31,7 @@ pub mod types;
 pub static SUI: Lazy<Currency> = Lazy::new(|| Currency {
     symbol: "SUI".to_string(),
     decimals: 9,
+    metadata: None,
 });
 
 pub struct RosettaOnlineServer {
@@ -1,17 +1,24 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::ops::Not;
 use std::str::FromStr;
 use std::vec;
 
 use anyhow::anyhow;
+use fastcrypto::encoding::Base64;
+use move_binary_format::access::ModuleAccess;
+use move_binary_format::file_format::CompiledModule;
+use move_core_types::account_address::AccountAddress;
 use move_core_types::ident_str;
-use move_core_types::language_storage::StructTag;
+use move_core_types::language_storage::{StructTag, TypeTag};
 use move_core_types::value::MoveTypeLayout;
-use serde::Deserialize;
-use serde::Serialize;
+use serde::de::Error as _;
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
+use serde_with::{serde_as, DisplayFromStr};
 
 use sui_json_rpc_types::SuiCommand;
 use sui_json_rpc_types::SuiProgrammableMoveCall;
@@ -19,29 +26,93 @@ use sui_json_rpc_types::SuiProgrammableTransaction;
 use sui_json_rpc_types::{BalanceChange, SuiArgument};
 use sui_sdk::json::SuiJsonValue;
 use sui_sdk::rpc_types::{
-    SuiTransactionData, SuiTransactionDataAPI, SuiTransactionEffectsAPI, SuiTransactionKind,
-    SuiTransactionResponse,
+    DevInspectResults, SuiExecutionStatus, SuiTransactionData, SuiTransactionDataAPI,
+    SuiTransactionEffectsAPI, SuiTransactionKind, SuiTransactionResponse,
 };
+use sui_sdk::SuiClient;
 use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use sui_types::coin::{COIN_MINT_AND_TRANSFER_FUNC_NAME, COIN_MODULE_NAME};
 use sui_types::gas_coin::{GasCoin, GAS};
 use sui_types::governance::{ADD_STAKE_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
-use sui_types::messages::TransactionData;
-use sui_types::object::Owner;
+use sui_types::messages::{
+    Argument, CallArg, Command, ObjectArg, ProgrammableMoveCall, TransactionData,
+    TransactionDataAPI, TransactionKind,
+};
+use sui_types::move_package::PACKAGE_MODULE_NAME;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
-use sui_types::{SUI_FRAMEWORK_ADDRESS, SUI_FRAMEWORK_OBJECT_ID};
+use sui_types::{parse_sui_type_tag, SUI_FRAMEWORK_ADDRESS, SUI_FRAMEWORK_OBJECT_ID};
 
 use crate::types::{
-    AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, InternalOperation,
-    OperationIdentifier, OperationStatus, OperationType,
+    canonical_coin_type, AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier,
+    CoinPayment, ConstructionMetadata, CurrencyMetadata, InternalOperation, OperationIdentifier,
+    OperationStatus, OperationType,
 };
-use crate::Error;
+use crate::{Error, SUI};
 
 #[cfg(test)]
 #[path = "unit_tests/operations_tests.rs"]
 mod operations_tests;
 
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
-pub struct Operations(Vec<Operation>);
+/// The operations of a transaction, along with the address that signed it when parsed from
+/// transaction data. Operations of types this version doesn't know about are kept aside as
+/// they were received, so that they are re-emitted unchanged.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Operations {
+    operations: Vec<Operation>,
+    sender: Option<SuiAddress>,
+    unknown: Vec<UnknownOperation>,
+}
+
+/// An operation with an unrecognized type, e.g. one introduced by a newer version of the
+/// Rosetta API, and its position among the operations.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnknownOperation {
+    position: usize,
+    pub type_: String,
+    pub raw: Value,
+}
+
+impl Serialize for Operations {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let len = self.operations.len() + self.unknown.len();
+        let mut seq = serializer.serialize_seq(Some(len))?;
+        let mut known = self.operations.iter();
+        let mut unknown = self.unknown.iter().peekable();
+        for position in 0..len {
+            if let Some(op) = unknown.next_if(|op| op.position == position) {
+                seq.serialize_element(&op.raw)?;
+            } else if let Some(op) = known.next() {
+                seq.serialize_element(op)?;
+            }
+        }
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Operations {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut known = vec![];
+        let mut unknown = vec![];
+        let values = Vec::<Value>::deserialize(deserializer)?;
+        for (position, raw) in values.into_iter().enumerate() {
+            match raw.get("type").and_then(Value::as_str) {
+                Some(type_) if serde_json::from_value::<OperationType>(json!(type_)).is_err() => {
+                    unknown.push(UnknownOperation {
+                        position,
+                        type_: type_.to_string(),
+                        raw,
+                    })
+                }
+                _ => known.push(serde_json::from_value(raw).map_err(D::Error::custom)?),
+            }
+        }
+        Ok(Self {
+            operations: known,
+            sender: None,
+            unknown,
+        })
+    }
+}
 
 impl FromIterator<Operation> for Operations {
     fn from_iter<T: IntoIterator<Item = Operation>>(iter: T) -> Self {
@@ -59,7 +130,58 @@ impl IntoIterator for Operations {
     type Item = Operation;
     type IntoIter = vec::IntoIter<Operation>;
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+        self.operations.into_iter()
+    }
+}
+
+/// Generates valid operation sets, i.e. pays to distinct recipients and stakes that can be
+/// turned into a transaction and parsed back into the same operations.
+#[cfg(any(test, feature = "testing"))]
+impl proptest::arbitrary::Arbitrary for Operations {
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use proptest::collection::btree_map;
+        use proptest::prelude::*;
+
+        fn address() -> impl Strategy<Value = SuiAddress> {
+            any::<[u8; AccountAddress::LENGTH]>()
+                .prop_map(|bytes| SuiAddress::from(AccountAddress::new(bytes)))
+        }
+        // Small enough that the sum of the amounts of a pay fits in a u64.
+        let amount = 1..=10_000_000_000_000_000u64;
+
+        let pay_sui = (address(), btree_map(address(), amount.clone(), 1..5))
+            .prop_filter("The sender cannot pay itself", |(sender, recipients)| {
+                !recipients.contains_key(sender)
+            })
+            .prop_map(|(sender, recipients)| {
+                let total: u64 = recipients.values().sum();
+                recipients
+                    .into_iter()
+                    .map(|(recipient, amount)| Operation::pay_sui(None, recipient, amount.into()))
+                    .chain([Operation::pay_sui(None, sender, -(total as i128))])
+                    .collect::<Operations>()
+            });
+        let stake = (address(), address(), proptest::option::of(amount)).prop_map(
+            |(sender, validator, amount)| {
+                Operations::new(vec![Operation {
+                    operation_identifier: Default::default(),
+                    related_operations: vec![],
+                    type_: OperationType::Stake,
+                    status: None,
+                    account: Some(sender.into()),
+                    amount: amount.map(|amount| Amount::new(-(amount as i128))),
+                    coin_change: None,
+                    metadata: Some(OperationMetadata::Stake {
+                        validator,
+                        sponsor: None,
+                    }),
+                }])
+            },
+        );
+        prop_oneof![pay_sui, stake].boxed()
     }
 }
 
@@ -68,12 +190,33 @@ impl Operations {
         for (index, mut op) in ops.iter_mut().enumerate() {
             op.operation_identifier = (index as u64).into()
         }
-        Self(ops)
+        Self {
+            operations: ops,
+            sender: None,
+            unknown: vec![],
+        }
+    }
+
+    pub(crate) fn with_sender(mut self, sender: SuiAddress) -> Self {
+        self.sender = Some(sender);
+        self
+    }
+
+    /// Sender of the transaction the operations were parsed from, for multisig transactions
+    /// this is the multisig address.
+    pub fn sender(&self) -> Option<SuiAddress> {
+        self.sender
+    }
+
+    /// Transaction level metadata, exposes the sender so it doesn't have to be inferred from
+    /// the operations.
+    pub fn metadata(&self) -> Option<Value> {
+        self.sender.map(|sender| json!({ "sender": sender }))
     }
 
     pub fn contains(&self, other: &Operations) -> bool {
-        for (i, other_op) in other.0.iter().enumerate() {
-            if let Some(op) = self.0.get(i) {
+        for (i, other_op) in other.operations.iter().enumerate() {
+            if let Some(op) = self.operations.get(i) {
                 if op != other_op {
                     return false;
                 }
@@ -85,60 +228,350 @@ impl Operations {
     }
 
     pub fn set_status(mut self, status: Option<OperationStatus>) -> Self {
-        for op in &mut self.0 {
+        for op in &mut self.operations {
             op.status = status
         }
         self
     }
 
     pub fn type_(&self) -> Option<OperationType> {
-        self.0.first().map(|op| op.type_)
+        self.operations.first().map(|op| op.type_)
+    }
+
+    /// Total magnitude of the gas operations, 0 if the transaction has none.
+    pub fn total_gas(&self) -> i128 {
+        self.operations
+            .iter()
+            .filter(|op| op.type_ == OperationType::Gas)
+            .filter_map(|op| op.amount.as_ref())
+            .map(|amount| amount.value.abs())
+            .sum()
+    }
+
+    /// Check that the amounts follow the Rosetta sign conventions: debits (stakes) are negative,
+    /// credits (stake principal, rewards and genesis coins) are not, and the debit of a PaySui
+    /// matches its credits.
+    pub fn check_sign_conventions(&self) -> Result<(), Error> {
+        let mut pay_sui_total = 0i128;
+        for (index, op) in self.operations.iter().enumerate() {
+            let Some(amount) = &op.amount else {
+                continue;
+            };
+            let value = amount.value;
+            match op.type_ {
+                OperationType::Stake if !value.is_negative() => {
+                    return Err(Error::MalformedOperationError(format!(
+                        "Operation {index} of type {:?} is a debit, its amount should be negative, got {value}.",
+                        op.type_
+                    )));
+                }
+                OperationType::StakePrinciple
+                | OperationType::StakeReward
+                | OperationType::Genesis
+                    if value.is_negative() =>
+                {
+                    return Err(Error::MalformedOperationError(format!(
+                        "Operation {index} of type {:?} is a credit, its amount should be positive, got {value}.",
+                        op.type_
+                    )));
+                }
+                OperationType::PaySui => pay_sui_total += value,
+                _ => {}
+            }
+        }
+        if pay_sui_total != 0 {
+            return Err(Error::MalformedOperationError(format!(
+                "PaySui debits and credits should balance, got a total of {pay_sui_total}."
+            )));
+        }
+        Ok(())
+    }
+
+    /// Net amount per address of the operation at `group_index` and of the operations it is
+    /// linked to through `related_operations`, e.g. the credits and debit of a PaySui. Gas is
+    /// left out, so that itemized reports can show it separately.
+    pub fn net_of_group(&self, group_index: u64) -> HashMap<SuiAddress, i128> {
+        let mut group = vec![OperationIdentifier::from(group_index)];
+        let mut next = 0;
+        while let Some(id) = group.get(next) {
+            if let Some(op) = self
+                .operations
+                .iter()
+                .find(|op| &op.operation_identifier == id)
+            {
+                for related in &op.related_operations {
+                    if !group.contains(related) {
+                        group.push(related.clone());
+                    }
+                }
+            }
+            next += 1;
+        }
+        self.operations
+            .iter()
+            .filter(|op| op.type_ != OperationType::Gas && group.contains(&op.operation_identifier))
+            .fold(HashMap::new(), |mut net, op| {
+                if let (Some(account), Some(amount)) = (&op.account, &op.amount) {
+                    *net.entry(account.address).or_default() += amount.value;
+                }
+                net
+            })
+    }
+
+    /// Addresses touched by the operations: the sender of the transaction and the accounts of
+    /// the operations, e.g. the recipients of a payment or the owner of the gas.
+    pub fn affected_addresses(&self) -> BTreeSet<SuiAddress> {
+        self.operations
+            .iter()
+            .filter_map(|op| op.account.as_ref().map(|account| account.address))
+            .chain(self.sender)
+            .collect()
     }
 
     /// Parse operation input from rosetta operation to intermediate internal operation;
     pub fn into_internal(self) -> Result<InternalOperation, Error> {
+        self.into_internal_at().map_err(|(_, e)| e)
+    }
+
+    // Like [Self::into_internal], errors come with the index of the operation that caused them,
+    // if it is known.
+    fn into_internal_at(self) -> Result<InternalOperation, (Option<usize>, Error)> {
+        if let Some(op) = self.unknown.first() {
+            return Err((
+                None,
+                Error::InvalidInput(format!(
+                    "Unknown operation type {} at position {}",
+                    op.type_, op.position
+                )),
+            ));
+        }
         let type_ = self
             .type_()
-            .ok_or_else(|| Error::MissingInput("Operation type".into()))?;
+            .ok_or_else(|| (None, Error::MissingInput("Operation type".into())))?;
+        let index = self.type_index(type_);
+        let at = |e| (index, e);
         match type_ {
             OperationType::PaySui => self.pay_sui_ops_to_internal(),
-            OperationType::Stake => self.stake_ops_to_internal(),
-            OperationType::WithdrawStake => self.withdraw_stake_ops_to_internal(),
-            op => Err(Error::UnsupportedOperation(op)),
+            OperationType::Stake => self.stake_ops_to_internal().map_err(at),
+            OperationType::WithdrawStake => self.withdraw_stake_ops_to_internal().map_err(at),
+            OperationType::Publish => self.publish_ops_to_internal().map_err(at),
+            OperationType::MoveCall => self.move_call_ops_to_internal().map_err(at),
+            OperationType::Mint => self.mint_ops_to_internal().map_err(at),
+            op => Err(at(Error::UnsupportedOperation(op))),
         }
     }
 
-    fn pay_sui_ops_to_internal(self) -> Result<InternalOperation, Error> {
+    /// Check that the operations build into a transaction that executes successfully with
+    /// `metadata`, by dev-inspecting it. Dev-inspect doesn't verify signatures, so the
+    /// transaction doesn't need to be signed. Errors are reported against the operation that
+    /// caused them: the payment with an invalid amount or without coins to pay it from, or the
+    /// operation carried out by the command that failed. They are reported against the first
+    /// operation when the culprit can't be determined.
+    pub async fn dry_run(
+        self,
+        client: &SuiClient,
+        metadata: ConstructionMetadata,
+    ) -> Result<DevInspectResults, Error> {
+        let operations = self.clone();
+        let at_operation = |index: Option<usize>, error: String| {
+            let index = index.unwrap_or(0);
+            let type_ = operations.operations.get(index).map(|op| op.type_);
+            let type_ = type_.map_or_else(String::new, |type_| format!(" ({type_:?})"));
+            Error::TransactionDryRunError(format!("Operation {index}{type_} failed: {error}"))
+        };
+        let internal = self
+            .into_internal_at()
+            .map_err(|(index, e)| at_operation(index, e.to_string()))?;
+        if let InternalOperation::PaySui { coin_payments, .. } = &internal {
+            for payment in coin_payments {
+                let coins = metadata.currency_coins.get(&payment.coin_type);
+                if coins.map_or(true, Vec::is_empty) {
+                    return Err(at_operation(
+                        operations.coin_payment_index(&payment.coin_type),
+                        format!("No coins of type {} to pay from", payment.coin_type),
+                    ));
+                }
+            }
+        }
+        let gas_price = metadata.gas_price;
+        let data = internal
+            .try_into_data(metadata)
+            .map_err(|e| at_operation(None, e.to_string()))?;
+        let sender = data.sender();
+        let kind = data.into_kind();
+        let results = client
+            .read_api()
+            .dev_inspect_transaction(sender, kind.clone(), Some(gas_price), None)
+            .await?;
+        if let SuiExecutionStatus::Failure { error } = results.effects.status() {
+            // The command that failed is reported at the end of the error, if any.
+            let index = error
+                .rsplit_once(" in command ")
+                .and_then(|(_, command)| command.parse().ok())
+                .and_then(|command| operations.command_operation_index(&kind, command));
+            return Err(at_operation(index, error.clone()));
+        }
+        Ok(results)
+    }
+
+    // Index of the first operation of type `type_`.
+    fn type_index(&self, type_: OperationType) -> Option<usize> {
+        self.operations.iter().position(|op| op.type_ == type_)
+    }
+
+    // Index of the first payment in the currency of type `coin_type`.
+    fn coin_payment_index(&self, coin_type: &str) -> Option<usize> {
+        self.operations.iter().position(|op| {
+            op.amount
+                .as_ref()
+                .and_then(|amount| amount.currency.metadata.as_ref())
+                .map_or(false, |metadata| metadata.coin_type == coin_type)
+        })
+    }
+
+    // Index of the operation carried out by the command at `command` of the transaction built
+    // from the operations. The commands of a PaySui split the paid amounts out of a coin, merge
+    // the coins they are split from, or transfer them to a recipient, they are traced back to the
+    // payment of that amount to that recipient.
+    fn command_operation_index(&self, kind: &TransactionKind, command: usize) -> Option<usize> {
+        let type_ = self.type_()?;
+        if type_ != OperationType::PaySui {
+            return self.type_index(type_);
+        }
+        let TransactionKind::ProgrammableTransaction(pt) = kind else {
+            return None;
+        };
+        let pure = |arg: &Argument| match arg {
+            Argument::Input(i) => match pt.inputs.get(usize::from(*i))? {
+                CallArg::Pure(bytes) => Some(bytes),
+                _ => None,
+            },
+            _ => None,
+        };
+        let split = match pt.commands.get(command)? {
+            Command::SplitCoin(..) => command,
+            Command::TransferObjects(coins, _) => match coins.first()? {
+                Argument::Result(i) => usize::from(*i),
+                _ => return None,
+            },
+            Command::MergeCoins(primary, _) => pt.commands.iter().position(
+                |command| matches!(command, Command::SplitCoin(coin, _) if coin == primary),
+            )?,
+            _ => return None,
+        };
+        let Command::SplitCoin(_, amount) = pt.commands.get(split)? else {
+            return None;
+        };
+        let amount = bcs::from_bytes::<u64>(pure(amount)?).ok()?;
+        let split = Argument::Result(u16::try_from(split).ok()?);
+        let recipient = pt.commands.iter().find_map(|command| match command {
+            Command::TransferObjects(coins, recipient) if coins.contains(&split) => {
+                bcs::from_bytes::<SuiAddress>(pure(recipient)?).ok()
+            }
+            _ => None,
+        })?;
+        self.operations.iter().position(|op| {
+            op.account.as_ref().map(|account| account.address) == Some(recipient)
+                && op.amount.as_ref().map(|amount| amount.value) == Some(i128::from(amount))
+        })
+    }
+
+    // Errors come with the index of the payment that caused them, if any.
+    fn pay_sui_ops_to_internal(self) -> Result<InternalOperation, (Option<usize>, Error)> {
         let mut recipients = vec![];
         let mut amounts = vec![];
+        let mut coin_payments = BTreeMap::new();
         let mut sender = None;
-        for op in self {
+        for (index, op) in self.into_iter().enumerate() {
             if let (Some(amount), Some(account)) = (op.amount.clone(), op.account.clone()) {
                 if amount.value.is_negative() {
+                    // There is one debit per currency, all of them from the sender.
+                    if sender.map_or(false, |sender| sender != account.address) {
+                        return Err((
+                            Some(index),
+                            Error::MalformedOperationError(
+                                "PaySui debits should all be from the sender.".to_string(),
+                            ),
+                        ));
+                    }
                     sender = Some(account.address)
                 } else {
-                    recipients.push(account.address);
-                    let amount = amount.value.abs();
-                    if amount > u64::MAX as i128 {
-                        return Err(Error::InvalidInput(
-                            "Input amount exceed u64::MAX".to_string(),
+                    let value = amount.value.abs();
+                    if value > u64::MAX as i128 {
+                        return Err((
+                            Some(index),
+                            Error::InvalidInput("Input amount exceed u64::MAX".to_string()),
                         ));
                     }
-                    amounts.push(amount as u64)
+                    let decimals = amount.currency.decimals;
+                    // A currency with the coin type of SUI, in any form, is paid as SUI.
+                    let coin_type = match amount.currency.metadata {
+                        Some(CurrencyMetadata { coin_type }) => {
+                            let tag = parse_sui_type_tag(&coin_type).map_err(|e| {
+                                let error = format!("Invalid coin type {coin_type}: {e}");
+                                (Some(index), Error::InvalidInput(error))
+                            })?;
+                            let canonical = canonical_coin_type(&tag);
+                            (canonical != canonical_coin_type(&GAS::type_tag()))
+                                .then_some((canonical, coin_type))
+                        }
+                        None => None,
+                    };
+                    match coin_type {
+                        None => {
+                            if decimals != SUI.decimals {
+                                return Err((
+                                    Some(index),
+                                    Error::InvalidInput(format!(
+                                        "SUI has {} decimals, got {decimals}",
+                                        SUI.decimals
+                                    )),
+                                ));
+                            }
+                            recipients.push(account.address);
+                            amounts.push(value as u64)
+                        }
+                        Some((canonical, coin_type)) => {
+                            let payment =
+                                coin_payments
+                                    .entry(canonical)
+                                    .or_insert_with(|| CoinPayment {
+                                        coin_type,
+                                        decimals,
+                                        recipients: vec![],
+                                        amounts: vec![],
+                                    });
+                            // The coin's decimals are checked against its metadata when the
+                            // transaction is constructed, see [CoinPayment::check_decimals].
+                            if payment.decimals != decimals {
+                                return Err((
+                                    Some(index),
+                                    Error::MalformedOperationError(format!(
+                                        "Payments of {} should all have the same decimals.",
+                                        payment.coin_type
+                                    )),
+                                ));
+                            }
+                            payment.recipients.push(account.address);
+                            payment.amounts.push(value as u64)
+                        }
+                    }
                 }
             }
         }
-        let sender = sender.ok_or_else(|| Error::MissingInput("Sender address".to_string()))?;
+        let sender =
+            sender.ok_or_else(|| (None, Error::MissingInput("Sender address".to_string())))?;
         Ok(InternalOperation::PaySui {
             sender,
             recipients,
             amounts,
+            coin_payments: coin_payments.into_values().collect(),
         })
     }
 
     fn stake_ops_to_internal(self) -> Result<InternalOperation, Error> {
         let mut ops = self
-            .0
+            .operations
             .into_iter()
             .filter(|op| op.type_ == OperationType::Stake)
             .collect::<Vec<_>>();
@@ -169,7 +602,7 @@ impl Operations {
             None
         };
 
-        let OperationMetadata::Stake { validator } = metadata else {
+        let OperationMetadata::Stake { validator, sponsor } = metadata else {
             return Err(Error::InvalidInput("Cannot find delegation info from metadata.".into()))
         };
 
@@ -177,12 +610,13 @@ impl Operations {
             sender,
             validator,
             amount,
+            sponsor,
         })
     }
 
     fn withdraw_stake_ops_to_internal(self) -> Result<InternalOperation, Error> {
         let mut ops = self
-            .0
+            .operations
             .into_iter()
             .filter(|op| op.type_ == OperationType::WithdrawStake)
             .collect::<Vec<_>>();
@@ -210,14 +644,119 @@ impl Operations {
         Ok(InternalOperation::WithdrawStake { sender, stake_ids })
     }
 
+    fn publish_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let mut ops = self
+            .operations
+            .into_iter()
+            .filter(|op| op.type_ == OperationType::Publish)
+            .collect::<Vec<_>>();
+        if ops.len() != 1 {
+            return Err(Error::MalformedOperationError(
+                "Publish should only have one operation.".into(),
+            ));
+        }
+        // Checked above, safe to unwrap.
+        let op = ops.pop().unwrap();
+        let sender = op
+            .account
+            .ok_or_else(|| Error::MissingInput("Sender address".to_string()))?
+            .address;
+        let metadata = op
+            .metadata
+            .ok_or_else(|| Error::MissingInput("Publish metadata".to_string()))?;
+
+        let OperationMetadata::Publish { modules, dependencies } = metadata else {
+            return Err(Error::InvalidInput("Cannot find publish info from metadata.".into()))
+        };
+        if modules.is_empty() {
+            return Err(Error::MissingInput("Publish modules".to_string()));
+        }
+
+        Ok(InternalOperation::Publish {
+            sender,
+            modules,
+            dependencies,
+        })
+    }
+
+    fn move_call_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let mut ops = self
+            .operations
+            .into_iter()
+            .filter(|op| op.type_ == OperationType::MoveCall)
+            .collect::<Vec<_>>();
+        if ops.len() != 1 {
+            return Err(Error::MalformedOperationError(
+                "Move call should only have one operation.".into(),
+            ));
+        }
+        // Checked above, safe to unwrap.
+        let op = ops.pop().unwrap();
+        let sender = op
+            .account
+            .ok_or_else(|| Error::MissingInput("Sender address".to_string()))?
+            .address;
+        let metadata = op
+            .metadata
+            .ok_or_else(|| Error::MissingInput("Move call metadata".to_string()))?;
+
+        let OperationMetadata::MoveCall { package, module, function, type_arguments, arguments } = metadata else {
+            return Err(Error::InvalidInput("Cannot find move call info from metadata.".into()))
+        };
+
+        Ok(InternalOperation::MoveCall {
+            sender,
+            package,
+            module,
+            function,
+            type_arguments,
+            arguments,
+        })
+    }
+
+    fn mint_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let mut ops = self
+            .operations
+            .into_iter()
+            .filter(|op| op.type_ == OperationType::Mint)
+            .collect::<Vec<_>>();
+        if ops.len() != 1 {
+            return Err(Error::MalformedOperationError(
+                "Mint should only have one operation.".into(),
+            ));
+        }
+        // Checked above, safe to unwrap.
+        let op = ops.pop().unwrap();
+        let sender = op
+            .account
+            .ok_or_else(|| Error::MissingInput("Sender address".to_string()))?
+            .address;
+        let metadata = op
+            .metadata
+            .ok_or_else(|| Error::MissingInput("Mint metadata".to_string()))?;
+
+        let OperationMetadata::Mint { treasury_cap, coin_type, amount, recipient } = metadata else {
+            return Err(Error::InvalidInput("Cannot find mint info from metadata.".into()))
+        };
+
+        Ok(InternalOperation::Mint {
+            sender,
+            treasury_cap,
+            coin_type,
+            amount,
+            recipient,
+        })
+    }
+
     fn from_transaction(
         tx: SuiTransactionKind,
         sender: SuiAddress,
+        gas_owner: SuiAddress,
         status: Option<OperationStatus>,
     ) -> Result<Vec<Operation>, Error> {
         Ok(match tx {
             SuiTransactionKind::ProgrammableTransaction(pt) => {
-                Self::parse_programmable_transaction(sender, status, pt)?
+                Self::parse_programmable_transaction(sender, gas_owner, status, pt)?
             }
             _ => vec![Operation::generic_op(status, sender, tx)],
         })
@@ -225,6 +764,7 @@ impl Operations {
 
     fn parse_programmable_transaction(
         sender: SuiAddress,
+        gas_owner: SuiAddress,
         status: Option<OperationStatus>,
         pt: SuiProgrammableTransaction,
     ) -> Result<Vec<Operation>, Error> {
@@ -262,7 +802,7 @@ impl Operations {
             Some(vec![KnownValue::GasCoin(amount)])
         }
         fn transfer_object(
-            aggregated_recipients: &mut HashMap<SuiAddress, u64>,
+            aggregated_recipients: &mut BTreeMap<SuiAddress, u64>,
             inputs: &[SuiJsonValue],
             known_results: &[Vec<KnownValue>],
             objs: &[SuiArgument],
@@ -348,13 +888,24 @@ impl Operations {
         }
         let SuiProgrammableTransaction { inputs, commands } = &pt;
         let mut known_results: Vec<Vec<KnownValue>> = vec![];
-        let mut aggregated_recipients: HashMap<SuiAddress, u64> = HashMap::new();
+        // Ordered by recipient, so that the parsed operations are deterministic.
+        let mut aggregated_recipients: BTreeMap<SuiAddress, u64> = BTreeMap::new();
         let mut needs_generic = false;
         let mut operations = vec![];
         let mut stake_ids = vec![];
+        let sponsor = (gas_owner != sender).then_some(gas_owner);
         for command in commands {
             let result = match command {
-                SuiCommand::SplitCoin(_, amount) => split_coin(inputs, *amount),
+                // Only splits of the gas coin are SUI payments, unless the gas is sponsored and
+                // the staker's coins are split.
+                SuiCommand::SplitCoin(SuiArgument::GasCoin, amount) => split_coin(inputs, *amount),
+                SuiCommand::SplitCoin(_, amount) if sponsor.is_some() => {
+                    split_coin(inputs, *amount)
+                }
+                // Merging the staker's coins ahead of a sponsored stake.
+                SuiCommand::MergeCoins(SuiArgument::Input(_), _) if sponsor.is_some() => {
+                    Some(vec![])
+                }
                 SuiCommand::TransferObjects(objs, addr) => transfer_object(
                     &mut aggregated_recipients,
                     inputs,
@@ -367,12 +918,13 @@ impl Operations {
                         let amount = amount.map(|amount| Amount::new(-(amount as i128)));
                         operations.push(Operation {
                             operation_identifier: Default::default(),
+                            related_operations: vec![],
                             type_: OperationType::Stake,
                             status,
                             account: Some(sender.into()),
                             amount,
                             coin_change: None,
-                            metadata: Some(OperationMetadata::Stake { validator }),
+                            metadata: Some(OperationMetadata::Stake { validator, sponsor }),
                         });
                         vec![]
                     })
@@ -393,15 +945,22 @@ impl Operations {
         }
 
         if !needs_generic && !aggregated_recipients.is_empty() {
+            // The credits and the sender's debit are linked to each other.
+            let first_credit = operations.len() as u64;
+            let debit = first_credit + aggregated_recipients.len() as u64;
             let total_paid: u64 = aggregated_recipients.values().copied().sum();
             operations.extend(
                 aggregated_recipients
                     .into_iter()
                     .map(|(recipient, amount)| {
                         Operation::pay_sui(status, recipient, amount.into())
+                            .with_related_operations(vec![debit.into()])
                     }),
             );
-            operations.push(Operation::pay_sui(status, sender, -(total_paid as i128)));
+            operations.push(
+                Operation::pay_sui(status, sender, -(total_paid as i128))
+                    .with_related_operations((first_credit..debit).map(Into::into).collect()),
+            );
         } else if !stake_ids.is_empty() {
             let stake_ids = stake_ids.into_iter().flatten().collect::<Vec<_>>();
             let metadata = stake_ids
@@ -410,6 +969,7 @@ impl Operations {
                 .then_some(OperationMetadata::WithdrawStake { stake_ids });
             operations.push(Operation {
                 operation_identifier: Default::default(),
+                related_operations: vec![],
                 type_: OperationType::WithdrawStake,
                 status,
                 account: Some(sender.into()),
@@ -450,7 +1010,7 @@ impl Operations {
             .iter()
             .fold(balances, |mut balances, balance_change| {
                 // Rosetta only care about address owner
-                if let Owner::AddressOwner(owner) = balance_change.owner {
+                if let Some(owner) = balance_change.address_owner() {
                     if balance_change.coin_type == GAS::type_tag() {
                         *balances.entry(owner).or_default() += balance_change.amount;
                     }
@@ -475,15 +1035,23 @@ impl Operations {
     }
 }
 
+/// Sum of [Operations::total_gas] over a batch of transactions.
+pub fn total_gas_across(ops: &[Operations]) -> i128 {
+    ops.iter().map(Operations::total_gas).sum()
+}
+
 impl TryFrom<SuiTransactionData> for Operations {
     type Error = Error;
     fn try_from(data: SuiTransactionData) -> Result<Self, Self::Error> {
         let sender = *data.sender();
+        let gas_owner = data.gas_data().owner;
         Ok(Self::new(Self::from_transaction(
             data.transaction().clone(),
             sender,
+            gas_owner,
             None,
-        )?))
+        )?)
+        .with_sender(sender))
     }
 }
 
@@ -573,7 +1141,8 @@ impl TryFrom<SuiTransactionResponse> for Operations {
             .into_iter()
             .chain(coin_change_operations)
             .chain(staking_balance)
-            .collect())
+            .collect::<Operations>()
+            .with_sender(sender))
     }
 }
 
@@ -583,9 +1152,113 @@ fn is_unstake_event(tag: &StructTag) -> bool {
         && tag.name.as_ident_str() == ident_str!("UnstakingRequestEvent")
 }
 
+/// Returns the sorted ids of the packages referenced by the modules, excluding the package being
+/// published.
+pub(crate) fn module_dependencies(modules: &[Vec<u8>]) -> Result<Vec<ObjectID>, Error> {
+    let mut dependencies = vec![];
+    for bytes in modules {
+        let module = CompiledModule::deserialize(bytes)
+            .map_err(|e| Error::InvalidInput(format!("Cannot deserialize module: {e:?}")))?;
+        for dep in module.immediate_dependencies() {
+            let id = ObjectID::from(*dep.address());
+            if *dep.address() != AccountAddress::ZERO && !dependencies.contains(&id) {
+                dependencies.push(id)
+            }
+        }
+    }
+    dependencies.sort();
+    Ok(dependencies)
+}
+
+/// Map the arguments of a move call to [MoveCallArg], None if any argument is not a pure or
+/// owned object input.
+fn move_call_arguments(inputs: &[CallArg], arguments: &[Argument]) -> Option<Vec<MoveCallArg>> {
+    arguments
+        .iter()
+        .map(|arg| match arg {
+            Argument::Input(i) => match inputs.get(*i as usize)? {
+                CallArg::Pure(bytes) => Some(MoveCallArg::Pure(bytes.clone())),
+                CallArg::Object(ObjectArg::ImmOrOwnedObject((id, ..))) => {
+                    Some(MoveCallArg::Object(*id))
+                }
+                CallArg::Object(ObjectArg::SharedObject { .. }) => None,
+            },
+            Argument::GasCoin | Argument::Result(_) | Argument::NestedResult(..) => None,
+        })
+        .collect()
+}
+
+/// Parse a call to `coin::mint_and_transfer` into a Mint operation, None if `call` is anything
+/// else.
+fn mint_operation(
+    sender: SuiAddress,
+    inputs: &[CallArg],
+    call: &ProgrammableMoveCall,
+) -> Option<Operation> {
+    if call.package != SUI_FRAMEWORK_OBJECT_ID
+        || call.module.as_ident_str() != COIN_MODULE_NAME
+        || call.function.as_ident_str() != COIN_MINT_AND_TRANSFER_FUNC_NAME
+    {
+        return None;
+    }
+    let [coin_type] = &call.type_arguments[..] else {
+        return None;
+    };
+    match &move_call_arguments(inputs, &call.arguments)?[..] {
+        [MoveCallArg::Object(cap), MoveCallArg::Pure(amount), MoveCallArg::Pure(to)] => {
+            Some(Operation::mint(
+                sender,
+                *cap,
+                coin_type.clone(),
+                bcs::from_bytes(amount).ok()?,
+                bcs::from_bytes(to).ok()?,
+            ))
+        }
+        _ => None,
+    }
+}
+
 impl TryFrom<TransactionData> for Operations {
     type Error = Error;
     fn try_from(data: TransactionData) -> Result<Self, Self::Error> {
+        // Module bytes are not available in SuiTransactionData, publish has to be parsed from the
+        // raw transaction data.
+        if let TransactionKind::ProgrammableTransaction(pt) = data.kind() {
+            if let [Command::Publish(modules), Command::MoveCall(call)] = &pt.commands[..] {
+                if call.package == SUI_FRAMEWORK_OBJECT_ID
+                    && call.module.as_ident_str() == PACKAGE_MODULE_NAME
+                    && call.function.as_ident_str() == ident_str!("make_immutable")
+                {
+                    let dependencies = module_dependencies(modules)?;
+                    return Ok(Self::new(vec![Operation::publish(
+                        data.sender(),
+                        modules.clone(),
+                        dependencies,
+                    )])
+                    .with_sender(data.sender()));
+                }
+            }
+            if let [Command::MoveCall(call)] = &pt.commands[..] {
+                if let Some(mint) = mint_operation(data.sender(), &pt.inputs, call) {
+                    return Ok(Self::new(vec![mint]).with_sender(data.sender()));
+                }
+            }
+            // A single move call taking only pure and owned object inputs can be expressed as a
+            // MoveCall operation.
+            if let [Command::MoveCall(call)] = &pt.commands[..] {
+                if let Some(arguments) = move_call_arguments(&pt.inputs, &call.arguments) {
+                    return Ok(Self::new(vec![Operation::move_call(
+                        data.sender(),
+                        call.package,
+                        call.module.to_string(),
+                        call.function.to_string(),
+                        call.type_arguments.clone(),
+                        arguments,
+                    )])
+                    .with_sender(data.sender()));
+                }
+            }
+        }
         SuiTransactionData::try_from(data)?.try_into()
     }
 }
@@ -593,6 +1266,8 @@ impl TryFrom<TransactionData> for Operations {
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Operation {
     operation_identifier: OperationIdentifier,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    related_operations: Vec<OperationIdentifier>,
     #[serde(rename = "type")]
     pub type_: OperationType,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -618,11 +1293,48 @@ impl PartialEq for Operation {
     }
 }
 
+#[serde_as]
 #[derive(Deserialize, Serialize, Clone, Debug, Eq, PartialEq)]
 pub enum OperationMetadata {
     GenericTransaction(SuiTransactionKind),
-    Stake { validator: SuiAddress },
-    WithdrawStake { stake_ids: Vec<ObjectID> },
+    Stake {
+        validator: SuiAddress,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sponsor: Option<SuiAddress>,
+    },
+    WithdrawStake {
+        stake_ids: Vec<ObjectID>,
+    },
+    Publish {
+        #[serde_as(as = "Vec<Base64>")]
+        modules: Vec<Vec<u8>>,
+        dependencies: Vec<ObjectID>,
+    },
+    MoveCall {
+        package: ObjectID,
+        module: String,
+        function: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        type_arguments: Vec<TypeTag>,
+        arguments: Vec<MoveCallArg>,
+    },
+    Mint {
+        treasury_cap: ObjectID,
+        #[serde_as(as = "DisplayFromStr")]
+        coin_type: TypeTag,
+        #[serde_as(as = "DisplayFromStr")]
+        amount: u64,
+        recipient: SuiAddress,
+    },
+}
+
+/// Argument of a [OperationMetadata::MoveCall], objects are resolved to their latest reference
+/// when the transaction is constructed.
+#[serde_as]
+#[derive(Deserialize, Serialize, Clone, Debug, Eq, PartialEq)]
+pub enum MoveCallArg {
+    Pure(#[serde_as(as = "Base64")] Vec<u8>),
+    Object(ObjectID),
 }
 
 impl Operation {
@@ -633,6 +1345,7 @@ impl Operation {
     ) -> Self {
         Operation {
             operation_identifier: Default::default(),
+            related_operations: vec![],
             type_: (&tx).into(),
             status,
             account: Some(sender.into()),
@@ -645,6 +1358,7 @@ impl Operation {
     pub fn genesis(index: u64, sender: SuiAddress, coin: GasCoin) -> Self {
         Operation {
             operation_identifier: index.into(),
+            related_operations: vec![],
             type_: OperationType::Genesis,
             status: Some(OperationStatus::Success),
             account: Some(sender.into()),
@@ -662,9 +1376,15 @@ impl Operation {
         }
     }
 
+    fn with_related_operations(mut self, related_operations: Vec<OperationIdentifier>) -> Self {
+        self.related_operations = related_operations;
+        self
+    }
+
     fn pay_sui(status: Option<OperationStatus>, address: SuiAddress, amount: i128) -> Self {
         Operation {
             operation_identifier: Default::default(),
+            related_operations: vec![],
             type_: OperationType::PaySui,
             status,
             account: Some(address.into()),
@@ -674,9 +1394,76 @@ impl Operation {
         }
     }
 
+    fn publish(sender: SuiAddress, modules: Vec<Vec<u8>>, dependencies: Vec<ObjectID>) -> Self {
+        Operation {
+            operation_identifier: Default::default(),
+            related_operations: vec![],
+            type_: OperationType::Publish,
+            status: None,
+            account: Some(sender.into()),
+            amount: None,
+            coin_change: None,
+            metadata: Some(OperationMetadata::Publish {
+                modules,
+                dependencies,
+            }),
+        }
+    }
+
+    fn move_call(
+        sender: SuiAddress,
+        package: ObjectID,
+        module: String,
+        function: String,
+        type_arguments: Vec<TypeTag>,
+        arguments: Vec<MoveCallArg>,
+    ) -> Self {
+        Operation {
+            operation_identifier: Default::default(),
+            related_operations: vec![],
+            type_: OperationType::MoveCall,
+            status: None,
+            account: Some(sender.into()),
+            amount: None,
+            coin_change: None,
+            metadata: Some(OperationMetadata::MoveCall {
+                package,
+                module,
+                function,
+                type_arguments,
+                arguments,
+            }),
+        }
+    }
+
+    fn mint(
+        sender: SuiAddress,
+        treasury_cap: ObjectID,
+        coin_type: TypeTag,
+        amount: u64,
+        recipient: SuiAddress,
+    ) -> Self {
+        Operation {
+            operation_identifier: Default::default(),
+            related_operations: vec![],
+            type_: OperationType::Mint,
+            status: None,
+            account: Some(sender.into()),
+            amount: None,
+            coin_change: None,
+            metadata: Some(OperationMetadata::Mint {
+                treasury_cap,
+                coin_type,
+                amount,
+                recipient,
+            }),
+        }
+    }
+
     fn balance_change(status: Option<OperationStatus>, addr: SuiAddress, amount: i128) -> Self {
         Self {
             operation_identifier: Default::default(),
+            related_operations: vec![],
             type_: OperationType::SuiBalanceChange,
             status,
             account: Some(addr.into()),
@@ -688,6 +1475,7 @@ impl Operation {
     fn gas(addr: SuiAddress, amount: i128) -> Self {
         Self {
             operation_identifier: Default::default(),
+            related_operations: vec![],
             type_: OperationType::Gas,
             status: Some(OperationStatus::Success),
             account: Some(addr.into()),
@@ -699,6 +1487,7 @@ impl Operation {
     fn stake_reward(status: Option<OperationStatus>, addr: SuiAddress, amount: i128) -> Self {
         Self {
             operation_identifier: Default::default(),
+            related_operations: vec![],
             type_: OperationType::StakeReward,
             status,
             account: Some(addr.into()),
@@ -710,6 +1499,7 @@ impl Operation {
     fn stake_principle(status: Option<OperationStatus>, addr: SuiAddress, amount: i128) -> Self {
         Self {
             operation_identifier: Default::default(),
+            related_operations: vec![],
             type_: OperationType::StakePrinciple,
             status,
             account: Some(addr.into()),
@@ -244,11 +244,13 @@ impl CheckpointBlockProvider {
                         .with_events(),
                 )
                 .await?;
+            let tx_digest = tx.digest;
+            let operations = Operations::try_from(tx)?;
             transactions.push(Transaction {
-                transaction_identifier: TransactionIdentifier { hash: tx.digest },
-                operations: Operations::try_from(tx)?,
+                transaction_identifier: TransactionIdentifier { hash: tx_digest },
+                metadata: operations.metadata(),
+                operations,
                 related_transactions: vec![],
-                metadata: None,
             })
         }
 
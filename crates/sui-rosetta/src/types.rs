@@ -1,24 +1,31 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Debug;
 use std::str::FromStr;
 
 use axum::response::{IntoResponse, Response};
 use axum::Json;
-use fastcrypto::encoding::Hex;
+use fastcrypto::encoding::{Base64, Hex};
 use fastcrypto::traits::ToFromBytes;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::TypeTag;
 use serde::de::Error as DeError;
 use serde::{Deserialize, Serializer};
 use serde::{Deserializer, Serialize};
 use serde_json::Value;
+use serde_with::serde_as;
 use strum_macros::EnumIter;
 use strum_macros::EnumString;
 
-use sui_sdk::rpc_types::{SuiExecutionStatus, SuiTransactionKind};
+use sui_sdk::rpc_types::{SuiExecutionStatus, SuiObjectDataOptions, SuiTransactionKind};
+use sui_sdk::SuiClient;
 use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress, TransactionDigest};
+use sui_types::coin::{COIN_MINT_AND_TRANSFER_FUNC_NAME, COIN_MODULE_NAME};
 use sui_types::crypto::PublicKey as SuiPublicKey;
 use sui_types::crypto::SignatureScheme;
+use sui_types::error::SuiError;
 use sui_types::governance::{ADD_STAKE_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
 use sui_types::messages::{Argument, CallArg, Command, ObjectArg, TransactionData};
 use sui_types::messages_checkpoint::CheckpointDigest;
@@ -29,7 +36,7 @@ use sui_types::{
 };
 
 use crate::errors::{Error, ErrorType};
-use crate::operations::Operations;
+use crate::operations::{module_dependencies, MoveCallArg, Operations};
 use crate::SUI;
 
 pub type BlockHeight = u64;
@@ -102,6 +109,24 @@ impl From<SuiAddress> for AccountIdentifier {
 pub struct Currency {
     pub symbol: String,
     pub decimals: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<CurrencyMetadata>,
+}
+
+/// Identifies the coin type of a currency other than SUI.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct CurrencyMetadata {
+    pub coin_type: String,
+}
+
+/// Fully qualified form of a coin type, with zero-padded addresses, as used by the coin indexes
+/// of the server. Coin types should be compared in this form, `0x2::sui::SUI` and the same type
+/// with a full length address are the same coin.
+pub fn canonical_coin_type(tag: &TypeTag) -> String {
+    match tag {
+        TypeTag::Struct(tag) => tag.to_canonical_string(),
+        tag => tag.to_string(),
+    }
 }
 #[derive(Serialize, Deserialize)]
 pub struct AccountBalanceRequest {
@@ -398,6 +423,9 @@ pub enum OperationType {
     PaySui,
     Stake,
     WithdrawStake,
+    Publish,
+    MoveCall,
+    Mint,
     // All other Sui transaction types, readonly
     EpochChange,
     Genesis,
@@ -551,6 +579,11 @@ pub struct ConstructionPreprocessResponse {
 #[derive(Serialize, Deserialize, Debug)]
 pub struct MetadataOptions {
     pub internal_operation: InternalOperation,
+    /// How the gas payment is picked among the coins of the metadata. Preprocess leaves it to
+    /// the default, clients that want another preference set it in the options they pass to
+    /// /construction/metadata.
+    #[serde(default)]
+    pub gas_preference: GasPreference,
 }
 
 impl IntoResponse for ConstructionPreprocessResponse {
@@ -588,6 +621,97 @@ pub struct ConstructionMetadata {
     pub total_coin_value: u64,
     pub gas_price: u64,
     pub budget: u64,
+    /// Coins paid out by a PaySui in currencies other than SUI, by coin type.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub currency_coins: BTreeMap<String, Vec<ObjectRef>>,
+    /// Balances of `coins`, to pick the gas payment by size.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub coin_balances: BTreeMap<ObjectID, u64>,
+    #[serde(default)]
+    pub gas_preference: GasPreference,
+}
+
+/// Which of the coins of the metadata pay for gas, and for the SUI spent by the transaction.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, Eq, PartialEq)]
+pub enum GasPreference {
+    /// All the coins selected when the metadata was fetched.
+    #[default]
+    Selected,
+    /// The smallest coins covering the amount needed, to keep large coins intact.
+    SmallestFirst,
+    /// The largest coins covering the amount needed, to use as few coins as possible.
+    LargestFirst,
+    /// Only the given coin.
+    Specific(ObjectID),
+}
+
+impl ConstructionMetadata {
+    /// Coins to pay for gas with, chosen among `coins` according to the gas preference. `amount`
+    /// is what the gas coins have to cover, budget included, None if all of them are needed.
+    pub fn gas_payment(&self, amount: Option<u64>) -> Result<Vec<ObjectRef>, Error> {
+        let by_size = match &self.gas_preference {
+            GasPreference::Selected => return Ok(self.coins.clone()),
+            GasPreference::Specific(id) => {
+                return self
+                    .coins
+                    .iter()
+                    .find(|(coin_id, ..)| coin_id == id)
+                    .map(|coin| vec![*coin])
+                    .ok_or_else(|| Error::MissingInput(format!("Object reference of [{id}]")))
+            }
+            by_size => by_size,
+        };
+        let Some(amount) = amount else {
+            return Ok(self.coins.clone());
+        };
+        let balance = |(id, ..): &ObjectRef| self.coin_balances.get(id).copied().unwrap_or(0);
+        let mut coins = self.coins.clone();
+        coins.sort_by_key(balance);
+        if by_size == &GasPreference::LargestFirst {
+            coins.reverse();
+        }
+        let mut total = 0u128;
+        Ok(coins
+            .into_iter()
+            .take_while(|coin| {
+                let take = total < amount as u128;
+                total += balance(coin) as u128;
+                take
+            })
+            .collect())
+    }
+
+    /// Re-fetch the latest references of the coins and objects, so that a transaction built
+    /// long after the metadata was fetched doesn't use outdated versions. Returns the ids of the
+    /// objects whose reference changed.
+    pub async fn refresh_coins(&mut self, client: &SuiClient) -> Result<Vec<ObjectID>, Error> {
+        let ids = self
+            .coins
+            .iter()
+            .chain(&self.objects)
+            .map(|(id, _, _)| *id)
+            .collect::<Vec<_>>();
+        let latest = client
+            .read_api()
+            .multi_get_object_with_options(ids, SuiObjectDataOptions::default())
+            .await?
+            .into_iter()
+            .map(|object| object.into_object().map(|o| (o.object_id, o.object_ref())))
+            .collect::<Result<HashMap<_, _>, _>>()
+            .map_err(SuiError::from)?;
+
+        let mut changed = vec![];
+        for oref in self.coins.iter_mut().chain(self.objects.iter_mut()) {
+            let latest = latest
+                .get(&oref.0)
+                .ok_or_else(|| Error::DataError(format!("Cannot find object [{}]", oref.0)))?;
+            if *oref != *latest {
+                *oref = *latest;
+                changed.push(oref.0);
+            }
+        }
+        Ok(changed)
+    }
 }
 
 impl IntoResponse for ConstructionMetadataResponse {
@@ -831,23 +955,78 @@ pub struct PrefundedAccount {
     pub currency: Currency,
 }
 
+#[serde_as]
 #[derive(Serialize, Deserialize, Debug)]
 pub enum InternalOperation {
     PaySui {
         sender: SuiAddress,
         recipients: Vec<SuiAddress>,
         amounts: Vec<u64>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        coin_payments: Vec<CoinPayment>,
     },
     Stake {
         sender: SuiAddress,
         validator: SuiAddress,
         amount: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sponsor: Option<SuiAddress>,
     },
     WithdrawStake {
         sender: SuiAddress,
         #[serde(default, skip_serializing_if = "Vec::is_empty")]
         stake_ids: Vec<ObjectID>,
     },
+    Publish {
+        sender: SuiAddress,
+        #[serde_as(as = "Vec<Base64>")]
+        modules: Vec<Vec<u8>>,
+        dependencies: Vec<ObjectID>,
+    },
+    MoveCall {
+        sender: SuiAddress,
+        package: ObjectID,
+        module: String,
+        function: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        type_arguments: Vec<TypeTag>,
+        arguments: Vec<MoveCallArg>,
+    },
+    Mint {
+        sender: SuiAddress,
+        treasury_cap: ObjectID,
+        coin_type: TypeTag,
+        amount: u64,
+        recipient: SuiAddress,
+    },
+}
+
+/// Payments in a currency other than SUI, made alongside the SUI payments of a PaySui.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct CoinPayment {
+    pub coin_type: String,
+    /// Decimals of the currency, as declared by the operations.
+    pub decimals: u64,
+    pub recipients: Vec<SuiAddress>,
+    pub amounts: Vec<u64>,
+}
+
+impl CoinPayment {
+    /// Check that the decimals declared for the currency match the metadata of the coin, so that
+    /// the amounts, which are in the coin's smallest unit, are scaled the way the client expects.
+    pub async fn check_decimals(&self, client: &SuiClient) -> Result<(), Error> {
+        let metadata = client
+            .coin_read_api()
+            .get_coin_metadata(self.coin_type.clone())
+            .await?;
+        if u64::from(metadata.decimals) != self.decimals {
+            return Err(Error::InvalidInput(format!(
+                "{} has {} decimals, got {}",
+                self.coin_type, metadata.decimals, self.decimals
+            )));
+        }
+        Ok(())
+    }
 }
 
 impl InternalOperation {
@@ -855,23 +1034,91 @@ impl InternalOperation {
         match self {
             InternalOperation::PaySui { sender, .. }
             | InternalOperation::Stake { sender, .. }
-            | InternalOperation::WithdrawStake { sender, .. } => *sender,
+            | InternalOperation::WithdrawStake { sender, .. }
+            | InternalOperation::Publish { sender, .. }
+            | InternalOperation::MoveCall { sender, .. }
+            | InternalOperation::Mint { sender, .. } => *sender,
+        }
+    }
+
+    /// Address paying for gas, if different from the sender.
+    pub fn sponsor(&self) -> Option<SuiAddress> {
+        match self {
+            InternalOperation::Stake { sponsor, .. } => *sponsor,
+            InternalOperation::PaySui { .. }
+            | InternalOperation::WithdrawStake { .. }
+            | InternalOperation::Publish { .. }
+            | InternalOperation::MoveCall { .. }
+            | InternalOperation::Mint { .. } => None,
         }
     }
     /// Combine with ConstructionMetadata to form the TransactionData
     pub fn try_into_data(self, metadata: ConstructionMetadata) -> Result<TransactionData, Error> {
+        let gas_owner = self.sponsor().unwrap_or(metadata.sender);
+        // SUI spent out of the gas coins besides gas, None if it is all of it.
+        let spent = match &self {
+            Self::PaySui { amounts, .. } => Some(
+                amounts
+                    .iter()
+                    .try_fold(0u64, |total, amount| total.checked_add(*amount))
+                    .ok_or_else(|| {
+                        Error::InvalidInput("Total amount paid overflows u64".to_string())
+                    })?,
+            ),
+            Self::Stake {
+                amount, sponsor, ..
+            } => sponsor.map_or(*amount, |_| Some(0)),
+            Self::WithdrawStake { .. }
+            | Self::Publish { .. }
+            | Self::MoveCall { .. }
+            | Self::Mint { .. } => Some(0),
+        };
+        // The budget is in gas units, the gas coins have to cover it at the gas price.
+        let needed = spent
+            .map(|spent: u64| {
+                metadata
+                    .budget
+                    .checked_mul(metadata.gas_price)
+                    .and_then(|gas| spent.checked_add(gas))
+                    .ok_or_else(|| {
+                        Error::InvalidInput(
+                            "Amount spent plus gas budget overflows u64".to_string(),
+                        )
+                    })
+            })
+            .transpose()?;
+        let gas_payment = metadata.gas_payment(needed)?;
         let pt = match self {
             Self::PaySui {
                 recipients,
                 amounts,
+                coin_payments,
                 ..
             } => {
                 let mut builder = ProgrammableTransactionBuilder::new();
                 builder.pay_sui(recipients, amounts)?;
+                // Other currencies are paid out of the sender's coins of that type.
+                for payment in coin_payments {
+                    let coins = metadata
+                        .currency_coins
+                        .get(&payment.coin_type)
+                        .cloned()
+                        .unwrap_or_default();
+                    if coins.is_empty() {
+                        return Err(Error::MissingInput(format!(
+                            "Coins of type {}",
+                            payment.coin_type
+                        )));
+                    }
+                    builder.pay(coins, payment.recipients, payment.amounts)?;
+                }
                 builder.finish()
             }
             InternalOperation::Stake {
-                validator, amount, ..
+                validator,
+                amount,
+                sponsor,
+                ..
             } => {
                 let mut builder = ProgrammableTransactionBuilder::new();
                 let system_state = CallArg::Object(ObjectArg::SharedObject {
@@ -888,12 +1135,36 @@ impl InternalOperation {
                     let state = builder.input(system_state)?;
                     (validator, state, amount)
                 } else {
-                    let amount = builder.pure(metadata.total_coin_value - metadata.budget)?;
+                    // A sponsored stake pays no gas out of the staker's coins.
+                    let total = if sponsor.is_some() {
+                        metadata.total_coin_value
+                    } else {
+                        metadata.total_coin_value - metadata.budget
+                    };
+                    let amount = builder.pure(total)?;
                     let state = builder.input(system_state)?;
                     let validator = builder.input(CallArg::Pure(bcs::to_bytes(&validator)?))?;
                     (validator, state, amount)
                 };
-                let coin = builder.command(Command::SplitCoin(Argument::GasCoin, amount));
+                // The gas coin belongs to the sponsor, the principal has to come from the
+                // staker's own coins.
+                let source = if sponsor.is_some() {
+                    let mut coins = metadata.objects.iter();
+                    let primary = coins
+                        .next()
+                        .ok_or_else(|| Error::MissingInput("Coins to stake from".to_string()))?;
+                    let primary = builder.obj(ObjectArg::ImmOrOwnedObject(*primary))?;
+                    let coins = coins
+                        .map(|coin| builder.obj(ObjectArg::ImmOrOwnedObject(*coin)))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if !coins.is_empty() {
+                        builder.command(Command::MergeCoins(primary, coins));
+                    }
+                    primary
+                } else {
+                    Argument::GasCoin
+                };
+                let coin = builder.command(Command::SplitCoin(source, amount));
 
                 let arguments = vec![system_state, coin, validator];
 
@@ -938,14 +1209,94 @@ impl InternalOperation {
                 }
                 builder.finish()
             }
+            InternalOperation::Publish {
+                modules,
+                dependencies,
+                ..
+            } => {
+                // Publish command does not carry the dependencies, make sure the declared
+                // dependencies agree with the ones referenced by the modules.
+                for dep in module_dependencies(&modules)? {
+                    if !dependencies.contains(&dep) {
+                        return Err(Error::InvalidInput(format!(
+                            "Module dependency [{dep}] is missing from publish dependencies."
+                        )));
+                    }
+                }
+                let mut builder = ProgrammableTransactionBuilder::new();
+                builder.publish_immutable(modules);
+                builder.finish()
+            }
+            InternalOperation::MoveCall {
+                package,
+                module,
+                function,
+                type_arguments,
+                arguments,
+                ..
+            } => {
+                // Object arguments are resolved to `metadata.objects`, in argument order.
+                let mut objects = metadata.objects.iter();
+                let call_args = arguments
+                    .into_iter()
+                    .map(|arg| match arg {
+                        MoveCallArg::Pure(bytes) => Ok(CallArg::Pure(bytes)),
+                        MoveCallArg::Object(id) => objects
+                            .next()
+                            .filter(|oref| oref.0 == id)
+                            .map(|oref| CallArg::Object(ObjectArg::ImmOrOwnedObject(*oref)))
+                            .ok_or_else(|| {
+                                Error::MissingInput(format!("Object reference of [{id}]"))
+                            }),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut builder = ProgrammableTransactionBuilder::new();
+                builder.move_call(
+                    package,
+                    Identifier::new(module)?,
+                    Identifier::new(function)?,
+                    type_arguments,
+                    call_args,
+                )?;
+                builder.finish()
+            }
+            InternalOperation::Mint {
+                treasury_cap,
+                coin_type,
+                amount,
+                recipient,
+                ..
+            } => {
+                let treasury_cap = metadata
+                    .objects
+                    .first()
+                    .filter(|oref| oref.0 == treasury_cap)
+                    .ok_or_else(|| {
+                        Error::MissingInput(format!("Object reference of [{treasury_cap}]"))
+                    })?;
+                let mut builder = ProgrammableTransactionBuilder::new();
+                builder.move_call(
+                    SUI_FRAMEWORK_OBJECT_ID,
+                    COIN_MODULE_NAME.to_owned(),
+                    COIN_MINT_AND_TRANSFER_FUNC_NAME.to_owned(),
+                    vec![coin_type],
+                    vec![
+                        CallArg::Object(ObjectArg::ImmOrOwnedObject(*treasury_cap)),
+                        CallArg::Pure(bcs::to_bytes(&amount)?),
+                        CallArg::Pure(bcs::to_bytes(&recipient)?),
+                    ],
+                )?;
+                builder.finish()
+            }
         };
 
-        Ok(TransactionData::new_programmable(
+        Ok(TransactionData::new_programmable_allow_sponsor(
             metadata.sender,
-            metadata.coins,
+            gas_payment,
             pt,
             metadata.budget,
             metadata.gas_price,
+            gas_owner,
         ))
     }
 }
@@ -1,39 +1,46 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::PathBuf;
 use std::str::FromStr;
 
 use anyhow::anyhow;
+use fastcrypto::encoding::{Base64, Encoding};
 use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::{StructTag, TypeTag};
 use rand::seq::{IteratorRandom, SliceRandom};
+use serde::Deserialize;
 use serde_json::json;
 use signature::rand_core::OsRng;
-use sui_json_rpc_types::SuiTransactionResponseOptions;
+use sui_json_rpc_types::{SuiObjectDataOptions, SuiTransactionResponseOptions};
+use sui_types::object::Owner;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 
-use crate::operations::Operations;
+use crate::construction::submit_dry_run;
+use crate::operations::{total_gas_across, OperationMetadata, Operations};
 use shared_crypto::intent::Intent;
 use sui_framework_build::compiled_package::BuildConfig;
-use sui_json_rpc_types::{ObjectChange, SuiObjectRef};
+use sui_json_rpc_types::{filter_created, ObjectChange};
 use sui_keys::keystore::AccountKeystore;
 use sui_keys::keystore::Keystore;
 use sui_sdk::rpc_types::{
-    OwnedObjectRef, SuiData, SuiExecutionStatus, SuiObjectDataOptions, SuiTransactionEffectsAPI,
-    SuiTransactionResponse,
+    OwnedObjectRef, SuiExecutionStatus, SuiTransactionEffectsAPI, SuiTransactionResponse,
 };
 use sui_sdk::SuiClient;
 use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
-use sui_types::gas_coin::GasCoin;
+use sui_types::error::UserInputError;
+use sui_types::gas_coin::GAS;
+use sui_types::governance::StakedSui;
 use sui_types::messages::{
     CallArg, ExecuteTransactionRequestType, InputObjectKind, ObjectArg, ProgrammableTransaction,
     Transaction, TransactionData, TransactionDataAPI, TransactionKind, DUMMY_GAS_PRICE,
 };
+use sui_types::SUI_FRAMEWORK_ADDRESS;
 use test_utils::network::TestClusterBuilder;
 
 use crate::state::extract_balance_changes_from_ops;
-use crate::types::ConstructionMetadata;
+use crate::types::{ConstructionMetadata, InternalOperation, OperationType};
 
 #[tokio::test]
 async fn test_transfer_sui() {
@@ -49,7 +56,7 @@ async fn test_transfer_sui() {
         builder.transfer_sui(recipient, Some(50000));
         builder.finish()
     };
-    test_transaction(
+    let response = test_transaction(
         &client,
         keystore,
         vec![recipient],
@@ -58,8 +65,100 @@ async fn test_transfer_sui() {
         vec![],
         10000,
         false,
+        None,
     )
     .await;
+    assert_eq!(
+        format!("Transfer 0.00005 SUI to {recipient}"),
+        response.summarize()
+    );
+}
+
+#[tokio::test]
+async fn test_transfer_sui_remapped_recipient() {
+    let network = TestClusterBuilder::new().build().await.unwrap();
+    let client = network.wallet.get_client().await.unwrap();
+    let keystore = &network.wallet.config.keystore;
+
+    // Clone a Transfer Sui transaction with a different recipient
+    let sender = get_random_address(&network.accounts, vec![]);
+    let recipient = get_random_address(&network.accounts, vec![sender]);
+    let new_recipient = get_random_address(&network.accounts, vec![sender, recipient]);
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.transfer_sui(recipient, Some(50000));
+        builder.finish()
+    };
+
+    // The recipient is the first input.
+    assert_eq!(
+        UserInputError::PureInputSizeMismatch {
+            index: 0,
+            expected: 32,
+            actual: 8
+        },
+        pt.remap_pure_input(0, bcs::to_bytes(&1u64).unwrap())
+            .unwrap_err()
+    );
+    assert_eq!(
+        UserInputError::InputIndexOutOfBounds { index: 2, len: 2 },
+        pt.remap_pure_input(2, bcs::to_bytes(&new_recipient).unwrap())
+            .unwrap_err()
+    );
+    let remapped = pt
+        .remap_pure_input(0, bcs::to_bytes(&new_recipient).unwrap())
+        .unwrap();
+    assert_eq!(pt.commands, remapped.commands);
+
+    test_transaction(
+        &client,
+        keystore,
+        vec![new_recipient],
+        sender,
+        remapped,
+        vec![],
+        10000,
+        false,
+        None,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_submit_dry_run() {
+    let network = TestClusterBuilder::new().build().await.unwrap();
+    let client = network.wallet.get_client().await.unwrap();
+    let keystore = &network.wallet.config.keystore;
+
+    let sender = get_random_address(&network.accounts, vec![]);
+    let recipient = get_random_address(&network.accounts, vec![sender]);
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.transfer_sui(recipient, Some(50000));
+        builder.finish()
+    };
+    let gas = get_random_sui(&client, sender, vec![]).await;
+    let data = TransactionData::new_programmable(sender, vec![gas], pt, 10000, DUMMY_GAS_PRICE);
+
+    // A valid transaction passes, nothing is committed.
+    let signature = keystore
+        .sign_secure(&sender, &data, Intent::default())
+        .unwrap();
+    let tx = Transaction::from_data(data.clone(), Intent::default(), vec![signature]);
+    let balance = get_balance(&client, recipient, &GAS::type_tag()).await;
+    let digest = submit_dry_run(&client, tx.clone()).await.unwrap();
+    assert_eq!(*tx.digest(), digest);
+    assert_eq!(
+        balance,
+        get_balance(&client, recipient, &GAS::type_tag()).await
+    );
+
+    // A signature from another key is rejected.
+    let signature = keystore
+        .sign_secure(&recipient, &data, Intent::default())
+        .unwrap();
+    let tx = Transaction::from_data(data, Intent::default(), vec![signature]);
+    assert!(submit_dry_run(&client, tx).await.is_err());
 }
 
 #[tokio::test]
@@ -85,6 +184,7 @@ async fn test_transfer_sui_whole_coin() {
         vec![],
         10000,
         false,
+        None,
     )
     .await;
 }
@@ -113,8 +213,167 @@ async fn test_transfer_object() {
         vec![],
         10000,
         false,
+        None,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_transfer_object_checked_not_owned() {
+    let network = TestClusterBuilder::new().build().await.unwrap();
+    let client = network.wallet.get_client().await.unwrap();
+
+    // Try to transfer an object owned by someone else
+    let sender = get_random_address(&network.accounts, vec![]);
+    let owner = get_random_address(&network.accounts, vec![sender]);
+    let object_ref = get_random_sui(&client, owner, vec![]).await;
+    let err = client
+        .transaction_builder()
+        .transfer_object_checked(sender, object_ref, None, 10000, sender)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<UserInputError>(),
+        Some(UserInputError::NotOwned { object_id, sender: s, owner: Some(Owner::AddressOwner(o)) })
+            if *object_id == object_ref.0 && *s == sender && *o == owner
+    ));
+}
+
+#[tokio::test]
+async fn test_split_then_merge() {
+    let network = TestClusterBuilder::new().build().await.unwrap();
+    let client = network.wallet.get_client().await.unwrap();
+    let keystore = &network.wallet.config.keystore;
+
+    let sender = get_random_address(&network.accounts, vec![]);
+    let recipient = get_random_address(&network.accounts, vec![sender]);
+    let coins = client
+        .read_api()
+        .get_gas_coins_sorted(sender, false)
+        .await
+        .unwrap();
+    let (source, source_balance) = coins[0];
+    let (target, target_balance) = coins[1];
+
+    // Splitting more than the source holds is rejected by the builder
+    let mut builder = ProgrammableTransactionBuilder::new();
+    assert!(builder
+        .split_then_merge(source, source_balance, source_balance + 1, target)
+        .is_err());
+
+    // Send a split off amount to the recipient and consolidate the rest into the target
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let coin = builder
+            .split_then_merge(source, source_balance, 1000, target)
+            .unwrap();
+        builder.transfer_arg(recipient, coin);
+        builder.finish()
+    };
+    let response = test_transaction(
+        &client,
+        keystore,
+        vec![recipient],
+        sender,
+        pt,
+        vec![],
+        10000,
+        false,
+        None,
     )
     .await;
+
+    let effects = response.effects.unwrap();
+    assert!(effects
+        .deleted()
+        .iter()
+        .any(|deleted| deleted.object_id == source.0));
+    let coins = client
+        .read_api()
+        .get_gas_coins_sorted(sender, false)
+        .await
+        .unwrap();
+    let (_, new_target_balance) = coins.iter().find(|(coin, _)| coin.0 == target.0).unwrap();
+    assert_eq!(target_balance + source_balance - 1000, *new_target_balance);
+}
+
+#[tokio::test]
+async fn test_total_gas_across_transfers() {
+    let network = TestClusterBuilder::new().build().await.unwrap();
+    let client = network.wallet.get_client().await.unwrap();
+    let keystore = &network.wallet.config.keystore;
+
+    let mut ops = vec![];
+    let mut expected_gas = 0;
+    for _ in 0..3 {
+        let sender = get_random_address(&network.accounts, vec![]);
+        let recipient = get_random_address(&network.accounts, vec![sender]);
+        let pt = {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            builder.transfer_sui(recipient, Some(50000));
+            builder.finish()
+        };
+        let response = test_transaction(
+            &client,
+            keystore,
+            vec![recipient],
+            sender,
+            pt,
+            vec![],
+            10000,
+            false,
+            None,
+        )
+        .await;
+        let gas_used = response.effects.as_ref().unwrap().gas_used();
+        let gas = gas_used.storage_rebate as i128
+            - gas_used.storage_cost as i128
+            - gas_used.computation_cost as i128;
+        expected_gas += gas.abs();
+
+        let tx_ops: Operations = response.try_into().unwrap();
+        assert_eq!(gas.abs(), tx_ops.total_gas());
+        ops.push(tx_ops);
+    }
+    assert_eq!(expected_gas, total_gas_across(&ops));
+}
+
+#[tokio::test]
+async fn test_gas_net_change_on_transfer() {
+    let network = TestClusterBuilder::new().build().await.unwrap();
+    let client = network.wallet.get_client().await.unwrap();
+    let keystore = &network.wallet.config.keystore;
+
+    // Transferring part of a coin creates a new coin object, incurring a storage cost.
+    let sender = get_random_address(&network.accounts, vec![]);
+    let recipient = get_random_address(&network.accounts, vec![sender]);
+    let amount = 50000;
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.transfer_sui(recipient, Some(amount));
+        builder.finish()
+    };
+    let balance_before = get_balance(&client, sender, &GAS::type_tag()).await;
+    let response = test_transaction(
+        &client,
+        keystore,
+        vec![recipient],
+        sender,
+        pt,
+        vec![],
+        10000,
+        false,
+        None,
+    )
+    .await;
+    let balance_after = get_balance(&client, sender, &GAS::type_tag()).await;
+
+    let gas_used = response.effects.as_ref().unwrap().gas_used();
+    assert!(gas_used.storage_cost > 0);
+    assert_eq!(
+        balance_before as i128 - balance_after as i128,
+        amount as i128 + gas_used.net_change()
+    );
 }
 
 #[tokio::test]
@@ -142,45 +401,542 @@ async fn test_publish_and_move_call() {
         builder.publish_immutable(compiled_module);
         builder.finish()
     };
-    let response =
-        test_transaction(&client, keystore, vec![], sender, pt, vec![], 10000, false).await;
-    let object_changes = response.object_changes.unwrap();
+    let response = test_transaction(
+        &client,
+        keystore,
+        vec![],
+        sender,
+        pt,
+        vec![],
+        10000,
+        false,
+        None,
+    )
+    .await;
+    let object_changes = response.object_changes.clone().unwrap();
 
     // Test move call (reuse published module from above test)
+    let (package, modules) = object_changes
+        .iter()
+        .find_map(ObjectChange::as_published)
+        .unwrap();
+    let package = &package.0;
+    assert!(modules.contains(&"managed".to_string()));
+    assert_eq!(Some(*package), response.published_package_id().unwrap());
+    // The gas coin is the only mutated object, the module initializer creates the TreasuryCap.
+    let mutated = object_changes
+        .iter()
+        .filter_map(ObjectChange::as_mutated)
+        .collect::<Vec<_>>();
+    assert_eq!(1, mutated.len());
+    assert_eq!(&Owner::AddressOwner(sender), mutated[0].0);
+    assert!(filter_created(&object_changes)
+        .iter()
+        .any(|(_, object_type, _)| object_type.name.as_str() == "TreasuryCap"));
+
+    // Publishing registers the MANAGED currency, which emits a `CurrencyCreated` event.
+    #[derive(Deserialize)]
+    struct CurrencyCreated {
+        decimals: u8,
+    }
+    let currency_created = StructTag {
+        address: SUI_FRAMEWORK_ADDRESS,
+        module: Identifier::from_str("coin").unwrap(),
+        name: Identifier::from_str("CurrencyCreated").unwrap(),
+        type_params: vec![TypeTag::Struct(Box::new(StructTag {
+            address: (*package).into(),
+            module: Identifier::from_str("managed").unwrap(),
+            name: Identifier::from_str("MANAGED").unwrap(),
+            type_params: vec![],
+        }))],
+    };
+    let events = response
+        .events_of_type::<CurrencyCreated>(&currency_created)
+        .unwrap();
+    assert_eq!(1, events.len());
+    assert_eq!(2, events[0].decimals);
+
+    // TODO: Improve tx response to make it easier to find objects.
+    let treasury = find_module_object(&object_changes, "::TreasuryCap");
+    let treasury = treasury.clone().reference.to_object_ref();
+    let recipient = *network.accounts.choose(&mut OsRng::default()).unwrap();
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder
+            .move_call(
+                *package,
+                Identifier::from_str("managed").unwrap(),
+                Identifier::from_str("mint").unwrap(),
+                vec![],
+                vec![
+                    CallArg::Object(ObjectArg::ImmOrOwnedObject(treasury)),
+                    CallArg::Pure(bcs::to_bytes(&10000u64).unwrap()),
+                    CallArg::Pure(bcs::to_bytes(&recipient).unwrap()),
+                ],
+            )
+            .unwrap();
+        builder.finish()
+    };
+
+    let response = test_transaction(
+        &client,
+        keystore,
+        vec![],
+        sender,
+        pt,
+        vec![],
+        10000,
+        false,
+        None,
+    )
+    .await;
+    // Minting emits no `CurrencyCreated` events.
+    assert!(response
+        .events_of_type::<CurrencyCreated>(&currency_created)
+        .unwrap()
+        .is_empty());
+
+    // The amount and recipient of the mint are decoded with the types of `managed::mint`, the
+    // treasury cap is an object argument.
+    let abi = client
+        .read_api()
+        .get_normalized_move_modules_by_package(*package)
+        .await
+        .unwrap();
+    let args = response.decode_move_call_args(&abi).unwrap();
+    assert_eq!(2, args.len());
+    assert!(args.iter().all(|arg| arg.function == "mint"));
+    assert_eq!(1, args[0].parameter);
+    assert_eq!(json!("10000"), args[0].value);
+    assert_eq!(2, args[1].parameter);
+    assert_eq!(json!(recipient), args[1].value);
+
+    // The supply of MANAGED is what was minted, while SUI's is fixed.
+    let supply = client
+        .coin_read_api()
+        .get_total_supply(format!("{package}::managed::MANAGED"))
+        .await
+        .unwrap();
+    assert_eq!(10000, supply.value);
+    let supply = client
+        .coin_read_api()
+        .get_total_supply(GAS::type_().to_string())
+        .await
+        .unwrap();
+    assert!(supply.value > 0);
+}
+
+#[tokio::test]
+async fn test_move_call_operation() -> Result<(), anyhow::Error> {
+    let network = TestClusterBuilder::new().build().await.unwrap();
+    let client = network.wallet.get_client().await.unwrap();
+    let keystore = &network.wallet.config.keystore;
+
+    // Publish the fungible tokens example
+    let sender = get_random_address(&network.accounts, vec![]);
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../sui_programmability/examples/fungible_tokens");
+    let package = sui_framework::build_move_package(&path, BuildConfig::new_for_testing()).unwrap();
+    let compiled_module = package
+        .get_modules()
+        .map(|m| {
+            let mut module_bytes = Vec::new();
+            m.serialize(&mut module_bytes).unwrap();
+            module_bytes
+        })
+        .collect::<Vec<_>>();
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.publish_immutable(compiled_module);
+        builder.finish()
+    };
+    let response = test_transaction(
+        &client,
+        keystore,
+        vec![],
+        sender,
+        pt,
+        vec![],
+        10000,
+        false,
+        None,
+    )
+    .await;
+    let object_changes = response.object_changes.unwrap();
     let package = object_changes
         .iter()
         .find_map(|change| {
             if let ObjectChange::Published { package_id, .. } = change {
-                Some(package_id)
+                Some(*package_id)
             } else {
                 None
             }
         })
         .unwrap();
+    let treasury = find_module_object(&object_changes, "::TreasuryCap")
+        .reference
+        .to_object_ref();
 
-    // TODO: Improve tx response to make it easier to find objects.
+    // Mint via a MoveCall operation
+    let recipient = get_random_address(&network.accounts, vec![sender]);
+    let ops: Operations = serde_json::from_value(json!(
+        [{
+            "operation_identifier":{"index":0},
+            "type":"MoveCall",
+            "account": { "address" : sender.to_string() },
+            "metadata": { "MoveCall" : {
+                "package": package.to_string(),
+                "module": "managed",
+                "function": "mint",
+                "arguments": [
+                    { "Object": treasury.0.to_string() },
+                    { "Pure": Base64::encode(bcs::to_bytes(&10000u64)?) },
+                    { "Pure": Base64::encode(bcs::to_bytes(&recipient)?) },
+                ]
+            }}
+        }]
+    ))?;
+    let gas = get_random_sui(&client, sender, vec![]).await;
+    let metadata = ConstructionMetadata {
+        sender,
+        coins: vec![gas],
+        objects: vec![treasury],
+        total_coin_value: 0,
+        gas_price: client.read_api().get_reference_gas_price().await?,
+        budget: 10000,
+        currency_coins: Default::default(),
+        coin_balances: Default::default(),
+        gas_preference: Default::default(),
+    };
+    let data = ops.clone().into_internal()?.try_into_data(metadata)?;
+    // The call shape survives the round trip.
+    assert_eq!(ops.with_sender(sender), Operations::try_from(data.clone())?);
+
+    let signature = keystore.sign_secure(&sender, &data, Intent::default())?;
+    let response = client
+        .quorum_driver()
+        .execute_transaction(
+            Transaction::from_data(data, Intent::default(), vec![signature]).verify()?,
+            SuiTransactionResponseOptions::full_content(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    assert_eq!(
+        SuiExecutionStatus::Success,
+        *response.effects.unwrap().status()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mint_operation() -> Result<(), anyhow::Error> {
+    let network = TestClusterBuilder::new().build().await.unwrap();
+    let client = network.wallet.get_client().await.unwrap();
+    let keystore = &network.wallet.config.keystore;
+
+    // Publish the fungible tokens example
+    let sender = get_random_address(&network.accounts, vec![]);
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../sui_programmability/examples/fungible_tokens");
+    let package = sui_framework::build_move_package(&path, BuildConfig::new_for_testing()).unwrap();
+    let compiled_module = package
+        .get_modules()
+        .map(|m| {
+            let mut module_bytes = Vec::new();
+            m.serialize(&mut module_bytes).unwrap();
+            module_bytes
+        })
+        .collect::<Vec<_>>();
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.publish_immutable(compiled_module);
+        builder.finish()
+    };
+    let response = test_transaction(
+        &client,
+        keystore,
+        vec![],
+        sender,
+        pt,
+        vec![],
+        10000,
+        false,
+        None,
+    )
+    .await;
+    let object_changes = response.object_changes.unwrap();
+    let package = object_changes
+        .iter()
+        .find_map(|change| {
+            if let ObjectChange::Published { package_id, .. } = change {
+                Some(*package_id)
+            } else {
+                None
+            }
+        })
+        .unwrap();
+    let treasury = find_module_object(&object_changes, "::TreasuryCap")
+        .reference
+        .to_object_ref();
+    let coin_type = TypeTag::from_str(&format!("{package}::managed::MANAGED"))?;
+
+    // Mint via a Mint operation
+    let recipient = get_random_address(&network.accounts, vec![sender]);
+    let ops: Operations = serde_json::from_value(json!(
+        [{
+            "operation_identifier":{"index":0},
+            "type":"Mint",
+            "account": { "address" : sender.to_string() },
+            "metadata": { "Mint" : {
+                "treasury_cap": treasury.0.to_string(),
+                "coin_type": coin_type.to_string(),
+                "amount": "10000",
+                "recipient": recipient.to_string(),
+            }}
+        }]
+    ))?;
+    let gas = get_random_sui(&client, sender, vec![]).await;
+    let metadata = ConstructionMetadata {
+        sender,
+        coins: vec![gas],
+        objects: vec![treasury],
+        total_coin_value: 0,
+        gas_price: client.read_api().get_reference_gas_price().await?,
+        budget: 10000,
+        currency_coins: Default::default(),
+        coin_balances: Default::default(),
+        gas_preference: Default::default(),
+    };
+    let data = ops.clone().into_internal()?.try_into_data(metadata)?;
+    // The amount and recipient survive the round trip.
+    assert_eq!(ops.with_sender(sender), Operations::try_from(data.clone())?);
+
+    let signature = keystore.sign_secure(&sender, &data, Intent::default())?;
+    let response = client
+        .quorum_driver()
+        .execute_transaction(
+            Transaction::from_data(data, Intent::default(), vec![signature]).verify()?,
+            SuiTransactionResponseOptions::full_content(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    assert_eq!(
+        SuiExecutionStatus::Success,
+        *response.effects.unwrap().status()
+    );
+    assert_eq!(10000, get_balance(&client, recipient, &coin_type).await);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_transfer_custom_coin() {
+    let network = TestClusterBuilder::new().build().await.unwrap();
+    let client = network.wallet.get_client().await.unwrap();
+    let keystore = &network.wallet.config.keystore;
+
+    // Publish the fungible tokens example
+    let sender = get_random_address(&network.accounts, vec![]);
+    let recipient = get_random_address(&network.accounts, vec![sender]);
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../sui_programmability/examples/fungible_tokens");
+    let package = sui_framework::build_move_package(&path, BuildConfig::new_for_testing()).unwrap();
+    let compiled_module = package
+        .get_modules()
+        .map(|m| {
+            let mut module_bytes = Vec::new();
+            m.serialize(&mut module_bytes).unwrap();
+            module_bytes
+        })
+        .collect::<Vec<_>>();
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.publish_immutable(compiled_module);
+        builder.finish()
+    };
+    let response = test_transaction(
+        &client,
+        keystore,
+        vec![],
+        sender,
+        pt,
+        vec![],
+        10000,
+        false,
+        None,
+    )
+    .await;
+    let object_changes = response.object_changes.unwrap();
+    let package = object_changes
+        .iter()
+        .find_map(|change| {
+            if let ObjectChange::Published { package_id, .. } = change {
+                Some(*package_id)
+            } else {
+                None
+            }
+        })
+        .unwrap();
+    let coin_type = TypeTag::Struct(Box::new(StructTag {
+        address: package.into(),
+        module: Identifier::from_str("managed").unwrap(),
+        name: Identifier::from_str("MANAGED").unwrap(),
+        type_params: vec![],
+    }));
+
+    // Mint custom coin to the sender
     let treasury = find_module_object(&object_changes, "::TreasuryCap");
-    let treasury = treasury.clone().reference.to_object_ref();
-    let recipient = *network.accounts.choose(&mut OsRng::default()).unwrap();
+    let treasury = treasury.reference.to_object_ref();
     let pt = {
         let mut builder = ProgrammableTransactionBuilder::new();
         builder
             .move_call(
-                *package,
+                package,
                 Identifier::from_str("managed").unwrap(),
                 Identifier::from_str("mint").unwrap(),
                 vec![],
                 vec![
                     CallArg::Object(ObjectArg::ImmOrOwnedObject(treasury)),
                     CallArg::Pure(bcs::to_bytes(&10000u64).unwrap()),
-                    CallArg::Pure(bcs::to_bytes(&recipient).unwrap()),
+                    CallArg::Pure(bcs::to_bytes(&sender).unwrap()),
                 ],
             )
             .unwrap();
         builder.finish()
     };
+    let response = test_transaction(
+        &client,
+        keystore,
+        vec![],
+        sender,
+        pt,
+        vec![],
+        10000,
+        false,
+        Some(coin_type.clone()),
+    )
+    .await;
+
+    // Paying with a mix of SUI and custom coins should be rejected by the builder
+    let coin = find_module_object(&response.object_changes.unwrap(), "::coin::Coin<");
+    let sui_coin = get_random_sui(&client, sender, vec![]).await;
+    let err = client
+        .transaction_builder()
+        .pay(
+            sender,
+            vec![sui_coin.0, coin.reference.object_id],
+            vec![recipient],
+            vec![100],
+            None,
+            10000,
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<UserInputError>(),
+        Some(UserInputError::MixedCoinTypes { coin_types }) if coin_types.len() == 2
+    ));
+
+    // Transfer the minted coin, reconciling the custom coin balance instead of SUI
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder
+            .transfer_object(recipient, coin.reference.to_object_ref())
+            .unwrap();
+        builder.finish()
+    };
+    test_transaction(
+        &client,
+        keystore,
+        vec![recipient],
+        sender,
+        pt,
+        vec![],
+        10000,
+        false,
+        Some(coin_type),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_pay_sui_currency_decimals() -> Result<(), anyhow::Error> {
+    let network = TestClusterBuilder::new().build().await.unwrap();
+    let client = network.wallet.get_client().await.unwrap();
+    let keystore = &network.wallet.config.keystore;
+
+    let sender = get_random_address(&network.accounts, vec![]);
+    let recipient = get_random_address(&network.accounts, vec![sender]);
+    let path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/unit_tests/data/six_decimals_coin");
+    let package = sui_framework::build_move_package(&path, BuildConfig::new_for_testing()).unwrap();
+    let compiled_module = package
+        .get_modules()
+        .map(|m| {
+            let mut module_bytes = Vec::new();
+            m.serialize(&mut module_bytes).unwrap();
+            module_bytes
+        })
+        .collect::<Vec<_>>();
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.publish_immutable(compiled_module);
+        builder.finish()
+    };
+    let response = test_transaction(
+        &client,
+        keystore,
+        vec![],
+        sender,
+        pt,
+        vec![],
+        10000,
+        false,
+        None,
+    )
+    .await;
+    let package = response
+        .object_changes
+        .unwrap()
+        .iter()
+        .find_map(|change| {
+            if let ObjectChange::Published { package_id, .. } = change {
+                Some(*package_id)
+            } else {
+                None
+            }
+        })
+        .unwrap();
+    let coin_type = format!("{package}::usdc::USDC");
+
+    // 1.5 USDC, in the coin's smallest unit.
+    let ops = |decimals: u64| -> Operations {
+        let usdc = json!({
+            "symbol": "USDC", "decimals": decimals, "metadata": { "coin_type": coin_type }
+        });
+        serde_json::from_value(json!([
+            { "operation_identifier": { "index": 0 }, "type": "PaySui",
+              "account": { "address": recipient },
+              "amount": { "value": "1500000", "currency": usdc } },
+            { "operation_identifier": { "index": 1 }, "type": "PaySui",
+              "account": { "address": sender },
+              "amount": { "value": "-1500000", "currency": usdc } },
+        ]))
+        .unwrap()
+    };
+    let InternalOperation::PaySui { coin_payments, .. } = ops(6).into_internal()? else {
+        panic!("Expected a PaySui");
+    };
+    assert_eq!(6, coin_payments[0].decimals);
+    assert_eq!(vec![1_500_000], coin_payments[0].amounts);
+    coin_payments[0].check_decimals(&client).await?;
 
-    test_transaction(&client, keystore, vec![], sender, pt, vec![], 10000, false).await;
+    // Declaring the amounts with SUI's decimals doesn't match the coin.
+    let InternalOperation::PaySui { coin_payments, .. } = ops(9).into_internal()? else {
+        panic!("Expected a PaySui");
+    };
+    assert!(coin_payments[0].check_decimals(&client).await.is_err());
+
+    Ok(())
 }
 
 #[tokio::test]
@@ -201,7 +957,18 @@ async fn test_split_coin() {
         TransactionKind::ProgrammableTransaction(pt) => pt,
         _ => unreachable!(),
     };
-    test_transaction(&client, keystore, vec![], sender, pt, vec![], 10000, false).await;
+    test_transaction(
+        &client,
+        keystore,
+        vec![],
+        sender,
+        pt,
+        vec![],
+        10000,
+        false,
+        None,
+    )
+    .await;
 }
 
 #[tokio::test]
@@ -223,7 +990,18 @@ async fn test_merge_coin() {
         TransactionKind::ProgrammableTransaction(pt) => pt,
         _ => unreachable!(),
     };
-    test_transaction(&client, keystore, vec![], sender, pt, vec![], 10000, false).await;
+    test_transaction(
+        &client,
+        keystore,
+        vec![],
+        sender,
+        pt,
+        vec![],
+        10000,
+        false,
+        None,
+    )
+    .await;
 }
 
 #[tokio::test]
@@ -252,6 +1030,7 @@ async fn test_pay() {
         vec![],
         10000,
         false,
+        None,
     )
     .await;
 }
@@ -279,7 +1058,7 @@ async fn test_pay_multiple_coin_multiple_recipient() {
             .unwrap();
         builder.finish()
     };
-    test_transaction(
+    let response = test_transaction(
         &client,
         keystore,
         vec![recipient1, recipient2],
@@ -288,8 +1067,15 @@ async fn test_pay_multiple_coin_multiple_recipient() {
         vec![],
         10000,
         false,
+        None,
     )
     .await;
+
+    // The sender pays for the gas, so it is the only other affected address.
+    let expected = BTreeSet::from([sender, recipient1, recipient2]);
+    assert_eq!(expected, response.affected_addresses());
+    let ops = Operations::try_from(response).unwrap();
+    assert_eq!(expected, ops.affected_addresses());
 }
 
 #[tokio::test]
@@ -322,6 +1108,7 @@ async fn test_pay_sui_multiple_coin_same_recipient() {
         vec![coin1, coin2],
         10000,
         false,
+        None,
     )
     .await;
 }
@@ -354,6 +1141,7 @@ async fn test_pay_sui() {
         vec![coin1, coin2],
         10000,
         false,
+        None,
     )
     .await;
 }
@@ -377,7 +1165,7 @@ async fn test_failed_pay_sui() {
             .unwrap();
         builder.finish()
     };
-    test_transaction(
+    let response = test_transaction(
         &client,
         keystore,
         vec![],
@@ -386,8 +1174,88 @@ async fn test_failed_pay_sui() {
         vec![coin1, coin2],
         110,
         true,
+        None,
     )
     .await;
+    assert_only_gas_charged(&Operations::try_from(response).unwrap(), sender);
+}
+
+#[test]
+fn test_assert_only_gas_charged() {
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    // The payment of a failed transaction is reported, but not applied.
+    let ops: Operations = serde_json::from_value(json!([
+        { "operation_identifier": { "index": 0 }, "type": "PaySui", "status": "FAILURE",
+          "account": { "address": recipient },
+          "amount": { "value": "1000", "currency": { "symbol": "SUI", "decimals": 9 } } },
+        { "operation_identifier": { "index": 1 }, "type": "PaySui", "status": "FAILURE",
+          "account": { "address": sender },
+          "amount": { "value": "-1000", "currency": { "symbol": "SUI", "decimals": 9 } } },
+        { "operation_identifier": { "index": 2 }, "type": "Gas", "status": "SUCCESS",
+          "account": { "address": sender },
+          "amount": { "value": "-110", "currency": { "symbol": "SUI", "decimals": 9 } } },
+    ]))
+    .unwrap();
+    assert_only_gas_charged(&ops, sender);
+}
+
+#[test]
+#[should_panic]
+fn test_assert_only_gas_charged_with_payment() {
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let ops: Operations = serde_json::from_value(json!([
+        { "operation_identifier": { "index": 0 }, "type": "PaySui", "status": "SUCCESS",
+          "account": { "address": recipient },
+          "amount": { "value": "1000", "currency": { "symbol": "SUI", "decimals": 9 } } },
+        { "operation_identifier": { "index": 1 }, "type": "PaySui", "status": "SUCCESS",
+          "account": { "address": sender },
+          "amount": { "value": "-1000", "currency": { "symbol": "SUI", "decimals": 9 } } },
+        { "operation_identifier": { "index": 2 }, "type": "Gas", "status": "SUCCESS",
+          "account": { "address": sender },
+          "amount": { "value": "-110", "currency": { "symbol": "SUI", "decimals": 9 } } },
+    ]))
+    .unwrap();
+    assert_only_gas_charged(&ops, sender);
+}
+
+#[tokio::test]
+async fn test_pay_sui_insufficient_funds() {
+    let network = TestClusterBuilder::new().build().await.unwrap();
+    let client = network.wallet.get_client().await.unwrap();
+
+    let sender = get_random_address(&network.accounts, vec![]);
+    let recipient = get_random_address(&network.accounts, vec![sender]);
+    let coins = client
+        .read_api()
+        .get_gas_coins_sorted(sender, false)
+        .await
+        .unwrap();
+    let (coin1, balance1) = coins[0];
+    let (coin2, balance2) = coins[1];
+    let gas_price = client.read_api().get_reference_gas_price().await.unwrap();
+
+    // Paying the whole balance of the coins leaves nothing for gas.
+    let available = balance1 as u128 + balance2 as u128;
+    let err = client
+        .transaction_builder()
+        .pay_sui(
+            sender,
+            vec![coin1.0, coin2.0],
+            vec![recipient, recipient],
+            vec![balance1, balance2],
+            10000,
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        Some(&UserInputError::InsufficientFunds {
+            needed: available + 10000 * gas_price as u128,
+            available,
+        }),
+        err.downcast_ref::<UserInputError>()
+    );
 }
 
 #[tokio::test]
@@ -415,6 +1283,7 @@ async fn test_stake_sui() {
             Some(1000000),
             validator,
             None,
+            None,
             100000,
         )
         .await
@@ -423,7 +1292,229 @@ async fn test_stake_sui() {
         TransactionKind::ProgrammableTransaction(pt) => pt,
         _ => unreachable!(),
     };
-    test_transaction(&client, keystore, vec![], sender, pt, vec![], 10000, false).await;
+    let response = test_transaction(
+        &client,
+        keystore,
+        vec![],
+        sender,
+        pt,
+        vec![],
+        10000,
+        false,
+        None,
+    )
+    .await;
+    assert_eq!("Stake 0.001 SUI", response.summarize());
+    // Staking creates exactly one StakedSui, which is the one the response points to.
+    let staked_sui_created = filter_created(response.object_changes.as_ref().unwrap())
+        .into_iter()
+        .filter(|(_, object_type, _)| StakedSui::is_staked_sui(object_type))
+        .map(|(_, _, (id, ..))| id)
+        .collect::<Vec<_>>();
+    assert_eq!(1, staked_sui_created.len());
+    let stake = response.extract_staked_sui_id().unwrap();
+    assert_eq!(staked_sui_created[0], stake);
+
+    // The new stake is listed among all StakedSui objects, whoever owns them.
+    let mut staked_sui = vec![];
+    let mut cursor = None;
+    loop {
+        let page = client
+            .read_api()
+            .get_objects_by_type(StakedSui::type_(), None, cursor, Some(5))
+            .await
+            .unwrap();
+        staked_sui.extend(page.data.iter().map(|o| o.object().unwrap().object_id));
+        if !page.has_next_page {
+            break;
+        }
+        cursor = page.next_cursor;
+    }
+    assert!(staked_sui.contains(&stake));
+}
+
+#[tokio::test]
+async fn test_dry_run_stake_operations() {
+    let network = TestClusterBuilder::new().build().await.unwrap();
+    let client = network.wallet.get_client().await.unwrap();
+
+    let sender = get_random_address(&network.accounts, vec![]);
+    let coin = get_random_sui(&client, sender, vec![]).await;
+    let validator = client
+        .governance_api()
+        .get_latest_sui_system_state()
+        .await
+        .unwrap()
+        .active_validators[0]
+        .sui_address;
+    let gas_price = client
+        .governance_api()
+        .get_reference_gas_price()
+        .await
+        .unwrap();
+    let stake = |validator: SuiAddress| -> Operations {
+        serde_json::from_value(json!(
+            [{
+                "operation_identifier":{"index":0},
+                "type":"Stake",
+                "account": { "address" : sender.to_string() },
+                "amount" : { "value": "-1000000", "currency": { "symbol": "SUI", "decimals": 9}},
+                "metadata": { "Stake" : {"validator": validator.to_string()} }
+            }]
+        ))
+        .unwrap()
+    };
+    let metadata = || ConstructionMetadata {
+        sender,
+        coins: vec![coin],
+        objects: vec![],
+        total_coin_value: 0,
+        gas_price,
+        budget: 10000,
+        currency_coins: Default::default(),
+        coin_balances: Default::default(),
+        gas_preference: Default::default(),
+    };
+
+    let results = stake(validator).dry_run(&client, metadata()).await.unwrap();
+    assert!(results.error.is_none());
+    assert_eq!(SuiExecutionStatus::Success, *results.effects.status());
+
+    // Staking with an address that isn't a validator aborts, the error points at the operation.
+    let err = stake(SuiAddress::random_for_testing_only())
+        .dry_run(&client, metadata())
+        .await
+        .unwrap_err();
+    let message = err.to_string();
+    assert!(
+        message.starts_with("Operation 0 (Stake) failed"),
+        "{message}"
+    );
+}
+
+#[tokio::test]
+async fn test_dry_run_pay_sui_operations() {
+    let network = TestClusterBuilder::new().build().await.unwrap();
+    let client = network.wallet.get_client().await.unwrap();
+
+    let sender = get_random_address(&network.accounts, vec![]);
+    let recipient1 = get_random_address(&network.accounts, vec![sender]);
+    let recipient2 = get_random_address(&network.accounts, vec![sender, recipient1]);
+    let coin = get_random_sui(&client, sender, vec![]).await;
+    let gas_price = client
+        .governance_api()
+        .get_reference_gas_price()
+        .await
+        .unwrap();
+    // The second recipient is paid `amount`.
+    let pay = |amount: &str| -> Operations {
+        let sui = json!({ "symbol": "SUI", "decimals": 9 });
+        serde_json::from_value(json!([
+            { "operation_identifier": { "index": 0 }, "type": "PaySui",
+              "account": { "address": sender.to_string() },
+              "amount": { "value": "-1000", "currency": sui } },
+            { "operation_identifier": { "index": 1 }, "type": "PaySui",
+              "account": { "address": recipient1.to_string() },
+              "amount": { "value": "1000", "currency": sui } },
+            { "operation_identifier": { "index": 2 }, "type": "PaySui",
+              "account": { "address": recipient2.to_string() },
+              "amount": { "value": amount, "currency": sui } },
+        ]))
+        .unwrap()
+    };
+    let metadata = || ConstructionMetadata {
+        sender,
+        coins: vec![coin],
+        objects: vec![],
+        total_coin_value: 0,
+        gas_price,
+        budget: 10000,
+        currency_coins: Default::default(),
+        coin_balances: Default::default(),
+        gas_preference: Default::default(),
+    };
+    let results = pay("1000").dry_run(&client, metadata()).await.unwrap();
+    assert_eq!(SuiExecutionStatus::Success, *results.effects.status());
+
+    // An amount that doesn't fit in a u64 is rejected when building the transaction.
+    let message = pay("18446744073709551616")
+        .dry_run(&client, metadata())
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(
+        message.starts_with("Operation 2 (PaySui) failed"),
+        "{message}"
+    );
+
+    // Splitting more than the balance of the gas coin fails in the command paying the recipient.
+    let message = pay("18446744073709551615")
+        .dry_run(&client, metadata())
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(
+        message.starts_with("Operation 2 (PaySui) failed"),
+        "{message}"
+    );
+}
+
+#[tokio::test]
+async fn test_refresh_construction_metadata() {
+    let network = TestClusterBuilder::new().build().await.unwrap();
+    let client = network.wallet.get_client().await.unwrap();
+    let keystore = &network.wallet.config.keystore;
+
+    let sender = get_random_address(&network.accounts, vec![]);
+    let recipient = get_random_address(&network.accounts, vec![sender]);
+    let coin1 = get_random_sui(&client, sender, vec![]).await;
+    let coin2 = get_random_sui(&client, sender, vec![coin1.0]).await;
+    let mut metadata = ConstructionMetadata {
+        sender,
+        coins: vec![coin1, coin2],
+        objects: vec![],
+        total_coin_value: 0,
+        gas_price: DUMMY_GAS_PRICE,
+        budget: 10000,
+        currency_coins: Default::default(),
+        coin_balances: Default::default(),
+        gas_preference: Default::default(),
+    };
+
+    // Spending from the first coin advances its version.
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.transfer_sui(recipient, Some(50000));
+        builder.finish()
+    };
+    test_transaction(
+        &client,
+        keystore,
+        vec![recipient],
+        sender,
+        pt,
+        vec![coin1],
+        10000,
+        false,
+        None,
+    )
+    .await;
+
+    let changed = metadata.refresh_coins(&client).await.unwrap();
+    assert_eq!(vec![coin1.0], changed);
+    let latest = client
+        .read_api()
+        .get_object_with_options(coin1.0, SuiObjectDataOptions::new())
+        .await
+        .unwrap()
+        .into_object()
+        .unwrap()
+        .object_ref();
+    assert_eq!(vec![latest, coin2], metadata.coins);
+    assert!(latest.1 > coin1.1);
+
+    // Nothing changed since the last refresh.
+    assert!(metadata.refresh_coins(&client).await.unwrap().is_empty());
 }
 
 #[tokio::test]
@@ -451,6 +1542,7 @@ async fn test_stake_sui_with_none_amount() {
             None,
             validator,
             None,
+            None,
             100000,
         )
         .await
@@ -459,7 +1551,106 @@ async fn test_stake_sui_with_none_amount() {
         TransactionKind::ProgrammableTransaction(pt) => pt,
         _ => unreachable!(),
     };
-    test_transaction(&client, keystore, vec![], sender, pt, vec![], 10000, false).await;
+    test_transaction(
+        &client,
+        keystore,
+        vec![],
+        sender,
+        pt,
+        vec![],
+        10000,
+        false,
+        None,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_sponsored_stake_sui() {
+    let network = TestClusterBuilder::new().build().await.unwrap();
+    let client = network.wallet.get_client().await.unwrap();
+    let keystore = &network.wallet.config.keystore;
+
+    // Test Staking Sui with gas paid by a sponsor
+    let sender = get_random_address(&network.accounts, vec![]);
+    let sponsor = get_random_address(&network.accounts, vec![sender]);
+    let coin = get_random_sui(&client, sender, vec![]).await;
+    let validator = client
+        .governance_api()
+        .get_latest_sui_system_state()
+        .await
+        .unwrap()
+        .active_validators[0]
+        .sui_address;
+    let data = client
+        .transaction_builder()
+        .request_add_stake(
+            sender,
+            vec![coin.0],
+            Some(1000000),
+            validator,
+            Some(sponsor),
+            None,
+            100000,
+        )
+        .await
+        .unwrap();
+    assert_eq!(sponsor, data.gas_owner());
+
+    let sender_balance = get_balance(&client, sender, &GAS::type_tag()).await as i128;
+    let sponsor_balance = get_balance(&client, sponsor, &GAS::type_tag()).await as i128;
+
+    let signatures = vec![
+        keystore
+            .sign_secure(&sender, &data, Intent::default())
+            .unwrap(),
+        keystore
+            .sign_secure(&sponsor, &data, Intent::default())
+            .unwrap(),
+    ];
+    let response = client
+        .quorum_driver()
+        .execute_transaction(
+            Transaction::from_data(data, Intent::default(), signatures)
+                .verify()
+                .unwrap(),
+            SuiTransactionResponseOptions::full_content(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await
+        .unwrap();
+    let effects = response.effects.as_ref().unwrap();
+    assert_eq!(SuiExecutionStatus::Success, *effects.status());
+    let gas = effects.gas_used();
+    let gas_used =
+        gas.computation_cost as i128 + gas.storage_cost as i128 - gas.storage_rebate as i128;
+
+    // The staker is only debited the principal, the sponsor pays for gas.
+    let sender_change =
+        get_balance(&client, sender, &GAS::type_tag()).await as i128 - sender_balance;
+    let sponsor_change =
+        get_balance(&client, sponsor, &GAS::type_tag()).await as i128 - sponsor_balance;
+    assert_eq!(-1000000, sender_change);
+    assert_eq!(-gas_used, sponsor_change);
+
+    let ops: Operations = response.try_into().unwrap();
+    let stake_op = ops
+        .clone()
+        .into_iter()
+        .find(|op| op.type_ == OperationType::Stake)
+        .unwrap();
+    assert_eq!(
+        Some(OperationMetadata::Stake {
+            validator,
+            sponsor: Some(sponsor)
+        }),
+        stake_op.metadata
+    );
+    let balances_from_ops = extract_balance_changes_from_ops(ops);
+    assert_eq!(
+        HashMap::from([(sender, sender_change), (sponsor, sponsor_change)]),
+        balances_from_ops
+    );
 }
 
 #[tokio::test]
@@ -487,6 +1678,7 @@ async fn test_pay_all_sui() {
         vec![coin1, coin2],
         10000,
         false,
+        None,
     )
     .await;
 }
@@ -515,6 +1707,7 @@ async fn test_delegation_parsing() -> Result<(), anyhow::Error> {
         }]
     ))
     .unwrap();
+    ops.check_sign_conventions()?;
     let metadata = ConstructionMetadata {
         sender,
         coins: vec![gas],
@@ -522,38 +1715,23 @@ async fn test_delegation_parsing() -> Result<(), anyhow::Error> {
         total_coin_value: 0,
         gas_price: client.read_api().get_reference_gas_price().await?,
         budget: 10000,
+        currency_coins: Default::default(),
+        coin_balances: Default::default(),
+        gas_preference: Default::default(),
     };
     let parsed_data = ops.clone().into_internal()?.try_into_data(metadata)?;
-    assert_eq!(ops, Operations::try_from(parsed_data)?);
+    assert_eq!(ops.with_sender(sender), Operations::try_from(parsed_data)?);
 
     Ok(())
 }
 
 fn find_module_object(changes: &[ObjectChange], object_type_name: &str) -> OwnedObjectRef {
-    let mut results: Vec<_> = changes
-        .iter()
-        .filter_map(|change| {
-            if let ObjectChange::Created {
-                object_id,
-                object_type,
-                owner,
-                version,
-                digest,
-                ..
-            } = change
-            {
-                if object_type.to_string().contains(object_type_name) {
-                    return Some(OwnedObjectRef {
-                        owner: *owner,
-                        reference: SuiObjectRef {
-                            object_id: *object_id,
-                            version: *version,
-                            digest: *digest,
-                        },
-                    });
-                }
-            };
-            None
+    let mut results: Vec<_> = filter_created(changes)
+        .into_iter()
+        .filter(|(_, object_type, _)| object_type.to_string().contains(object_type_name))
+        .map(|(owner, _, object_ref)| OwnedObjectRef {
+            owner: *owner,
+            reference: object_ref.into(),
         })
         .collect();
     // Check that there is only one object found, and hence no ambiguity.
@@ -561,8 +1739,10 @@ fn find_module_object(changes: &[ObjectChange], object_type_name: &str) -> Owned
     results.pop().unwrap()
 }
 
-// Record current Sui balance of an address then execute the transaction,
+// Record current balance of an address then execute the transaction,
 // and compare the balance change reported by the event against the actual balance change.
+// `coin_type` defaults to SUI, other coin types are reconciled against the balance changes
+// in the response, as Rosetta operations only track SUI.
 async fn test_transaction(
     client: &SuiClient,
     keystore: &Keystore,
@@ -572,7 +1752,9 @@ async fn test_transaction(
     gas: Vec<ObjectRef>,
     budget: u64,
     expect_fail: bool,
+    coin_type: Option<TypeTag>,
 ) -> SuiTransactionResponse {
+    let coin_type = coin_type.unwrap_or_else(GAS::type_tag);
     let gas = if !gas.is_empty() {
         gas
     } else {
@@ -608,7 +1790,7 @@ async fn test_transaction(
     let mut addr_to_check = addr_to_check;
     addr_to_check.push(sender);
     for addr in addr_to_check {
-        balances.insert(addr, get_balance(client, addr).await);
+        balances.insert(addr, get_balance(client, addr, &coin_type).await);
     }
 
     let response = client
@@ -624,6 +1806,9 @@ async fn test_transaction(
         .map_err(|e| anyhow!("TX execution failed for {data:#?}, error : {e}"))
         .unwrap();
 
+    // The digest computed before signing is the one assigned by the network
+    assert_eq!(data.digest(), response.digest);
+
     let effects = response.effects.as_ref().unwrap();
 
     if !expect_fail {
@@ -640,13 +1825,27 @@ async fn test_transaction(
         ));
     }
 
-    let ops = response.clone().try_into().unwrap();
-    let balances_from_ops = extract_balance_changes_from_ops(ops);
+    let balances_from_ops = if coin_type == GAS::type_tag() {
+        let ops = response.clone().try_into().unwrap();
+        extract_balance_changes_from_ops(ops)
+    } else {
+        response.balance_changes.iter().flatten().fold(
+            HashMap::new(),
+            |mut changes, balance_change| {
+                if let Some(owner) = balance_change.address_owner() {
+                    if balance_change.coin_type == coin_type {
+                        *changes.entry(owner).or_default() += balance_change.amount;
+                    }
+                }
+                changes
+            },
+        )
+    };
 
     // get actual balance changed after transaction
     let mut actual_balance_change = HashMap::new();
     for (addr, balance) in balances {
-        let new_balance = get_balance(client, addr).await as i128;
+        let new_balance = get_balance(client, addr, &coin_type).await as i128;
         let balance_changed = new_balance - balance as i128;
         actual_balance_change.insert(addr, balance_changed);
     }
@@ -665,28 +1864,16 @@ async fn get_random_sui(
 ) -> ObjectRef {
     let coins = client
         .read_api()
-        .get_owned_objects(
-            sender,
-            Some(SuiObjectDataOptions::full_content()),
-            /* cursor */ None,
-            /* limit */ None,
-            /* at_checkpoint */ None,
-        )
+        .get_gas_coins_sorted(sender, false)
         .await
-        .unwrap()
-        .data;
-
-    let coin_resp = coins
-        .iter()
-        .filter(|object| {
-            let obj = object.object().unwrap();
-            obj.is_gas_coin() && !except.contains(&obj.object_id)
-        })
-        .choose(&mut OsRng::default())
         .unwrap();
 
-    let coin = coin_resp.object().unwrap();
-    (coin.object_id, coin.version, coin.digest)
+    coins
+        .into_iter()
+        .map(|(coin, _)| coin)
+        .filter(|coin| !except.contains(&coin.0))
+        .choose(&mut OsRng::default())
+        .unwrap()
 }
 
 fn get_random_address(addresses: &[SuiAddress], except: Vec<SuiAddress>) -> SuiAddress {
@@ -697,40 +1884,28 @@ fn get_random_address(addresses: &[SuiAddress], except: Vec<SuiAddress>) -> SuiA
         .unwrap()
 }
 
-async fn get_balance(client: &SuiClient, address: SuiAddress) -> u64 {
-    let coins = client
-        .read_api()
-        .get_owned_objects(
-            address,
-            Some(SuiObjectDataOptions::full_content()),
-            /* cursor */ None,
-            /* limit */ None,
-            /* at_checkpoint */ None,
-        )
+/// Assert that the only balance change applied by `ops` is the gas paid by `sender`, as is the
+/// case for a transaction that failed to execute.
+fn assert_only_gas_charged(ops: &Operations, sender: SuiAddress) {
+    let changes = extract_balance_changes_from_ops(ops.clone())
+        .into_iter()
+        .filter(|(_, amount)| *amount != 0)
+        .collect::<Vec<_>>();
+    let gas: i128 = ops
+        .clone()
+        .into_iter()
+        .filter(|op| op.type_ == OperationType::Gas)
+        .filter_map(|op| op.amount.map(|amount| amount.value))
+        .sum();
+    assert!(gas < 0, "Expected a gas charge, got {gas}");
+    assert_eq!(vec![(sender, gas)], changes);
+}
+
+async fn get_balance(client: &SuiClient, address: SuiAddress, coin_type: &TypeTag) -> u128 {
+    client
+        .coin_read_api()
+        .get_balance(address, Some(coin_type.to_string()))
         .await
         .unwrap()
-        .data;
-
-    let mut balance = 0u64;
-    for coin in coins {
-        let obj = coin.into_object().unwrap();
-        if obj.is_gas_coin() {
-            let object = client
-                .read_api()
-                .get_object_with_options(obj.object_id, SuiObjectDataOptions::new().with_bcs())
-                .await
-                .unwrap();
-            let coin: GasCoin = object
-                .into_object()
-                .unwrap()
-                .bcs
-                .unwrap()
-                .try_as_move()
-                .unwrap()
-                .deserialize()
-                .unwrap();
-            balance += coin.value()
-        }
-    }
-    balance
+        .total_balance
 }
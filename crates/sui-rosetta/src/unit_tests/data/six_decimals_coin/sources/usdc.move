@@ -0,0 +1,18 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// A coin with 6 decimals, to test currencies that aren't scaled like SUI.
+module six_decimals_coin::usdc {
+    use std::option;
+    use sui::coin;
+    use sui::transfer;
+    use sui::tx_context::{Self, TxContext};
+
+    struct USDC has drop {}
+
+    fun init(witness: USDC, ctx: &mut TxContext) {
+        let (treasury_cap, metadata) = coin::create_currency<USDC>(witness, 6, b"USDC", b"", b"", option::none(), ctx);
+        transfer::freeze_object(metadata);
+        transfer::transfer(treasury_cap, tx_context::sender(ctx))
+    }
+}
@@ -1,15 +1,29 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use fastcrypto::encoding::{Encoding, Hex};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use fastcrypto::encoding::{Base64, Encoding, Hex};
+use proptest::prelude::*;
+use serde_json::{json, Value};
 
 use shared_crypto::intent::IntentMessage;
+use sui_framework_build::compiled_package::BuildConfig;
 use sui_types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
-use sui_types::messages::TransactionData;
+use sui_types::gas_coin::GAS;
+use sui_types::messages::{
+    Argument, CallArg, Command, ObjectArg, TransactionData, TransactionDataAPI, TransactionKind,
+};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::{parse_sui_type_tag, MOVE_STDLIB_OBJECT_ID, SUI_FRAMEWORK_OBJECT_ID};
 
-use crate::operations::Operations;
-use crate::types::{ConstructionMetadata, OperationType};
+use crate::errors::Error;
+use crate::operations::{Operation, Operations};
+use crate::types::{
+    canonical_coin_type, CoinPayment, ConstructionMetadata, GasPreference, InternalOperation,
+    OperationType,
+};
 
 #[tokio::test]
 async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
@@ -38,12 +52,57 @@ async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
         total_coin_value: 0,
         gas_price: 1,
         budget: 1000,
+        currency_coins: Default::default(),
+        coin_balances: Default::default(),
+        gas_preference: Default::default(),
     };
     let parsed_data = ops.into_internal()?.try_into_data(metadata)?;
     assert_eq!(data, parsed_data);
 
     Ok(())
 }
+#[tokio::test]
+async fn test_net_of_pay_sui_group() -> Result<(), anyhow::Error> {
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let sender = SuiAddress::random_for_testing_only();
+    let recipients = [
+        SuiAddress::random_for_testing_only(),
+        SuiAddress::random_for_testing_only(),
+    ];
+
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder
+            .pay_sui(recipients.to_vec(), vec![10000, 20000])
+            .unwrap();
+        builder.finish()
+    };
+    let data = TransactionData::new_programmable_with_dummy_gas_price(sender, vec![gas], pt, 1000);
+    let ops: Operations = data.try_into()?;
+    // Gas is reported next to the PaySui operations, but is not part of the group.
+    let ops = ops
+        .into_iter()
+        .chain([Operation::gas(sender, -500)])
+        .collect::<Operations>();
+
+    // The group is the same whichever of its operations it is looked up from.
+    for index in 0..3 {
+        let net = ops.net_of_group(index);
+        assert_eq!(3, net.len());
+        assert_eq!(10000, net[&recipients[0]]);
+        assert_eq!(20000, net[&recipients[1]]);
+        assert_eq!(-30000, net[&sender]);
+        assert_eq!(0, net.values().sum::<i128>());
+    }
+    assert!(ops.net_of_group(3).is_empty());
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_shorter_bytearray_bug() {
     // Sometime CallArg::Pure(Vec<u8>) for u64 will serialise to 8 bytes array instead of 9 bytes (length + data), this is to test the work around until we fix it in Sui Json.
@@ -54,3 +113,446 @@ async fn test_shorter_bytearray_bug() {
     let op = Operations::try_from(data.value).unwrap();
     assert_eq!(OperationType::PaySui, op.type_().unwrap());
 }
+
+#[tokio::test]
+async fn test_publish_operation_parsing() -> Result<(), anyhow::Error> {
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let sender = SuiAddress::random_for_testing_only();
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../sui_programmability/examples/fungible_tokens");
+    let package = sui_framework::build_move_package(&path, BuildConfig::new_for_testing())?;
+    let modules = package
+        .get_modules()
+        .map(|m| {
+            let mut module_bytes = Vec::new();
+            m.serialize(&mut module_bytes).unwrap();
+            module_bytes
+        })
+        .collect::<Vec<_>>();
+
+    let ops: Operations = serde_json::from_value(json!(
+        [{
+            "operation_identifier":{"index":0},
+            "type":"Publish",
+            "account": { "address" : sender.to_string() },
+            "metadata": { "Publish" : {
+                "modules": modules.iter().map(Base64::encode).collect::<Vec<_>>(),
+                "dependencies": [MOVE_STDLIB_OBJECT_ID, SUI_FRAMEWORK_OBJECT_ID],
+            }}
+        }]
+    ))?;
+    let metadata = ConstructionMetadata {
+        sender,
+        coins: vec![gas],
+        objects: vec![],
+        total_coin_value: 0,
+        gas_price: 1,
+        budget: 10000,
+        currency_coins: Default::default(),
+        coin_balances: Default::default(),
+        gas_preference: Default::default(),
+    };
+    let parsed_data = ops.clone().into_internal()?.try_into_data(metadata)?;
+
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.publish_immutable(modules);
+        builder.finish()
+    };
+    let data = TransactionData::new_programmable(sender, vec![gas], pt, 10000, 1);
+    assert_eq!(data, parsed_data);
+    assert_eq!(ops.with_sender(sender), Operations::try_from(parsed_data)?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_operations_sender() -> Result<(), anyhow::Error> {
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.transfer_sui(recipient, Some(10000));
+        builder.finish()
+    };
+    let data = TransactionData::new_programmable_with_dummy_gas_price(sender, vec![gas], pt, 1000);
+
+    let ops = Operations::try_from(data)?;
+    assert_eq!(Some(sender), ops.sender());
+    assert_eq!(Some(json!({ "sender": sender })), ops.metadata());
+
+    Ok(())
+}
+
+#[test]
+fn test_unknown_operation_type_round_trip() -> Result<(), anyhow::Error> {
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let sui = json!({ "symbol": "SUI", "decimals": 9 });
+    let json = json!([
+        { "operation_identifier": { "index": 0 }, "type": "PaySui",
+          "account": { "address": recipient }, "amount": { "value": "1000", "currency": sui } },
+        { "operation_identifier": { "index": 1 }, "type": "TransferToObject",
+          "account": { "address": sender }, "metadata": { "object": ObjectID::random() } },
+        { "operation_identifier": { "index": 2 }, "type": "PaySui",
+          "account": { "address": sender }, "amount": { "value": "-1000", "currency": sui } },
+    ]);
+
+    let ops: Operations = serde_json::from_value(json.clone())?;
+    assert_eq!(json, serde_json::to_value(&ops)?);
+
+    let err = ops.into_internal().unwrap_err();
+    assert!(err.to_string().contains("TransferToObject"), "{err}");
+
+    Ok(())
+}
+
+#[test]
+fn test_multi_currency_pay_sui() -> Result<(), anyhow::Error> {
+    let random_ref = || {
+        (
+            ObjectID::random(),
+            SequenceNumber::new(),
+            ObjectDigest::random(),
+        )
+    };
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let coin_type = format!("{}::managed::MANAGED", ObjectID::random());
+    let sui = json!({ "symbol": "SUI", "decimals": 9 });
+    let managed =
+        json!({ "symbol": "MANAGED", "decimals": 2, "metadata": { "coin_type": coin_type } });
+    let ops: Operations = serde_json::from_value(json!([
+        { "operation_identifier": { "index": 0 }, "type": "PaySui",
+          "account": { "address": recipient }, "amount": { "value": "1000", "currency": sui } },
+        { "operation_identifier": { "index": 1 }, "type": "PaySui",
+          "account": { "address": recipient }, "amount": { "value": "30", "currency": managed } },
+        { "operation_identifier": { "index": 2 }, "type": "PaySui",
+          "account": { "address": sender }, "amount": { "value": "-1000", "currency": sui } },
+        { "operation_identifier": { "index": 3 }, "type": "PaySui",
+          "account": { "address": sender }, "amount": { "value": "-30", "currency": managed } },
+    ]))?;
+    ops.check_sign_conventions()?;
+
+    let internal = ops.into_internal()?;
+    let expected = InternalOperation::PaySui {
+        sender,
+        recipients: vec![recipient],
+        amounts: vec![1000],
+        coin_payments: vec![CoinPayment {
+            coin_type: coin_type.clone(),
+            decimals: 2,
+            recipients: vec![recipient],
+            amounts: vec![30],
+        }],
+    };
+    assert_eq!(
+        serde_json::to_value(&expected)?,
+        serde_json::to_value(&internal)?
+    );
+    // The internal operation is passed around as JSON between the construction endpoints.
+    let internal: InternalOperation = serde_json::from_value(serde_json::to_value(internal)?)?;
+
+    let gas = random_ref();
+    let managed_coins = vec![random_ref(), random_ref()];
+    let metadata = ConstructionMetadata {
+        sender,
+        coins: vec![gas],
+        objects: vec![],
+        total_coin_value: 1_000_000,
+        gas_price: 1,
+        budget: 1000,
+        currency_coins: BTreeMap::from([(coin_type, managed_coins.clone())]),
+        coin_balances: Default::default(),
+        gas_preference: Default::default(),
+    };
+    let data = internal.try_into_data(metadata)?;
+    let TransactionKind::ProgrammableTransaction(pt) = data.kind() else {
+        panic!("Expected a programmable transaction");
+    };
+    // SUI is split off the gas coin, MANAGED off the merged MANAGED coins.
+    for coin in &managed_coins {
+        assert!(pt
+            .inputs
+            .contains(&CallArg::Object(ObjectArg::ImmOrOwnedObject(*coin))));
+    }
+    let splits = pt
+        .commands
+        .iter()
+        .filter_map(|command| match command {
+            Command::SplitCoin(source, _) => Some(*source),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(2, splits.len());
+    assert_eq!(Argument::GasCoin, splits[0]);
+    assert_ne!(Argument::GasCoin, splits[1]);
+    assert!(pt.commands.iter().any(
+        |command| matches!(command, Command::MergeCoins(primary, _) if *primary == splits[1])
+    ));
+
+    // Without the coin types, the transaction can't be reported as a SUI payment.
+    let parsed = Operations::try_from(data)?;
+    assert_eq!(Some(OperationType::ProgrammableTransaction), parsed.type_());
+
+    Ok(())
+}
+
+#[test]
+fn test_gas_preference() -> Result<(), anyhow::Error> {
+    let random_ref = || {
+        (
+            ObjectID::random(),
+            SequenceNumber::new(),
+            ObjectDigest::random(),
+        )
+    };
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let coins = vec![random_ref(), random_ref(), random_ref()];
+    let coin_balances = coins
+        .iter()
+        .zip([5000, 100_000, 2000])
+        .map(|((id, ..), balance)| (*id, balance))
+        .collect::<BTreeMap<_, _>>();
+    let gas_payment = |gas_preference| -> Result<Vec<_>, anyhow::Error> {
+        let internal = InternalOperation::PaySui {
+            sender,
+            recipients: vec![recipient],
+            amounts: vec![3000],
+            coin_payments: vec![],
+        };
+        let metadata = ConstructionMetadata {
+            sender,
+            coins: coins.clone(),
+            objects: vec![],
+            total_coin_value: 107_000,
+            gas_price: 1,
+            budget: 1000,
+            currency_coins: Default::default(),
+            coin_balances: coin_balances.clone(),
+            gas_preference,
+        };
+        let data = internal.try_into_data(metadata)?;
+        Ok(data.gas().to_vec())
+    };
+
+    assert_eq!(coins, gas_payment(GasPreference::Selected)?);
+    // Exactly the named coin pays, even if it is not the first one selected.
+    assert_eq!(
+        vec![coins[1]],
+        gas_payment(GasPreference::Specific(coins[1].0))?
+    );
+    // The 3000 paid and the budget of 1000 take the two smallest coins, or the largest one.
+    assert_eq!(
+        vec![coins[2], coins[0]],
+        gas_payment(GasPreference::SmallestFirst)?
+    );
+    assert_eq!(vec![coins[1]], gas_payment(GasPreference::LargestFirst)?);
+    // The named coin has to be one of the coins of the metadata.
+    assert!(gas_payment(GasPreference::Specific(ObjectID::random())).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_gas_preference_covers_gas_price() -> Result<(), anyhow::Error> {
+    let random_ref = || {
+        (
+            ObjectID::random(),
+            SequenceNumber::new(),
+            ObjectDigest::random(),
+        )
+    };
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let (small, large) = (random_ref(), random_ref());
+    let internal = InternalOperation::PaySui {
+        sender,
+        recipients: vec![recipient],
+        amounts: vec![100],
+        coin_payments: vec![],
+    };
+    let metadata = ConstructionMetadata {
+        sender,
+        coins: vec![small, large],
+        objects: vec![],
+        total_coin_value: 10_005_000,
+        gas_price: 1000,
+        budget: 2000,
+        currency_coins: Default::default(),
+        coin_balances: BTreeMap::from([(small.0, 5000), (large.0, 10_000_000)]),
+        gas_preference: GasPreference::SmallestFirst,
+    };
+
+    // The small coin covers the budget of 2000 gas units, but not at a gas price of 1000.
+    let data = internal.try_into_data(metadata)?;
+    assert_eq!(vec![small, large], data.gas().to_vec());
+
+    Ok(())
+}
+
+#[test]
+fn test_payment_overflow() {
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let try_pay = |amounts| {
+        let internal = InternalOperation::PaySui {
+            sender,
+            recipients: vec![recipient, recipient],
+            amounts,
+            coin_payments: vec![],
+        };
+        let metadata = ConstructionMetadata {
+            sender,
+            coins: vec![gas],
+            objects: vec![],
+            total_coin_value: u64::MAX,
+            gas_price: 1,
+            budget: 1000,
+            currency_coins: Default::default(),
+            coin_balances: BTreeMap::from([(gas.0, u64::MAX)]),
+            gas_preference: Default::default(),
+        };
+        internal.try_into_data(metadata)
+    };
+
+    // The amounts paid overflow on their own, or once the budget is added.
+    let err = try_pay(vec![u64::MAX, 1]).unwrap_err();
+    assert!(matches!(err, Error::InvalidInput(_)));
+    let err = try_pay(vec![u64::MAX - 1000, 1]).unwrap_err();
+    assert!(matches!(err, Error::InvalidInput(_)));
+}
+
+#[test]
+fn test_canonical_coin_type() -> Result<(), anyhow::Error> {
+    let short = parse_sui_type_tag("0x2::sui::SUI")?;
+    let long = parse_sui_type_tag(&format!("{}::sui::SUI", SUI_FRAMEWORK_OBJECT_ID))?;
+    assert_eq!(canonical_coin_type(&short), canonical_coin_type(&long));
+    assert_eq!(
+        canonical_coin_type(&GAS::type_tag()),
+        canonical_coin_type(&short)
+    );
+
+    // Payments of the same coin are grouped whichever form its type is written in, and SUI
+    // given by its coin type is paid as SUI.
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let package = ObjectID::from_hex_literal("0x42")?;
+    let managed = |coin_type: String| {
+        let metadata = json!({ "coin_type": coin_type });
+        json!({ "symbol": "MANAGED", "decimals": 2, "metadata": metadata })
+    };
+    let sui =
+        json!({ "symbol": "SUI", "decimals": 9, "metadata": { "coin_type": "0x2::sui::SUI" } });
+    let pay = |index: u64, address: SuiAddress, value: i128, currency: &Value| {
+        json!({ "operation_identifier": { "index": index }, "type": "PaySui",
+                "account": { "address": address },
+                "amount": { "value": value.to_string(), "currency": currency } })
+    };
+    let short_managed = managed("0x42::managed::MANAGED".into());
+    let long_managed = managed(format!("{package}::managed::MANAGED"));
+    let ops: Operations = serde_json::from_value(json!([
+        pay(0, recipient, 10, &short_managed),
+        pay(1, recipient, 20, &long_managed),
+        pay(2, recipient, 1000, &sui),
+        pay(3, sender, -30, &short_managed),
+        pay(4, sender, -1000, &sui),
+    ]))?;
+    let (amounts, coin_payments) = match ops.into_internal()? {
+        InternalOperation::PaySui {
+            amounts,
+            coin_payments,
+            ..
+        } => (amounts, coin_payments),
+        _ => panic!("Expected a PaySui"),
+    };
+    assert_eq!(vec![1000], amounts);
+    assert_eq!(1, coin_payments.len());
+    assert_eq!(vec![10, 20], coin_payments[0].amounts);
+
+    Ok(())
+}
+
+#[test]
+fn test_check_sign_conventions() {
+    let sender = SuiAddress::random_for_testing_only();
+    let validator = SuiAddress::random_for_testing_only();
+    let stake = |value: &str| -> Operations {
+        serde_json::from_value(json!(
+            [{
+                "operation_identifier":{"index":0},
+                "type":"Stake",
+                "account": { "address" : sender.to_string() },
+                "amount" : { "value": value, "currency": { "symbol": "SUI", "decimals": 9}},
+                "metadata": { "Stake" : {"validator": validator.to_string()} }
+            }]
+        ))
+        .unwrap()
+    };
+    assert!(stake("-100000").check_sign_conventions().is_ok());
+
+    // A stake is a debit, a positive amount is a violation.
+    let err = stake("100000").check_sign_conventions().unwrap_err();
+    assert!(err.to_string().contains("should be negative"));
+
+    // PaySui debits must balance credits.
+    let data = TransactionData::new_programmable_with_dummy_gas_price(
+        sender,
+        vec![(
+            ObjectID::random(),
+            SequenceNumber::new(),
+            ObjectDigest::random(),
+        )],
+        {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            builder.transfer_sui(validator, Some(10000));
+            builder.finish()
+        },
+        1000,
+    );
+    let ops = Operations::try_from(data).unwrap();
+    assert!(ops.check_sign_conventions().is_ok());
+}
+
+proptest! {
+    #[test]
+    fn test_operations_round_trip(ops in any::<Operations>()) {
+        let gas = (
+            ObjectID::random(),
+            SequenceNumber::new(),
+            ObjectDigest::random(),
+        );
+        let internal = ops.clone().into_internal().unwrap();
+        let sender = internal.sender();
+        let metadata = ConstructionMetadata {
+            sender,
+            coins: vec![gas],
+            objects: vec![],
+            total_coin_value: 1_000_000,
+            gas_price: 1,
+            budget: 1000,
+            currency_coins: Default::default(),
+            coin_balances: Default::default(),
+            gas_preference: Default::default(),
+        };
+        let data = internal.try_into_data(metadata).unwrap();
+        prop_assert_eq!(ops.with_sender(sender), Operations::try_from(data).unwrap());
+    }
+}
@@ -91,6 +91,7 @@ async fn test_get_staked_sui() {
             Some(100000),
             validator,
             None,
+            None,
             10000,
         )
         .await
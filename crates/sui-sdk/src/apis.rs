@@ -2,47 +2,123 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::error::{Error, SuiRpcResult};
-use crate::{RpcClient, WAIT_FOR_TX_TIMEOUT_SEC};
+use crate::{RpcClient, DEFAULT_MAX_CONCURRENT_EXECUTIONS, WAIT_FOR_TX_TIMEOUT_SEC};
 use fastcrypto::encoding::Base64;
 use futures::stream;
 use futures_core::Stream;
 use jsonrpsee::core::client::Subscription;
+use jsonrpsee::core::Error as RpcError;
+use jsonrpsee::types::error::{CallError, CALL_EXECUTION_FAILED_CODE};
+use move_core_types::language_storage::{StructTag, TypeTag};
+use rand::Rng;
+use rust_decimal::Decimal;
+use serde_json::json;
 use std::collections::BTreeMap;
 use std::future;
-use std::sync::Arc;
-use std::time::{Duration, Instant};
-use sui_json_rpc::api::GovernanceReadApiClient;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use sui_json_rpc::api::{
+    GovernanceReadApiClient, MAX_GET_OWNED_OBJECT_LIMIT, QUERY_MAX_RESULT_LIMIT,
+};
 use sui_json_rpc_types::{
-    Balance, Checkpoint, CheckpointId, Coin, CoinPage, DelegatedStake, DryRunTransactionResponse,
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, SuiCoinMetadata, SuiCommittee, SuiEvent,
+    Balance, BalanceChange, Checkpoint, CheckpointId, Coin, CoinPage, DelegatedStake,
+    DevInspectResults, DryRunTransactionResponse, DynamicFieldPage, EventFilter, EventPage,
+    ObjectsPage, SuiCoinMetadata, SuiCommittee, SuiData, SuiEvent, SuiExecutionStatus,
     SuiGetPastObjectRequest, SuiMoveNormalizedModule, SuiObjectDataOptions, SuiObjectResponse,
     SuiPastObjectResponse, SuiTransactionEffectsAPI, SuiTransactionResponse,
     SuiTransactionResponseOptions, SuiTransactionResponseQuery, TransactionsPage,
 };
 use sui_types::balance::Supply;
 use sui_types::base_types::{
-    ObjectID, SequenceNumber, SuiAddress, TransactionDigest, TxSequenceNumber,
+    MoveObjectType, ObjectID, ObjectRef, ObjectType, SequenceNumber, SuiAddress, TransactionDigest,
+    TxSequenceNumber,
 };
 use sui_types::committee::EpochId;
-use sui_types::error::TRANSACTION_NOT_FOUND_MSG_PREFIX;
+use sui_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName, Field};
+use sui_types::error::{CHECKPOINT_NOT_FOUND_MSG_PREFIX, TRANSACTION_NOT_FOUND_MSG_PREFIX};
 use sui_types::event::EventID;
-use sui_types::messages::{ExecuteTransactionRequestType, TransactionData, VerifiedTransaction};
+use sui_types::gas_coin::{GAS, SUI_DECIMALS};
+use sui_types::governance::MIN_STAKE_AMOUNT_MIST;
+use sui_types::messages::{
+    CallArg, ExecuteTransactionRequestType, GasData, ObjectArg, TransactionData,
+    TransactionDataAPI, TransactionExpiration, TransactionKind, VerifiedTransaction,
+};
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::query::TransactionFilter;
 
 use futures::StreamExt;
 use sui_json_rpc::api::{CoinReadApiClient, EventReadApiClient, ReadApiClient, WriteApiClient};
+use sui_types::sui_system_state::sui_system_state_inner_v1::SuiSystemStateInnerV1;
 use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
+use sui_types::sui_system_state::{
+    PoolTokenExchangeRate, SuiSystemState, SuiSystemStateTrait, SuiSystemStateWrapper,
+    SystemEpochInfoEvent,
+};
+use sui_types::{parse_sui_type_tag, SUI_SYSTEM_STATE_OBJECT_ID};
+use tokio::sync::Semaphore;
+use tracing::warn;
 
 #[derive(Debug)]
 pub struct ReadApi {
     api: Arc<RpcClient>,
+    gas_price_cache: RwLock<Option<CachedGasPrice>>,
+    gas_price_cache_ttl: Duration,
+    latest_epoch: RwLock<Option<EpochId>>,
+}
+
+/// Advisory measure of how busy a shared object has been recently, transactions using a
+/// congested shared object are more likely to be delayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CongestionLevel {
+    Low,
+    Medium,
+    High,
+}
+
+/// An owned input of a transaction whose version is behind the latest version of the object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaleInput {
+    pub object_id: ObjectID,
+    pub expected: SequenceNumber,
+    pub current: SequenceNumber,
+}
+
+/// Reference gas price, along with when it was fetched and the latest epoch the client had
+/// seen then.
+#[derive(Debug, Clone, Copy)]
+struct CachedGasPrice {
+    price: u64,
+    epoch: Option<EpochId>,
+    fetched_at: Instant,
+}
+
+/// A balance change along with the checkpoint and transaction it was made in.
+type CheckpointBalanceChange = (CheckpointSequenceNumber, TransactionDigest, BalanceChange);
+
+/// Whether `error` is the node reporting that the checkpoint asked for doesn't exist, which is
+/// also how pruned checkpoints are reported.
+fn is_checkpoint_not_found(error: &Error) -> bool {
+    match error {
+        Error::RpcError(RpcError::Call(CallError::Custom(error))) => {
+            error.code() == CALL_EXECUTION_FAILED_CODE
+                && error.message().starts_with(CHECKPOINT_NOT_FOUND_MSG_PREFIX)
+        }
+        _ => false,
+    }
 }
 
 impl ReadApi {
-    pub(crate) fn new(api: Arc<RpcClient>) -> Self {
-        Self { api }
+    pub(crate) fn new(api: Arc<RpcClient>, gas_price_cache_ttl: Duration) -> Self {
+        Self {
+            api,
+            gas_price_cache: RwLock::new(None),
+            gas_price_cache_ttl,
+            latest_epoch: RwLock::new(None),
+        }
     }
 
+    /// Return a page of the objects owned by `address`. `limit` defaults to the server maximum
+    /// of `MAX_GET_OWNED_OBJECT_LIMIT` objects per page, larger limits are clamped to it.
     pub async fn get_owned_objects(
         &self,
         address: SuiAddress,
@@ -51,6 +127,15 @@ impl ReadApi {
         limit: Option<usize>,
         checkpoint: Option<CheckpointId>,
     ) -> SuiRpcResult<ObjectsPage> {
+        let limit = limit.map(|limit| {
+            if limit > MAX_GET_OWNED_OBJECT_LIMIT {
+                warn!(
+                    "Requested page of {limit} owned objects exceeds the maximum, \
+                    using {MAX_GET_OWNED_OBJECT_LIMIT}"
+                );
+            }
+            limit.min(MAX_GET_OWNED_OBJECT_LIMIT)
+        });
         Ok(self
             .api
             .http
@@ -58,6 +143,127 @@ impl ReadApi {
             .await?)
     }
 
+    /// Return the address owned objects of type `struct_tag` across all owners. Fails if the
+    /// connected node does not serve the type index.
+    pub async fn get_objects_by_type(
+        &self,
+        struct_tag: StructTag,
+        options: Option<SuiObjectDataOptions>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> SuiRpcResult<ObjectsPage> {
+        const METHOD: &str = "sui_getObjectsByType";
+        if !self.api.info.rpc_methods.iter().any(|m| m == METHOD) {
+            return Err(Error::DataError(format!(
+                "The connected node does not support {METHOD}"
+            )));
+        }
+        Ok(self
+            .api
+            .http
+            .get_objects_by_type(struct_tag.to_string(), options, cursor, limit)
+            .await?)
+    }
+
+    /// Return all gas coins owned by `owner` with their values, sorted by value.
+    pub async fn get_gas_coins_sorted(
+        &self,
+        owner: SuiAddress,
+        descending: bool,
+    ) -> SuiRpcResult<Vec<(ObjectRef, u64)>> {
+        let mut coins = vec![];
+        let mut cursor = None;
+        loop {
+            let page = self.api.http.get_coins(owner, None, cursor, None).await?;
+            coins.extend(page.data.iter().map(|c| (c.object_ref(), c.balance)));
+            if !page.has_next_page {
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+        if descending {
+            coins.sort_by(|(_, a), (_, b)| b.cmp(a));
+        } else {
+            coins.sort_by_key(|(_, value)| *value);
+        }
+        Ok(coins)
+    }
+
+    /// Net worth of `owner` in SUI: its SUI plus its other coins, valued with `price_oracle`, which
+    /// returns the price in SUI of one whole coin of a type. Coins without a price are skipped.
+    pub async fn get_net_worth(
+        &self,
+        owner: SuiAddress,
+        price_oracle: impl Fn(TypeTag) -> Option<Decimal>,
+    ) -> SuiRpcResult<Decimal> {
+        let mut net_worth = Decimal::ZERO;
+        for balance in self.api.http.get_all_balances(owner).await? {
+            let coin_type = parse_sui_type_tag(&balance.coin_type)
+                .map_err(|e| Error::DataError(e.to_string()))?;
+            let (price, decimals) = if coin_type == GAS::type_tag() {
+                (Decimal::ONE, SUI_DECIMALS)
+            } else if let Some(price) = price_oracle(coin_type) {
+                let metadata = self.api.http.get_coin_metadata(balance.coin_type).await?;
+                (price, metadata.decimals.into())
+            } else {
+                continue;
+            };
+            net_worth = i128::try_from(balance.total_balance)
+                .ok()
+                .and_then(|total| Decimal::try_from_i128_with_scale(total, decimals).ok())
+                .and_then(|amount| amount.checked_mul(price))
+                .and_then(|value| net_worth.checked_add(value))
+                .ok_or_else(|| {
+                    Error::DataError(format!("Net worth of [{owner}] overflows a Decimal"))
+                })?;
+        }
+        Ok(net_worth.normalize())
+    }
+
+    /// Estimate the congestion of each shared object in `ids` from the number of transactions
+    /// that used it as an input in the last minute.
+    pub async fn get_shared_object_congestion(
+        &self,
+        ids: Vec<ObjectID>,
+    ) -> SuiRpcResult<Vec<(ObjectID, CongestionLevel)>> {
+        const WINDOW: Duration = Duration::from_secs(60);
+        const MEDIUM_THRESHOLD: usize = 10;
+        const HIGH_THRESHOLD: usize = 50;
+
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| Error::DataError(e.to_string()))?
+            .as_millis() as u64;
+        let since_ms = now_ms.saturating_sub(WINDOW.as_millis() as u64);
+
+        let mut levels = vec![];
+        for id in ids {
+            // The effects are requested so the responses carry their checkpoint timestamp.
+            let query = SuiTransactionResponseQuery::new(
+                Some(TransactionFilter::InputObject(id)),
+                Some(SuiTransactionResponseOptions::new().with_effects()),
+            );
+            let page = self
+                .query_transactions(query, None, Some(HIGH_THRESHOLD), true)
+                .await?;
+            let recent = page
+                .data
+                .iter()
+                // Transactions not yet in a checkpoint are the most recent ones.
+                .filter(|tx| tx.timestamp_ms.map_or(true, |ts| ts >= since_ms))
+                .count();
+            let level = if recent >= HIGH_THRESHOLD {
+                CongestionLevel::High
+            } else if recent >= MEDIUM_THRESHOLD {
+                CongestionLevel::Medium
+            } else {
+                CongestionLevel::Low
+            };
+            levels.push((id, level));
+        }
+        Ok(levels)
+    }
+
     pub async fn get_dynamic_fields(
         &self,
         object_id: ObjectID,
@@ -71,6 +277,47 @@ impl ReadApi {
             .await?)
     }
 
+    /// Stream the dynamic fields of `object_id` after `cursor`, fetched a page at a time. A
+    /// failed request is yielded as an error and ends the stream.
+    pub fn get_dynamic_fields_stream(
+        &self,
+        object_id: ObjectID,
+        cursor: Option<ObjectID>,
+    ) -> impl Stream<Item = SuiRpcResult<DynamicFieldInfo>> + '_ {
+        stream::unfold(
+            (vec![], cursor, true),
+            move |(mut data, cursor, has_next_page)| async move {
+                if let Some(item) = data.pop() {
+                    return Some((Ok(item), (data, cursor, has_next_page)));
+                }
+                if !has_next_page {
+                    return None;
+                }
+                match self.get_dynamic_fields(object_id, cursor, Some(100)).await {
+                    Ok(page) => {
+                        let mut data = page.data;
+                        data.reverse();
+                        data.pop()
+                            .map(|item| (Ok(item), (data, page.next_cursor, page.has_next_page)))
+                    }
+                    Err(e) => Some((Err(e), (vec![], cursor, false))),
+                }
+            },
+        )
+    }
+
+    pub async fn get_dynamic_field_object(
+        &self,
+        parent_object_id: ObjectID,
+        name: DynamicFieldName,
+    ) -> SuiRpcResult<SuiObjectResponse> {
+        Ok(self
+            .api
+            .http
+            .get_dynamic_field_object(parent_object_id, name)
+            .await?)
+    }
+
     pub async fn try_get_parsed_past_object(
         &self,
         object_id: ObjectID,
@@ -84,6 +331,21 @@ impl ReadApi {
             .await?)
     }
 
+    /// Return the object at the given historical version, or an error if that version does
+    /// not exist, was deleted, or has been pruned from the full node.
+    pub async fn get_object_at_version(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+        options: SuiObjectDataOptions,
+    ) -> SuiRpcResult<SuiObjectResponse> {
+        let object = self
+            .try_get_parsed_past_object(object_id, version, options)
+            .await?
+            .into_object()?;
+        Ok(SuiObjectResponse::Exists(object))
+    }
+
     pub async fn try_multi_get_parsed_past_object(
         &self,
         past_objects: Vec<SuiGetPastObjectRequest>,
@@ -120,6 +382,46 @@ impl ReadApi {
             .await?)
     }
 
+    /// Return the coin type `T` of the `0x2::coin::Coin<T>` object `object_id`, or an error if
+    /// the object is not a coin.
+    pub async fn get_coin_type(&self, object_id: ObjectID) -> SuiRpcResult<TypeTag> {
+        let object = self
+            .get_object_with_options(object_id, SuiObjectDataOptions::new().with_type())
+            .await?
+            .into_object()?;
+        match object.type_ {
+            Some(ObjectType::Struct(MoveObjectType::GasCoin)) => Ok(GAS::type_tag()),
+            Some(ObjectType::Struct(MoveObjectType::Coin(coin_type))) => Ok(coin_type),
+            type_ => Err(Error::DataError(format!(
+                "Object [{object_id}] is not a coin, found type {type_:?}"
+            ))),
+        }
+    }
+
+    /// Return the storage rebate the owner of `object_ids` would receive for deleting all of
+    /// them, which offsets the gas paid by a transaction cleaning them up.
+    pub async fn estimate_storage_rebate(&self, object_ids: Vec<ObjectID>) -> SuiRpcResult<u64> {
+        let mut rebate = 0;
+        for ids in object_ids.chunks(QUERY_MAX_RESULT_LIMIT) {
+            let responses = self
+                .multi_get_object_with_options(
+                    ids.to_vec(),
+                    SuiObjectDataOptions::new().with_storage_rebate(),
+                )
+                .await?;
+            for response in responses {
+                let object = response.into_object()?;
+                rebate += object.storage_rebate.ok_or_else(|| {
+                    Error::DataError(format!(
+                        "Missing storage rebate of object [{}]",
+                        object.object_id
+                    ))
+                })?;
+            }
+        }
+        Ok(rebate)
+    }
+
     pub async fn get_total_transaction_number(&self) -> SuiRpcResult<u64> {
         Ok(self.api.http.get_total_transaction_number().await?)
     }
@@ -178,9 +480,31 @@ impl ReadApi {
             .await?)
     }
 
+    /// Return a page of the transactions `address` sent or received, the address history
+    /// used by wallets. Pass the returned `next_cursor` back in to read the next page.
+    pub async fn get_transactions_for_address(
+        &self,
+        address: SuiAddress,
+        cursor: Option<TransactionDigest>,
+        limit: Option<usize>,
+        descending_order: bool,
+    ) -> SuiRpcResult<TransactionsPage> {
+        self.query_transactions(
+            SuiTransactionResponseQuery::new_with_filter(TransactionFilter::FromOrToAddress(
+                address,
+            )),
+            cursor,
+            limit,
+            descending_order,
+        )
+        .await
+    }
+
     /// Return a checkpoint
     pub async fn get_checkpoint(&self, id: CheckpointId) -> SuiRpcResult<Checkpoint> {
-        Ok(self.api.http.get_checkpoint(id).await?)
+        let checkpoint = self.api.http.get_checkpoint(id).await?;
+        self.observe_epoch(checkpoint.epoch);
+        Ok(checkpoint)
     }
 
     /// Return the sequence number of the latest checkpoint that has been executed
@@ -194,6 +518,189 @@ impl ReadApi {
             .await?)
     }
 
+    /// Return the sequence number of the oldest checkpoint the node still serves, historical
+    /// reads at earlier checkpoints fail. Nodes prune their oldest checkpoints first, so the
+    /// watermark is found by bisecting between the genesis checkpoint and the latest one.
+    /// Only checkpoints the node reports as not found count as pruned, any other failure to
+    /// read a checkpoint is returned as an error.
+    pub async fn get_pruning_watermark(&self) -> SuiRpcResult<CheckpointSequenceNumber> {
+        let mut high = self.get_latest_checkpoint_sequence_number().await?;
+        let mut low = 0;
+        // The latest checkpoint is always available, look for the first one that is.
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let available = match self.get_checkpoint(CheckpointId::SequenceNumber(mid)).await {
+                Ok(_) => true,
+                Err(e) if is_checkpoint_not_found(&e) => false,
+                Err(e) => return Err(e),
+            };
+            if available {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        Ok(low)
+    }
+
+    /// Return a stream of checkpoints in sequence order, starting from `start`, or from the
+    /// latest checkpoint if `None`. Checkpoints that have already been executed are fetched
+    /// first, after which new checkpoints are polled for as they are produced. Failed requests
+    /// are retried with a backoff, the error is only yielded once the retries are exhausted and
+    /// the stream then resumes from the same checkpoint.
+    pub fn subscribe_checkpoints(
+        &self,
+        start: Option<CheckpointSequenceNumber>,
+    ) -> impl Stream<Item = SuiRpcResult<Checkpoint>> + '_ {
+        stream::unfold((start, None), move |(next, latest)| async move {
+            match self.next_checkpoint(next, latest).await {
+                Ok((checkpoint, latest)) => {
+                    let next = checkpoint.sequence_number + 1;
+                    Some((Ok(checkpoint), (Some(next), Some(latest))))
+                }
+                Err(e) => Some((Err(e), (next, latest))),
+            }
+        })
+    }
+
+    /// Fetch checkpoint `next`, waiting for it to be executed if it is ahead of `latest`.
+    /// Returns the checkpoint along with the latest known checkpoint sequence number.
+    async fn next_checkpoint(
+        &self,
+        mut next: Option<CheckpointSequenceNumber>,
+        mut latest: Option<CheckpointSequenceNumber>,
+    ) -> SuiRpcResult<(Checkpoint, CheckpointSequenceNumber)> {
+        const POLL_INTERVAL: Duration = Duration::from_millis(500);
+        const MAX_RETRIES: u32 = 5;
+
+        let mut failures = 0;
+        loop {
+            let result = match (next, latest) {
+                (Some(seq), Some(latest)) if seq <= latest => self
+                    .get_checkpoint(seq.into())
+                    .await
+                    .map(|checkpoint| Some((checkpoint, latest))),
+                _ => self
+                    .get_latest_checkpoint_sequence_number()
+                    .await
+                    .map(|seq| {
+                        latest = Some(seq);
+                        next.get_or_insert(seq);
+                        None
+                    }),
+            };
+            match result {
+                Ok(Some(found)) => return Ok(found),
+                Ok(None) => {
+                    failures = 0;
+                    if next > latest {
+                        tokio::time::sleep(POLL_INTERVAL).await;
+                    }
+                }
+                Err(_) if failures < MAX_RETRIES => {
+                    failures += 1;
+                    tokio::time::sleep(POLL_INTERVAL * 2u32.pow(failures)).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Return a stream of the balance changes of `owner`, in checkpoint order, starting from
+    /// checkpoint `start` (see [Self::subscribe_checkpoints]). Each change is paired with the
+    /// digest of the transaction that caused it. If `coin_type` is set, changes to balances of
+    /// other coin types are dropped before they reach the stream.
+    pub fn subscribe_balance_changes(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<TypeTag>,
+        start: Option<CheckpointSequenceNumber>,
+    ) -> impl Stream<Item = SuiRpcResult<(TransactionDigest, BalanceChange)>> + '_ {
+        self.balance_changes_by_checkpoint(owner, coin_type, start)
+            .map(|change| change.map(|(_, digest, change)| (digest, change)))
+    }
+
+    /// Return a stream of the balance changes of `owner`, paired with the checkpoint they were
+    /// included in, starting from checkpoint `from_checkpoint`. Changes are yielded as each
+    /// checkpoint is read, so an account's history can be consumed without computing it all
+    /// up front. `coin_type` filters changes as in [Self::subscribe_balance_changes].
+    pub fn stream_balance_changes(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<TypeTag>,
+        from_checkpoint: CheckpointSequenceNumber,
+    ) -> impl Stream<Item = SuiRpcResult<(CheckpointSequenceNumber, BalanceChange)>> + '_ {
+        self.balance_changes_by_checkpoint(owner, coin_type, Some(from_checkpoint))
+            .map(|change| change.map(|(seq, _, change)| (seq, change)))
+    }
+
+    fn balance_changes_by_checkpoint(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<TypeTag>,
+        start: Option<CheckpointSequenceNumber>,
+    ) -> impl Stream<Item = SuiRpcResult<CheckpointBalanceChange>> + '_ {
+        self.subscribe_checkpoints(start)
+            .then(move |checkpoint| {
+                let coin_type = coin_type.clone();
+                async move {
+                    let changes = match checkpoint {
+                        Ok(checkpoint) => {
+                            let seq = checkpoint.sequence_number;
+                            self.checkpoint_balance_changes(checkpoint, owner, coin_type.as_ref())
+                                .await
+                                .map(|changes| {
+                                    changes
+                                        .into_iter()
+                                        .map(|(digest, change)| (seq, digest, change))
+                                        .collect()
+                                })
+                        }
+                        Err(e) => Err(e),
+                    };
+                    match changes {
+                        Ok(changes) => changes.into_iter().map(Ok).collect(),
+                        Err(e) => vec![Err(e)],
+                    }
+                }
+            })
+            .flat_map(stream::iter)
+    }
+
+    /// Fetch the balance changes of the transactions in `checkpoint` that affect `owner`'s
+    /// balance of `coin_type` (or of any coin type if `None`).
+    async fn checkpoint_balance_changes(
+        &self,
+        checkpoint: Checkpoint,
+        owner: SuiAddress,
+        coin_type: Option<&TypeTag>,
+    ) -> SuiRpcResult<Vec<(TransactionDigest, BalanceChange)>> {
+        let mut changes = vec![];
+        for digests in checkpoint.transactions.chunks(QUERY_MAX_RESULT_LIMIT) {
+            let responses = self
+                .multi_get_transactions_with_options(
+                    digests.to_vec(),
+                    SuiTransactionResponseOptions::new().with_balance_changes(),
+                )
+                .await?;
+            for response in responses {
+                let digest = response.digest;
+                changes.extend(
+                    response
+                        .balance_changes
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter(|change| {
+                            change.address_owner() == Some(owner)
+                                && coin_type.map_or(true, |t| &change.coin_type == t)
+                        })
+                        .map(|change| (digest, change)),
+                );
+            }
+        }
+        Ok(changes)
+    }
+
     pub fn get_transactions_stream(
         &self,
         query: SuiTransactionResponseQuery,
@@ -232,9 +739,42 @@ impl ReadApi {
             .await?)
     }
 
-    // TODO(devx): we can probably cache this given an epoch
+    /// Return the reference gas price of the current epoch. The price is cached until the
+    /// client sees a later epoch, in a checkpoint or while waiting for an epoch, and at most for
+    /// the TTL set with [crate::SuiClientBuilder::gas_price_cache_ttl], so that an epoch change
+    /// the client doesn't see is picked up too. See [Self::invalidate_gas_price_cache] to force
+    /// a refresh.
     pub async fn get_reference_gas_price(&self) -> SuiRpcResult<u64> {
-        Ok(self.api.http.get_reference_gas_price().await?)
+        let epoch = *self.latest_epoch.read().unwrap();
+        if let Some(cached) = *self.gas_price_cache.read().unwrap() {
+            if cached.epoch == epoch && cached.fetched_at.elapsed() < self.gas_price_cache_ttl {
+                return Ok(cached.price);
+            }
+        }
+
+        let fetched_at = Instant::now();
+        let price = self.api.http.get_reference_gas_price().await?;
+        *self.gas_price_cache.write().unwrap() = Some(CachedGasPrice {
+            price,
+            epoch,
+            fetched_at,
+        });
+        Ok(price)
+    }
+
+    /// Record that the network has reached `epoch`, so a price cached in an earlier epoch is
+    /// fetched again.
+    fn observe_epoch(&self, epoch: EpochId) {
+        let mut latest_epoch = self.latest_epoch.write().unwrap();
+        if latest_epoch.map_or(true, |latest| latest < epoch) {
+            *latest_epoch = Some(epoch);
+        }
+    }
+
+    /// Drop the cached reference gas price, so the next call to
+    /// [Self::get_reference_gas_price] fetches it from the network.
+    pub fn invalidate_gas_price_cache(&self) {
+        *self.gas_price_cache.write().unwrap() = None;
     }
 
     pub async fn dry_run_transaction(
@@ -247,6 +787,191 @@ impl ReadApi {
             .dry_run_transaction(Base64::from_bytes(&bcs::to_bytes(&tx)?))
             .await?)
     }
+
+    /// Run `tx` as `sender` in dev-inspect mode, nothing is committed and no gas is charged.
+    pub async fn dev_inspect_transaction(
+        &self,
+        sender: SuiAddress,
+        tx: TransactionKind,
+        gas_price: Option<u64>,
+        epoch: Option<EpochId>,
+    ) -> SuiRpcResult<DevInspectResults> {
+        Ok(self
+            .api
+            .http
+            .dev_inspect_transaction(
+                sender,
+                Base64::from_bytes(&bcs::to_bytes(&tx)?),
+                gas_price,
+                epoch,
+            )
+            .await?)
+    }
+
+    /// Number of objects `kind` would create if `sender` ran it now, found by dev-inspecting it.
+    /// A publish creates a single package object holding all of its modules, plus any object
+    /// created by the module initializers.
+    pub async fn estimate_created_objects(
+        &self,
+        kind: TransactionKind,
+        sender: SuiAddress,
+    ) -> SuiRpcResult<usize> {
+        let results = self
+            .dev_inspect_transaction(sender, kind, None, None)
+            .await?;
+        if let Some(error) = results.error {
+            return Err(Error::DataError(format!(
+                "Cannot estimate the objects created by a failing transaction: {error}"
+            )));
+        }
+        Ok(results.effects.created().len())
+    }
+
+    /// Re-resolve the owned object inputs and the gas payment of `data` to their latest
+    /// versions, so a transaction prepared for offline signing can be signed again before
+    /// submission. Returns the updated transaction data and the ids of the inputs that changed.
+    pub async fn refresh_transaction_inputs(
+        &self,
+        data: TransactionData,
+    ) -> SuiRpcResult<(TransactionData, Vec<ObjectID>)> {
+        let sender = data.sender();
+        let expiration = *data.expiration();
+        let GasData {
+            mut payment,
+            owner,
+            price,
+            budget,
+        } = data.gas_data().clone();
+        let mut kind = data.into_kind();
+
+        let mut object_refs = payment.iter_mut().collect::<Vec<_>>();
+        if let TransactionKind::ProgrammableTransaction(pt) = &mut kind {
+            object_refs.extend(pt.inputs.iter_mut().filter_map(|input| match input {
+                CallArg::Object(ObjectArg::ImmOrOwnedObject(obj_ref)) => Some(obj_ref),
+                _ => None,
+            }));
+        }
+
+        let ids = object_refs.iter().map(|(id, ..)| *id).collect();
+        let responses = self
+            .multi_get_object_with_options(ids, SuiObjectDataOptions::new())
+            .await?;
+
+        let mut changed = vec![];
+        for (obj_ref, response) in object_refs.into_iter().zip(responses) {
+            let latest = response.into_object()?.object_ref();
+            if *obj_ref != latest {
+                changed.push(latest.0);
+                *obj_ref = latest;
+            }
+        }
+
+        let mut data = TransactionData::new_with_gas_data(
+            kind,
+            sender,
+            GasData {
+                payment,
+                owner,
+                price,
+                budget,
+            },
+        );
+        *data.expiration_mut() = expiration;
+        Ok((data, changed))
+    }
+
+    /// Check that the owned object inputs and the gas payment of `data` are at their latest
+    /// versions before it is signed, returning the inputs that are stale. An empty result means
+    /// the transaction is safe to sign.
+    pub async fn validate_input_objects(
+        &self,
+        data: &TransactionData,
+    ) -> SuiRpcResult<Vec<StaleInput>> {
+        let mut object_refs = data.gas().to_vec();
+        if let TransactionKind::ProgrammableTransaction(pt) = data.kind() {
+            object_refs.extend(pt.inputs.iter().filter_map(|input| match input {
+                CallArg::Object(ObjectArg::ImmOrOwnedObject(obj_ref)) => Some(*obj_ref),
+                _ => None,
+            }));
+        }
+
+        let ids = object_refs.iter().map(|(id, ..)| *id).collect();
+        let responses = self
+            .multi_get_object_with_options(ids, SuiObjectDataOptions::new())
+            .await?;
+
+        let mut stale = vec![];
+        for ((object_id, expected, _), response) in object_refs.into_iter().zip(responses) {
+            let current = response.into_object()?.version;
+            if current != expected {
+                stale.push(StaleInput {
+                    object_id,
+                    expected,
+                    current,
+                });
+            }
+        }
+        Ok(stale)
+    }
+
+    /// Poll the balance of `owner` for `coin_type` (SUI if None) until it reaches `at_least`,
+    /// returning the observed balance, or fail once `timeout` has elapsed.
+    pub async fn wait_for_balance(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        at_least: u128,
+        timeout: Duration,
+    ) -> SuiRpcResult<u128> {
+        let start = Instant::now();
+        loop {
+            let balance = self
+                .api
+                .http
+                .get_balance(owner, coin_type.clone())
+                .await?
+                .total_balance;
+            if balance >= at_least {
+                return Ok(balance);
+            }
+            if start.elapsed() >= timeout {
+                return Err(Error::WaitForBalanceTimeout {
+                    address: owner,
+                    amount: at_least,
+                    balance,
+                    timeout,
+                });
+            }
+            let jitter = rand::thread_rng().gen_range(0..100);
+            tokio::time::sleep(Duration::from_millis(300 + jitter)).await;
+        }
+    }
+
+    /// Poll the system state until the network reaches epoch `target`, returning the observed
+    /// epoch, or fail once `timeout` has elapsed.
+    pub async fn wait_for_epoch(
+        &self,
+        target: EpochId,
+        timeout: Duration,
+    ) -> SuiRpcResult<EpochId> {
+        let start = Instant::now();
+        loop {
+            let epoch = self.api.http.get_latest_sui_system_state().await?.epoch;
+            self.observe_epoch(epoch);
+            if epoch >= target {
+                return Ok(epoch);
+            }
+            if start.elapsed() >= timeout {
+                return Err(Error::WaitForEpochTimeout {
+                    target,
+                    epoch,
+                    timeout,
+                });
+            }
+            let jitter = rand::thread_rng().gen_range(0..100);
+            tokio::time::sleep(Duration::from_millis(300 + jitter)).await;
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -454,6 +1179,19 @@ impl QuorumDriver {
         options: SuiTransactionResponseOptions,
         request_type: Option<ExecuteTransactionRequestType>,
     ) -> SuiRpcResult<SuiTransactionResponse> {
+        // Reject expired transactions locally instead of waiting for validators to refuse them.
+        let expiration = *tx.data().transaction_data().expiration();
+        if let TransactionExpiration::Epoch(expiration_epoch) = expiration {
+            let epoch = self.api.http.get_latest_sui_system_state().await?.epoch;
+            if expiration.is_expired(epoch) {
+                return Err(Error::TransactionExpired {
+                    digest: *tx.digest(),
+                    expiration: expiration_epoch,
+                    epoch,
+                });
+            }
+        }
+
         let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
         let request_type = request_type.unwrap_or_else(|| options.default_execution_request_type());
         let mut response: SuiTransactionResponse = self
@@ -491,6 +1229,79 @@ impl QuorumDriver {
         })
     }
 
+    /// Execute a transaction and return only its balance changes, the rest of the response is
+    /// not fetched. Effects are requested alongside so that execution failures are surfaced as
+    /// [Error::TransactionExecutionFailure].
+    pub async fn execute_and_get_balance_changes(
+        &self,
+        tx: VerifiedTransaction,
+    ) -> SuiRpcResult<Vec<BalanceChange>> {
+        let response = self
+            .execute_transaction(
+                tx,
+                SuiTransactionResponseOptions::new()
+                    .with_effects()
+                    .with_balance_changes(),
+                Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+            )
+            .await?;
+        let effects = response
+            .effects
+            .ok_or_else(|| Error::DataError("Expect effects to be non-empty".to_string()))?;
+        if let SuiExecutionStatus::Failure { error } = effects.status() {
+            return Err(Error::TransactionExecutionFailure {
+                digest: response.digest,
+                error: error.clone(),
+            });
+        }
+        Ok(response.balance_changes.unwrap_or_default())
+    }
+
+    /// Execute independent transactions concurrently, at most `max_concurrency` at a time
+    /// (`DEFAULT_MAX_CONCURRENT_EXECUTIONS` if None), returning their results in the order of
+    /// `txs`. A failing transaction does not prevent the others from being executed.
+    pub async fn execute_transactions(
+        &self,
+        txs: Vec<VerifiedTransaction>,
+        options: SuiTransactionResponseOptions,
+        max_concurrency: Option<usize>,
+    ) -> Vec<SuiRpcResult<SuiTransactionResponse>> {
+        let permits = Semaphore::new(
+            max_concurrency
+                .unwrap_or(DEFAULT_MAX_CONCURRENT_EXECUTIONS)
+                .max(1),
+        );
+        futures::future::join_all(txs.into_iter().map(|tx| async {
+            // The semaphore is never closed, acquiring a permit cannot fail.
+            let _permit = permits.acquire().await.unwrap();
+            self.execute_transaction(tx, options.clone(), None).await
+        }))
+        .await
+    }
+
+    /// Submit a transaction and return its digest as soon as it is certified, without waiting
+    /// for the fullnode to execute it or fetching any of the response content. Use
+    /// [Self::wait_for_transaction] to wait for its execution later.
+    pub async fn submit_transaction(
+        &self,
+        tx: VerifiedTransaction,
+    ) -> SuiRpcResult<TransactionDigest> {
+        let response = self
+            .execute_transaction(
+                tx,
+                SuiTransactionResponseOptions::new(),
+                Some(ExecuteTransactionRequestType::WaitForEffectsCert),
+            )
+            .await?;
+        Ok(response.digest)
+    }
+
+    /// Wait until the fullnode has executed the transaction `digest`, or until times out (see
+    /// WAIT_FOR_TX_TIMEOUT_SEC).
+    pub async fn wait_for_transaction(&self, digest: TransactionDigest) -> SuiRpcResult<()> {
+        Self::wait_until_fullnode_sees_tx(&self.api, digest).await
+    }
+
     async fn wait_until_fullnode_sees_tx(
         c: &RpcClient,
         tx_digest: TransactionDigest,
@@ -549,8 +1360,209 @@ impl GovernanceApi {
         Ok(self.api.http.get_latest_sui_system_state().await?)
     }
 
+    /// Same as [Self::get_latest_sui_system_state], but reads the system state object and
+    /// decodes it locally according to its version, so that the summary doesn't depend on the
+    /// fullnode's view of the current system state type.
+    pub async fn get_system_state_typed(&self) -> SuiRpcResult<SuiSystemStateSummary> {
+        let wrapper: SuiSystemStateWrapper = self
+            .api
+            .http
+            .get_object_with_options(
+                SUI_SYSTEM_STATE_OBJECT_ID,
+                Some(SuiObjectDataOptions::new().with_bcs()),
+            )
+            .await?
+            .into_object()?
+            .bcs
+            .as_ref()
+            .and_then(|bcs| bcs.try_as_move())
+            .ok_or_else(|| Error::DataError("Missing system state object".to_string()))?
+            .deserialize()
+            .map_err(|e| Error::DataError(e.to_string()))?;
+
+        let name = DynamicFieldName {
+            type_: TypeTag::U64,
+            value: json!(wrapper.version.to_string()),
+        };
+        let object = self
+            .api
+            .http
+            .get_dynamic_field_object(wrapper.id.id.bytes, name)
+            .await?
+            .into_object()?;
+        let inner = object
+            .bcs
+            .as_ref()
+            .and_then(|bcs| bcs.try_as_move())
+            .ok_or_else(|| {
+                Error::DataError(format!(
+                    "Missing system state of version {}",
+                    wrapper.version
+                ))
+            })?;
+        let system_state = match wrapper.version {
+            1 => {
+                let field: Field<u64, SuiSystemStateInnerV1> = inner
+                    .deserialize()
+                    .map_err(|e| Error::DataError(e.to_string()))?;
+                SuiSystemState::V1(field.value)
+            }
+            version => {
+                return Err(Error::DataError(format!(
+                    "Unsupported SuiSystemState version: {version}"
+                )))
+            }
+        };
+        Ok(system_state.into_sui_system_state_summary())
+    }
+
     /// Return the reference gas price for the network
     pub async fn get_reference_gas_price(&self) -> SuiRpcResult<u64> {
         Ok(self.api.http.get_reference_gas_price().await?)
     }
+
+    /// Return the reference gas price of `epoch`, see [Self::get_gas_price_history].
+    pub async fn get_reference_gas_price_at(&self, epoch: EpochId) -> SuiRpcResult<u64> {
+        let history = self.get_gas_price_history(epoch, epoch).await?;
+        Ok(history[0].1)
+    }
+
+    /// Return the reference gas price of each epoch from `from` to `to` inclusive, in epoch order.
+    /// Past epochs are read from the event emitted by the system state when the epoch started, so
+    /// the genesis epoch and epochs whose events have been pruned are reported as errors.
+    pub async fn get_gas_price_history(
+        &self,
+        from: EpochId,
+        to: EpochId,
+    ) -> SuiRpcResult<Vec<(EpochId, u64)>> {
+        let system_state = self.api.http.get_latest_sui_system_state().await?;
+        if from > to || to > system_state.epoch {
+            return Err(Error::DataError(format!(
+                "Invalid epoch range [{from}, {to}], the current epoch is {}",
+                system_state.epoch
+            )));
+        }
+
+        let mut prices = BTreeMap::new();
+        prices.insert(system_state.epoch, system_state.reference_gas_price);
+        // Page through the epoch events until every epoch in the range has a price.
+        let mut cursor = None;
+        let mut has_next_page = true;
+        while has_next_page && (prices.range(from..=to).count() as u64) <= to - from {
+            let page = self
+                .api
+                .http
+                .query_events(
+                    EventFilter::MoveEventType(SystemEpochInfoEvent::type_()),
+                    cursor,
+                    None,
+                    Some(false),
+                )
+                .await?;
+            for event in page.data {
+                let info: SystemEpochInfoEvent =
+                    bcs::from_bytes(&event.bcs).map_err(|e| Error::DataError(e.to_string()))?;
+                prices.insert(info.epoch, info.reference_gas_price);
+            }
+            cursor = page.next_cursor;
+            has_next_page = page.has_next_page;
+        }
+
+        (from..=to)
+            .map(|epoch| {
+                let price = prices.get(&epoch).ok_or_else(|| {
+                    Error::DataError(format!(
+                        "Reference gas price of epoch {epoch} is not available, it may have been \
+                         pruned"
+                    ))
+                })?;
+                Ok((epoch, *price))
+            })
+            .collect()
+    }
+
+    /// Return the minimum amount of MIST that can be staked in a single request, according to
+    /// the version of the current system state.
+    pub async fn min_stake_amount(&self) -> SuiRpcResult<u64> {
+        let system_state = self.get_latest_sui_system_state().await?;
+        match system_state.system_state_version {
+            1 => Ok(MIN_STAKE_AMOUNT_MIST),
+            version => Err(Error::DataError(format!(
+                "Unsupported SuiSystemState version: {version}"
+            ))),
+        }
+    }
+
+    /// Return the reward `staked_sui` earned in each epoch since its stake became active, derived
+    /// from the exchange rates of its staking pool. Rewards accrue without any transaction, so
+    /// this lets tools attribute reward income to the epoch it was earned in.
+    pub async fn get_stake_rewards_history(
+        &self,
+        staked_sui: ObjectID,
+    ) -> SuiRpcResult<Vec<(EpochId, i128)>> {
+        let delegated_stake = self
+            .api
+            .http
+            .get_stakes_by_ids(vec![staked_sui])
+            .await?
+            .pop()
+            .ok_or_else(|| Error::DataError(format!("Cannot find stake [{staked_sui}]")))?;
+        let stake = delegated_stake
+            .stakes
+            .first()
+            .ok_or_else(|| Error::DataError(format!("Cannot find stake [{staked_sui}]")))?;
+
+        let system_state = self.api.http.get_latest_sui_system_state().await?;
+        let exchange_rates_id = system_state
+            .active_validators
+            .iter()
+            .find(|v| v.staking_pool_id == delegated_stake.staking_pool)
+            .map(|v| v.exchange_rates_id)
+            .ok_or_else(|| {
+                Error::DataError(format!(
+                    "Staking pool [{}] is not active",
+                    delegated_stake.staking_pool
+                ))
+            })?;
+
+        let stake_rate = self
+            .get_exchange_rate(exchange_rates_id, stake.stake_request_epoch)
+            .await?;
+        let mut history = vec![];
+        let mut previous_reward = 0;
+        for epoch in stake.stake_active_epoch..=system_state.epoch {
+            let rate = self.get_exchange_rate(exchange_rates_id, epoch).await?;
+            // Same estimate as the `estimated_reward` of active stakes.
+            let reward = (((stake_rate.rate() / rate.rate()) - 1.0) * stake.principal as f64)
+                .round() as i128;
+            history.push((epoch, reward - previous_reward));
+            previous_reward = reward;
+        }
+        Ok(history)
+    }
+
+    async fn get_exchange_rate(
+        &self,
+        exchange_rates_id: ObjectID,
+        epoch: EpochId,
+    ) -> SuiRpcResult<PoolTokenExchangeRate> {
+        let name = DynamicFieldName {
+            type_: TypeTag::U64,
+            value: json!(epoch.to_string()),
+        };
+        let object = self
+            .api
+            .http
+            .get_dynamic_field_object(exchange_rates_id, name)
+            .await?
+            .into_object()?;
+        let field: Field<EpochId, PoolTokenExchangeRate> = object
+            .bcs
+            .as_ref()
+            .and_then(|bcs| bcs.try_as_move())
+            .ok_or_else(|| Error::DataError(format!("Missing exchange rate for epoch {epoch}")))?
+            .deserialize()
+            .map_err(|e| Error::DataError(e.to_string()))?;
+        Ok(field.value)
+    }
 }
@@ -1,7 +1,9 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::time::Duration;
 use sui_types::base_types::{SuiAddress, TransactionDigest};
+use sui_types::committee::EpochId;
 use sui_types::error::UserInputError;
 use thiserror::Error;
 
@@ -21,8 +23,21 @@ pub enum Error {
     TransactionConfirmationError(TransactionDigest, jsonrpsee::core::Error),
     #[error("Failed to confirm tx status for {0:?} within {1} seconds.")]
     FailToConfirmTransactionStatus(TransactionDigest, u64),
+    #[error(
+        "Transaction {digest:?} expired at the end of epoch {expiration}, current epoch is {epoch}"
+    )]
+    TransactionExpired {
+        digest: TransactionDigest,
+        expiration: EpochId,
+        epoch: EpochId,
+    },
     #[error("Data error: {0}")]
     DataError(String),
+    #[error("Transaction {digest:?} failed to execute: {error}")]
+    TransactionExecutionFailure {
+        digest: TransactionDigest,
+        error: String,
+    },
     #[error("Client/Server api version mismatch, client api version : {client_version}, server api version : {server_version}")]
     ServerVersionMismatch {
         client_version: String,
@@ -30,4 +45,17 @@ pub enum Error {
     },
     #[error("Insufficient fund for address [{address}], requested amount: {amount}")]
     InsufficientFund { address: SuiAddress, amount: u128 },
+    #[error("Balance of address [{address}] did not reach {amount} within {timeout:?}, last observed balance: {balance}")]
+    WaitForBalanceTimeout {
+        address: SuiAddress,
+        amount: u128,
+        balance: u128,
+        timeout: Duration,
+    },
+    #[error("Epoch {target} was not reached within {timeout:?}, last observed epoch: {epoch}")]
+    WaitForEpochTimeout {
+        target: EpochId,
+        epoch: EpochId,
+        timeout: Duration,
+    },
 }
@@ -14,6 +14,7 @@ use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
 
 use crate::error::{Error, SuiRpcResult};
 use serde_json::Value;
+use shared_crypto::intent::Intent;
 use sui_adapter::execution_mode::Normal;
 pub use sui_json as json;
 
@@ -22,19 +23,29 @@ use sui_json_rpc::{
     CLIENT_SDK_TYPE_HEADER, CLIENT_SDK_VERSION_HEADER, CLIENT_TARGET_API_VERSION_HEADER,
 };
 pub use sui_json_rpc_types as rpc_types;
-use sui_json_rpc_types::{CheckpointId, ObjectsPage, SuiObjectDataOptions, SuiObjectResponse};
+use sui_json_rpc_types::{
+    CheckpointId, ObjectsPage, SuiObjectDataOptions, SuiObjectResponse, SuiTransactionData,
+    SuiTransactionResponse, SuiTransactionResponseOptions,
+};
+use sui_keys::keystore::{AccountKeystore, Keystore};
 use sui_transaction_builder::{DataReader, TransactionBuilder};
 pub use sui_types as types;
 use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::messages::{
+    ExecuteTransactionRequestType, Transaction, TransactionData, TransactionDataAPI,
+    TransactionKind, VerifiedTransaction,
+};
 pub mod apis;
 pub mod error;
 pub const SUI_COIN_TYPE: &str = "0x2::sui::SUI";
 const WAIT_FOR_TX_TIMEOUT_SEC: u64 = 60;
+const DEFAULT_MAX_CONCURRENT_EXECUTIONS: usize = 4;
 
 pub struct SuiClientBuilder {
     request_timeout: Duration,
     max_concurrent_requests: usize,
     ws_url: Option<String>,
+    gas_price_cache_ttl: Duration,
 }
 
 impl Default for SuiClientBuilder {
@@ -43,6 +54,7 @@ impl Default for SuiClientBuilder {
             request_timeout: Duration::from_secs(60),
             max_concurrent_requests: 256,
             ws_url: None,
+            gas_price_cache_ttl: Duration::from_secs(60),
         }
     }
 }
@@ -63,6 +75,12 @@ impl SuiClientBuilder {
         self
     }
 
+    /// How long [ReadApi::get_reference_gas_price] keeps returning a cached price at most.
+    pub fn gas_price_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.gas_price_cache_ttl = ttl;
+        self
+    }
+
     pub async fn build(self, http: impl AsRef<str>) -> SuiRpcResult<SuiClient> {
         let client_version = env!("CARGO_PKG_VERSION");
         let mut headers = HeaderMap::new();
@@ -102,7 +120,7 @@ impl SuiClientBuilder {
 
         let rpc = RpcClient { http, ws, info };
         let api = Arc::new(rpc);
-        let read_api = Arc::new(ReadApi::new(api.clone()));
+        let read_api = Arc::new(ReadApi::new(api.clone(), self.gas_price_cache_ttl));
         let quorum_driver = QuorumDriver::new(api.clone());
         let event_api = EventApi::new(api.clone());
         let transaction_builder = TransactionBuilder::new(read_api.clone());
@@ -245,6 +263,68 @@ impl SuiClient {
     }
 }
 
+impl SuiClient {
+    /// Build a transaction for `kind`, sign it with `sender`'s key from `keystore` and execute it,
+    /// waiting for local execution. The gas payment is chosen by
+    /// [TransactionBuilder::select_gas]: `gas` when provided, otherwise a gas coin of the sender
+    /// that is not an input of `kind`.
+    pub async fn build_sign_execute(
+        &self,
+        kind: TransactionKind,
+        sender: SuiAddress,
+        keystore: &Keystore,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+        options: SuiTransactionResponseOptions,
+    ) -> SuiRpcResult<SuiTransactionResponse> {
+        let gas_price = self.read_api.get_reference_gas_price().await?;
+        let input_objects = kind
+            .input_objects()?
+            .iter()
+            .map(|obj| obj.object_id())
+            .collect();
+        let gas = self
+            .transaction_builder
+            .select_gas(sender, gas, gas_budget, input_objects, gas_price)
+            .await
+            .map_err(|e| Error::DataError(e.to_string()))?;
+
+        let data =
+            TransactionData::new_with_gas_coins(kind, sender, vec![gas], gas_budget, gas_price);
+        let tx = sign_transaction(keystore, data)?;
+        self.quorum_driver
+            .execute_transaction(
+                tx,
+                options,
+                Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+            )
+            .await
+    }
+}
+
+/// Sign `data` with its sender's key from `keystore`, e.g. one loaded with [Keystore::load], and
+/// verify the resulting transaction.
+pub fn sign_transaction(
+    keystore: &Keystore,
+    data: TransactionData,
+) -> SuiRpcResult<VerifiedTransaction> {
+    let signature = keystore
+        .sign_secure(&data.sender(), &data, Intent::default())
+        .map_err(|e| Error::DataError(e.to_string()))?;
+    Transaction::from_data(data, Intent::default(), vec![signature])
+        .verify()
+        .map_err(|e| Error::DataError(e.to_string()))
+}
+
+/// Decode transaction data received as BCS bytes, e.g. from an offline builder, returning it
+/// along with a readable view of its sender, gas data and commands to check before signing.
+pub fn inspect_transaction(bytes: &[u8]) -> SuiRpcResult<(TransactionData, SuiTransactionData)> {
+    let data = TransactionData::from_bcs(bytes)?;
+    let view =
+        SuiTransactionData::try_from(data.clone()).map_err(|e| Error::DataError(e.to_string()))?;
+    Ok((data, view))
+}
+
 #[async_trait]
 impl DataReader for ReadApi {
     async fn get_owned_objects(
@@ -0,0 +1,24 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module dynamic_fields::dynamic_fields {
+    use sui::dynamic_field;
+    use sui::object::{Self, UID};
+    use sui::transfer;
+    use sui::tx_context::{Self, TxContext};
+
+    struct Parent has key {
+        id: UID,
+    }
+
+    /// Create a parent object owned by the sender, with `count` dynamic fields named `0..count`.
+    public entry fun create(count: u64, ctx: &mut TxContext) {
+        let parent = Parent { id: object::new(ctx) };
+        let i = 0;
+        while (i < count) {
+            dynamic_field::add(&mut parent.id, i, i);
+            i = i + 1;
+        };
+        transfer::transfer(parent, tx_context::sender(ctx))
+    }
+}
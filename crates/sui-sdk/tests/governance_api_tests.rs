@@ -0,0 +1,226 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use shared_crypto::intent::Intent;
+use sui_json_rpc_types::{StakeStatus, SuiTransactionEffectsAPI, SuiTransactionResponseOptions};
+use sui_sdk::SuiClientBuilder;
+use sui_types::error::UserInputError;
+use sui_types::messages::{ExecuteTransactionRequestType, Transaction};
+use test_utils::network::TestClusterBuilder;
+
+#[tokio::test]
+async fn test_get_stake_rewards_history() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new()
+        .with_epoch_duration_ms(10000)
+        .build()
+        .await?;
+    let address = test_cluster.get_address_0();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let system_state = client
+        .governance_api()
+        .get_latest_sui_system_state()
+        .await?;
+    let validator = system_state.active_validators[0].sui_address;
+    let coin = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data[0]
+        .coin_object_id;
+    let data = client
+        .transaction_builder()
+        .request_add_stake(
+            address,
+            vec![coin],
+            Some(1000000),
+            validator,
+            None,
+            None,
+            10000,
+        )
+        .await?;
+    let signature = test_cluster.sign_transaction(&address, &data);
+    client
+        .quorum_driver()
+        .execute_transaction(
+            Transaction::from_data(data, Intent::default(), vec![signature]).verify()?,
+            SuiTransactionResponseOptions::new(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    let stake = client.governance_api().get_stakes(address).await?[0].stakes[0].clone();
+
+    // Wait until the stake has been active across at least one epoch boundary.
+    tokio::time::timeout(Duration::from_secs(60), async {
+        while client
+            .governance_api()
+            .get_latest_sui_system_state()
+            .await
+            .unwrap()
+            .epoch
+            <= stake.stake_active_epoch
+        {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+    })
+    .await?;
+
+    let history = client
+        .governance_api()
+        .get_stake_rewards_history(stake.staked_sui_id)
+        .await?;
+    let epochs = history.iter().map(|(epoch, _)| *epoch).collect::<Vec<_>>();
+    assert!(epochs.len() >= 2);
+    assert_eq!(stake.stake_active_epoch, epochs[0]);
+    assert!(epochs.windows(2).all(|pair| pair[0] + 1 == pair[1]));
+
+    // The deltas add up to the estimated reward of the stake, as of the last reported epoch.
+    let epoch = client
+        .governance_api()
+        .get_latest_sui_system_state()
+        .await?
+        .epoch;
+    let stakes = client.governance_api().get_stakes(address).await?;
+    if epoch == *epochs.last().unwrap() {
+        let StakeStatus::Active { estimated_reward } = stakes[0].stakes[0].status else {
+            panic!("Stake should be active");
+        };
+        let total = history.iter().map(|(_, reward)| reward).sum::<i128>();
+        assert_eq!(estimated_reward as i128, total);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_system_state_typed() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let typed = client.governance_api().get_system_state_typed().await?;
+    let latest = client
+        .governance_api()
+        .get_latest_sui_system_state()
+        .await?;
+    assert_eq!(latest.epoch, typed.epoch);
+    assert_eq!(latest.system_state_version, typed.system_state_version);
+    assert_eq!(
+        latest.active_validators.len(),
+        typed.active_validators.len()
+    );
+    assert!(!typed.active_validators.is_empty());
+    assert!(typed.reference_gas_price > 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stake_below_minimum() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let min = client.governance_api().min_stake_amount().await?;
+    let validator = client
+        .governance_api()
+        .get_latest_sui_system_state()
+        .await?
+        .active_validators[0]
+        .sui_address;
+    let coin = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data[0]
+        .coin_object_id;
+
+    let err = client
+        .transaction_builder()
+        .request_add_stake(
+            address,
+            vec![coin],
+            Some(min - 1),
+            validator,
+            None,
+            None,
+            10000,
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        Some(&UserInputError::StakeBelowMinimum {
+            amount: min - 1,
+            min
+        }),
+        err.downcast_ref::<UserInputError>()
+    );
+
+    let data = client
+        .transaction_builder()
+        .request_add_stake(address, vec![coin], Some(min), validator, None, None, 10000)
+        .await?;
+    let signature = test_cluster.sign_transaction(&address, &data);
+    let response = client
+        .quorum_driver()
+        .execute_transaction(
+            Transaction::from_data(data, Intent::default(), vec![signature]).verify()?,
+            SuiTransactionResponseOptions::new().with_effects(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    assert!(response.effects.unwrap().status().is_ok());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_gas_price_history() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new()
+        .with_epoch_duration_ms(5000)
+        .build()
+        .await?;
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    // Wait for a few epoch changes.
+    tokio::time::timeout(Duration::from_secs(60), async {
+        while client
+            .governance_api()
+            .get_latest_sui_system_state()
+            .await
+            .unwrap()
+            .epoch
+            < 3
+        {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+    })
+    .await?;
+
+    let history = client.governance_api().get_gas_price_history(1, 3).await?;
+    let epochs = history.iter().map(|(epoch, _)| *epoch).collect::<Vec<_>>();
+    assert_eq!(vec![1, 2, 3], epochs);
+    assert!(history.iter().all(|(_, price)| *price > 0));
+    assert_eq!(
+        history[1].1,
+        client
+            .governance_api()
+            .get_reference_gas_price_at(2)
+            .await?
+    );
+
+    // The price of the genesis epoch isn't recorded by any epoch change.
+    assert!(client
+        .governance_api()
+        .get_reference_gas_price_at(0)
+        .await
+        .is_err());
+
+    Ok(())
+}
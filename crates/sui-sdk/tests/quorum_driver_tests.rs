@@ -0,0 +1,756 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use shared_crypto::intent::Intent;
+use sui_json_rpc_types::{
+    SuiCommand, SuiTransactionDataAPI, SuiTransactionEffects, SuiTransactionEffectsAPI,
+    SuiTransactionKind, SuiTransactionResponseOptions,
+};
+use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
+use sui_sdk::error::Error;
+use sui_sdk::{inspect_transaction, sign_transaction, SuiClientBuilder};
+use sui_types::crypto::SignatureScheme;
+use sui_types::error::UserInputError;
+use sui_types::messages::{
+    ExecuteTransactionRequestType, Transaction, TransactionData, TransactionDataAPI,
+    TransactionEffects, TransactionEffectsAPI, TransactionExpiration, TransactionKind,
+};
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use test_utils::network::TestClusterBuilder;
+
+#[tokio::test]
+async fn test_reject_expired_transaction() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new()
+        .with_epoch_duration_ms(5000)
+        .build()
+        .await?;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let epoch = client
+        .governance_api()
+        .get_latest_sui_system_state()
+        .await?
+        .epoch;
+    let coin = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data[0]
+        .coin_object_id;
+    let data = client
+        .transaction_builder()
+        .transfer_sui(address, coin, 10000, recipient, Some(50000))
+        .await?;
+    let data = client.transaction_builder().with_expiration(data, epoch);
+    assert_eq!(&TransactionExpiration::Epoch(epoch), data.expiration());
+    let signature = test_cluster.sign_transaction(&address, &data);
+    let tx = Transaction::from_data(data, Intent::default(), vec![signature]).verify()?;
+
+    // Wait for the network to move past the expiration epoch.
+    tokio::time::timeout(Duration::from_secs(60), async {
+        while client
+            .governance_api()
+            .get_latest_sui_system_state()
+            .await
+            .unwrap()
+            .epoch
+            <= epoch
+        {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+    })
+    .await?;
+
+    let err = client
+        .quorum_driver()
+        .execute_transaction(
+            tx,
+            SuiTransactionResponseOptions::new(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::TransactionExpired { expiration, .. } if expiration == epoch
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_execute_and_get_balance_changes() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let coin = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data[0]
+        .coin_object_id;
+    let data = client
+        .transaction_builder()
+        .transfer_sui(address, coin, 10000, recipient, Some(50000))
+        .await?;
+    let signature = test_cluster.sign_transaction(&address, &data);
+    let tx = Transaction::from_data(data, Intent::default(), vec![signature]).verify()?;
+    let digest = *tx.digest();
+
+    let balance_changes = client
+        .quorum_driver()
+        .execute_and_get_balance_changes(tx)
+        .await?;
+    assert!(balance_changes
+        .iter()
+        .any(|change| change.address_owner() == Some(recipient) && change.amount == 50000));
+
+    // Same changes as the full content of the transaction.
+    let response = client
+        .read_api()
+        .get_transaction_with_options(digest, SuiTransactionResponseOptions::full_content())
+        .await?;
+    assert_eq!(
+        serde_json::to_value(response.balance_changes.unwrap())?,
+        serde_json::to_value(balance_changes)?
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_build_sign_execute() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+    let keystore = &test_cluster.wallet.config.keystore;
+    let gas = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data[0]
+        .coin_object_id;
+
+    // Let the client select gas first, then inject a gas coin.
+    for gas in [None, Some(gas)] {
+        let sender_balance = client.coin_read_api().get_balance(address, None).await?;
+        let recipient_balance = client.coin_read_api().get_balance(recipient, None).await?;
+
+        let kind = {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            builder.pay_sui(vec![recipient], vec![1000])?;
+            TransactionKind::programmable(builder.finish())
+        };
+        let response = client
+            .build_sign_execute(
+                kind,
+                address,
+                keystore,
+                gas,
+                10000,
+                SuiTransactionResponseOptions::new().with_effects(),
+            )
+            .await?;
+        let effects = response.effects.unwrap();
+        assert!(effects.status().is_ok());
+        if let Some(gas) = gas {
+            assert_eq!(gas, effects.gas_object().reference.object_id);
+        }
+
+        let gas_used = effects.gas_used().net_gas_usage() as i128;
+        let new_sender_balance = client.coin_read_api().get_balance(address, None).await?;
+        let new_recipient_balance = client.coin_read_api().get_balance(recipient, None).await?;
+        assert_eq!(
+            sender_balance.total_balance as i128 - 1000 - gas_used,
+            new_sender_balance.total_balance as i128
+        );
+        assert_eq!(
+            recipient_balance.total_balance + 1000,
+            new_recipient_balance.total_balance
+        );
+    }
+
+    // An injected gas coin is checked like a selected one, it has to cover the budget.
+    let kind = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.pay_sui(vec![recipient], vec![1000])?;
+        TransactionKind::programmable(builder.finish())
+    };
+    let result = client
+        .build_sign_execute(
+            kind,
+            address,
+            keystore,
+            Some(gas),
+            u64::MAX,
+            SuiTransactionResponseOptions::new(),
+        )
+        .await;
+    assert!(matches!(result, Err(Error::DataError(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_execute_with_raw_effects() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let coin = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data[0]
+        .coin_object_id;
+    let data = client
+        .transaction_builder()
+        .transfer_sui(address, coin, 10000, recipient, Some(50000))
+        .await?;
+    let signature = test_cluster.sign_transaction(&address, &data);
+    let tx = Transaction::from_data(data, Intent::default(), vec![signature]).verify()?;
+    let digest = *tx.digest();
+
+    let options = SuiTransactionResponseOptions::new()
+        .with_effects()
+        .with_raw_effects();
+    let response = client
+        .quorum_driver()
+        .execute_transaction(
+            tx,
+            options.clone(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    let raw_effects: TransactionEffects = bcs::from_bytes(&response.raw_effects.unwrap())?;
+    assert_eq!(&digest, raw_effects.transaction_digest());
+    assert_eq!(
+        response.effects.unwrap(),
+        SuiTransactionEffects::try_from(raw_effects.clone())?
+    );
+
+    // The read api returns the same bytes.
+    let response = client
+        .read_api()
+        .get_transaction_with_options(digest, options)
+        .await?;
+    assert_eq!(bcs::to_bytes(&raw_effects)?, response.raw_effects.unwrap());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_execute_with_raw_input() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let coin = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data[0]
+        .coin_object_id;
+    let data = client
+        .transaction_builder()
+        .transfer_sui(address, coin, 10000, recipient, Some(50000))
+        .await?;
+    let signature = test_cluster.sign_transaction(&address, &data);
+    let tx = Transaction::from_data(data.clone(), Intent::default(), vec![signature]).verify()?;
+    let digest = *tx.digest();
+
+    let options = SuiTransactionResponseOptions::new().with_raw_input();
+    let response = client
+        .quorum_driver()
+        .execute_transaction(
+            tx,
+            options.clone(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    let raw_transaction = response.raw_transaction.unwrap();
+    assert_eq!(data, bcs::from_bytes::<TransactionData>(&raw_transaction)?);
+    assert_eq!(bcs::to_bytes(&data)?, raw_transaction);
+
+    // The read api returns the same bytes.
+    let response = client
+        .read_api()
+        .get_transaction_with_options(digest, options)
+        .await?;
+    assert_eq!(raw_transaction, response.raw_transaction.unwrap());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_prepare_exact_coin() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let (data, coin_id) = client
+        .transaction_builder()
+        .prepare_exact_coin(address, "0x2::sui::SUI".to_string(), 123456, 10000)
+        .await?;
+    let signature = test_cluster.sign_transaction(&address, &data);
+    let response = client
+        .quorum_driver()
+        .execute_transaction(
+            Transaction::from_data(data, Intent::default(), vec![signature]).verify()?,
+            SuiTransactionResponseOptions::new().with_effects(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    assert!(response.effects.unwrap().status().is_ok());
+
+    let coins = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?;
+    assert!(coins
+        .data
+        .iter()
+        .any(|coin| coin.coin_object_id == coin_id && coin.balance == 123456));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_submit_transaction() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let coin = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data[0]
+        .coin_object_id;
+    let data = client
+        .transaction_builder()
+        .transfer_sui(address, coin, 10000, recipient, Some(50000))
+        .await?;
+    let signature = test_cluster.sign_transaction(&address, &data);
+    let tx = Transaction::from_data(data, Intent::default(), vec![signature]).verify()?;
+    let expected_digest = *tx.digest();
+
+    let digest = client.quorum_driver().submit_transaction(tx).await?;
+    assert_eq!(expected_digest, digest);
+
+    client.quorum_driver().wait_for_transaction(digest).await?;
+    let response = client
+        .read_api()
+        .get_transaction_with_options(digest, SuiTransactionResponseOptions::new().with_effects())
+        .await?;
+    assert!(response.effects.unwrap().status().is_ok());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_execute_with_gas_price() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let reference_gas_price = client.read_api().get_reference_gas_price().await?;
+    let coin = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data[0]
+        .coin_object_id;
+    let data = client
+        .transaction_builder()
+        .transfer_sui(address, coin, 10000, recipient, Some(50000))
+        .await?;
+
+    // Under the reference gas price.
+    assert!(client
+        .transaction_builder()
+        .with_gas_price(data.clone(), reference_gas_price - 1)
+        .await
+        .is_err());
+
+    let data = client
+        .transaction_builder()
+        .with_gas_price(data, reference_gas_price * 2)
+        .await?;
+    assert_eq!(reference_gas_price * 2, data.gas_price());
+
+    let signature = test_cluster.sign_transaction(&address, &data);
+    let response = client
+        .quorum_driver()
+        .execute_transaction(
+            Transaction::from_data(data, Intent::default(), vec![signature]).verify()?,
+            SuiTransactionResponseOptions::new().with_input(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    assert_eq!(
+        reference_gas_price * 2,
+        response.transaction.unwrap().data.gas_data().price
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sign_with_loaded_keystore() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    // A keystore file holding a single new key.
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("sui.keystore");
+    let (owner, _, _) = Keystore::from(FileBasedKeystore::new(&path)?)
+        .generate_and_add_new_key(SignatureScheme::ED25519, None)?;
+
+    assert!(Keystore::load(&dir.path().join("missing.keystore")).is_err());
+    let keystore = Keystore::load(&path)?;
+    assert_eq!(vec![owner], keystore.addresses());
+
+    // Fund the new address, then have it send SUI back, signing with the loaded keystore.
+    let coin = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data[0]
+        .coin_object_id;
+    let data = client
+        .transaction_builder()
+        .transfer_sui(address, coin, 10000, owner, Some(1_000_000_000))
+        .await?;
+    let signature = test_cluster.sign_transaction(&address, &data);
+    client
+        .quorum_driver()
+        .execute_transaction(
+            Transaction::from_data(data, Intent::default(), vec![signature]).verify()?,
+            SuiTransactionResponseOptions::new(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+
+    let coin = client
+        .coin_read_api()
+        .get_coins(owner, None, None, None)
+        .await?
+        .data[0]
+        .coin_object_id;
+    let data = client
+        .transaction_builder()
+        .transfer_sui(owner, coin, 10000, address, Some(1000))
+        .await?;
+    let tx = sign_transaction(&keystore, data)?;
+    let response = client
+        .quorum_driver()
+        .execute_transaction(
+            tx,
+            SuiTransactionResponseOptions::new().with_effects(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    assert!(response.effects.unwrap().status().is_ok());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_execute_transactions() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    // Each transfer pays from and for gas with its own coin, so they are independent.
+    let coins = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data;
+    let mut txs = vec![];
+    for (i, coin) in coins.iter().take(3).enumerate() {
+        let data = client
+            .transaction_builder()
+            .transfer_sui(
+                address,
+                coin.coin_object_id,
+                10000,
+                recipient,
+                Some(i as u64 + 1),
+            )
+            .await?;
+        let signature = test_cluster.sign_transaction(&address, &data);
+        txs.push(Transaction::from_data(data, Intent::default(), vec![signature]).verify()?);
+    }
+    let digests = txs.iter().map(|tx| *tx.digest()).collect::<Vec<_>>();
+
+    let results = client
+        .quorum_driver()
+        .execute_transactions(
+            txs,
+            SuiTransactionResponseOptions::new().with_effects(),
+            None,
+        )
+        .await;
+    assert_eq!(digests.len(), results.len());
+    for (digest, result) in digests.into_iter().zip(results) {
+        let response = result?;
+        assert_eq!(digest, response.digest);
+        assert!(response.effects.unwrap().status().is_ok());
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_execute_transactions_bounded_concurrency() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    // One independent transfer per coin, more than can run at once.
+    let coins = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data;
+    assert!(coins.len() > 2);
+    let mut txs = vec![];
+    for (i, coin) in coins.iter().enumerate() {
+        let data = client
+            .transaction_builder()
+            .transfer_sui(
+                address,
+                coin.coin_object_id,
+                10000,
+                recipient,
+                Some(i as u64 + 1),
+            )
+            .await?;
+        let signature = test_cluster.sign_transaction(&address, &data);
+        txs.push(Transaction::from_data(data, Intent::default(), vec![signature]).verify()?);
+    }
+    let digests = txs.iter().map(|tx| *tx.digest()).collect::<Vec<_>>();
+
+    let results = client
+        .quorum_driver()
+        .execute_transactions(
+            txs,
+            SuiTransactionResponseOptions::new().with_effects(),
+            Some(2),
+        )
+        .await;
+    let executed = results
+        .into_iter()
+        .map(|result| {
+            let response = result?;
+            assert!(response.effects.unwrap().status().is_ok());
+            Ok(response.digest)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    assert_eq!(digests, executed);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_prepare_gas_pool() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let coins = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?;
+    let (source, balance) = (coins.data[0].coin_object_id, coins.data[0].balance);
+    let err = client
+        .transaction_builder()
+        .prepare_gas_pool(address, source, 5, balance, 10000)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<UserInputError>(),
+        Some(UserInputError::InsufficientFunds { .. })
+    ));
+
+    let data = client
+        .transaction_builder()
+        .prepare_gas_pool(address, source, 5, 1_000_000, 10000)
+        .await?;
+    let signature = test_cluster.sign_transaction(&address, &data);
+    let response = client
+        .quorum_driver()
+        .execute_transaction(
+            Transaction::from_data(data, Intent::default(), vec![signature]).verify()?,
+            SuiTransactionResponseOptions::new().with_effects(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    let effects = response.effects.unwrap();
+    assert!(effects.status().is_ok());
+    let created = effects
+        .created()
+        .iter()
+        .map(|obj| obj.reference.object_id)
+        .collect::<Vec<_>>();
+    assert_eq!(5, created.len());
+
+    let coins = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?;
+    for id in created {
+        assert!(coins
+            .data
+            .iter()
+            .any(|coin| coin.coin_object_id == id && coin.balance == 1_000_000));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_inspect_transaction() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+    let keystore = &test_cluster.wallet.config.keystore;
+
+    let coin = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data[0]
+        .coin_object_id;
+    let data = client
+        .transaction_builder()
+        .transfer_sui(address, coin, 10000, recipient, Some(50000))
+        .await?;
+    let bytes = bcs::to_bytes(&data)?;
+
+    let err = inspect_transaction(&bytes[..bytes.len() - 1]).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::UserInputError(UserInputError::MalformedTransactionData { .. })
+    ));
+
+    let (decoded, view) = inspect_transaction(&bytes)?;
+    assert_eq!(data, decoded);
+    assert_eq!(&address, view.sender());
+    assert_eq!(10000, view.gas_data().budget);
+    let SuiTransactionKind::ProgrammableTransaction(pt) = view.transaction() else {
+        panic!("Expected a programmable transaction");
+    };
+    assert!(matches!(
+        &pt.commands[..],
+        [SuiCommand::SplitCoin(..), SuiCommand::TransferObjects(..)]
+    ));
+
+    // The decoded data can be signed and executed as is.
+    let response = client
+        .quorum_driver()
+        .execute_transaction(
+            sign_transaction(keystore, decoded)?,
+            SuiTransactionResponseOptions::new().with_effects(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    assert!(response.effects.unwrap().status().is_ok());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_transfer_last_coin() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+    let execute = |data| {
+        let signature = test_cluster.sign_transaction(&address, &data);
+        client.quorum_driver().execute_transaction(
+            Transaction::from_data(data, Intent::default(), vec![signature])
+                .verify()
+                .unwrap(),
+            SuiTransactionResponseOptions::new().with_effects(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+    };
+
+    // The genesis gives several coins to the address.
+    assert!(client
+        .transaction_builder()
+        .transfer_last_coin(address, recipient, 10000)
+        .await
+        .is_err());
+
+    // Merge them all into one.
+    let coins = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data
+        .iter()
+        .map(|coin| coin.coin_object_id)
+        .collect();
+    let data = client
+        .transaction_builder()
+        .pay_all_sui(address, coins, address, 10000)
+        .await?;
+    assert!(execute(data).await?.effects.unwrap().status().is_ok());
+
+    let err = client
+        .transaction_builder()
+        .transfer_last_coin(address, recipient, u64::MAX)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<UserInputError>(),
+        Some(UserInputError::GasBalanceTooLow { .. })
+    ));
+
+    let data = client
+        .transaction_builder()
+        .transfer_last_coin(address, recipient, 10000)
+        .await?;
+    assert!(execute(data).await?.effects.unwrap().status().is_ok());
+    assert!(client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data
+        .is_empty());
+
+    Ok(())
+}
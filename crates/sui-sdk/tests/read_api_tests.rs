@@ -0,0 +1,1284 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::anyhow;
+use futures::{StreamExt, TryStreamExt};
+use jsonrpsee::server::ServerBuilder;
+use jsonrpsee::types::error::CallError;
+use jsonrpsee::RpcModule;
+use move_core_types::language_storage::TypeTag;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use shared_crypto::intent::Intent;
+use sui_framework_build::compiled_package::BuildConfig;
+use sui_json_rpc::api::MAX_GET_OWNED_OBJECT_LIMIT;
+use sui_json_rpc_types::{
+    Checkpoint, CheckpointId, DynamicFieldPage, ObjectChange, SuiMoveNormalizedType,
+    SuiMoveVisibility, SuiObjectDataOptions, SuiTransactionEffectsAPI,
+    SuiTransactionResponseOptions, SuiTransactionResponseQuery,
+};
+use sui_sdk::apis::StaleInput;
+use sui_sdk::error::Error;
+use sui_sdk::json::SuiJsonValue;
+use sui_sdk::SuiClientBuilder;
+use sui_types::base_types::{random_object_ref, ObjectID, SuiAddress};
+use sui_types::digests::CheckpointDigest;
+use sui_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName, DynamicFieldType};
+use sui_types::error::CHECKPOINT_NOT_FOUND_MSG_PREFIX;
+use sui_types::gas_coin::GAS;
+use sui_types::messages::{
+    ExecuteTransactionRequestType, InputObjectKind, Transaction, TransactionDataAPI,
+    TransactionKind,
+};
+use sui_types::parse_sui_type_tag;
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::query::TransactionFilter;
+use sui_types::sui_system_state::{get_sui_system_state, SuiSystemStateTrait};
+use test_utils::authority::test_authority_configs;
+use test_utils::network::{TestCluster, TestClusterBuilder};
+use test_utils::transaction::{publish_package_with_wallet, submit_move_transaction};
+
+#[tokio::test]
+async fn test_refresh_transaction_inputs() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let rpc_url = test_cluster.rpc_url();
+
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+    let coins = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data;
+    let coin = coins[0].coin_object_id;
+    let gas = coins[1].coin_object_id;
+
+    // Prepare a transaction for offline signing.
+    let stale_data = client
+        .transaction_builder()
+        .transfer_object(address, coin, Some(gas), 10000, recipient)
+        .await?;
+
+    // Advance the versions of the prepared inputs before the transaction is submitted.
+    let split_data = client
+        .transaction_builder()
+        .split_coin(address, coin, vec![1000], Some(gas), 10000)
+        .await?;
+    let signature = test_cluster.sign_transaction(&address, &split_data);
+    client
+        .quorum_driver()
+        .execute_transaction(
+            Transaction::from_data(split_data, Intent::default(), vec![signature]).verify()?,
+            SuiTransactionResponseOptions::new(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+
+    let (data, changed) = client
+        .read_api()
+        .refresh_transaction_inputs(stale_data.clone())
+        .await?;
+
+    assert_eq!(2, changed.len());
+    assert!(changed.contains(&coin));
+    assert!(changed.contains(&gas));
+
+    let coin_ref = client
+        .read_api()
+        .get_object_with_options(coin, SuiObjectDataOptions::new())
+        .await?
+        .into_object()?
+        .object_ref();
+    let gas_ref = client
+        .read_api()
+        .get_object_with_options(gas, SuiObjectDataOptions::new())
+        .await?
+        .into_object()?
+        .object_ref();
+    assert!(data
+        .input_objects()?
+        .contains(&InputObjectKind::ImmOrOwnedMoveObject(coin_ref)));
+    assert_eq!(vec![gas_ref], data.gas());
+    assert_eq!(stale_data.gas_budget(), data.gas_budget());
+
+    // Refreshing up to date inputs is a no-op.
+    let (refreshed, changed) = client
+        .read_api()
+        .refresh_transaction_inputs(data.clone())
+        .await?;
+    assert!(changed.is_empty());
+    assert_eq!(data, refreshed);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_validate_input_objects() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let rpc_url = test_cluster.rpc_url();
+
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+    let coins = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data;
+    let coin = &coins[0];
+    let gas = coins[1].coin_object_id;
+    let split_gas = coins[2].coin_object_id;
+
+    // Prepare a transaction for offline signing, its inputs are current.
+    let data = client
+        .transaction_builder()
+        .transfer_object(address, coin.coin_object_id, Some(gas), 10000, recipient)
+        .await?;
+    assert!(client
+        .read_api()
+        .validate_input_objects(&data)
+        .await?
+        .is_empty());
+
+    // Advance the version of the transferred coin only.
+    let split_data = client
+        .transaction_builder()
+        .split_coin(
+            address,
+            coin.coin_object_id,
+            vec![1000],
+            Some(split_gas),
+            10000,
+        )
+        .await?;
+    let signature = test_cluster.sign_transaction(&address, &split_data);
+    client
+        .quorum_driver()
+        .execute_transaction(
+            Transaction::from_data(split_data, Intent::default(), vec![signature]).verify()?,
+            SuiTransactionResponseOptions::new(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+
+    let current = client
+        .read_api()
+        .get_object_with_options(coin.coin_object_id, SuiObjectDataOptions::new())
+        .await?
+        .into_object()?
+        .version;
+    assert_eq!(
+        vec![StaleInput {
+            object_id: coin.coin_object_id,
+            expected: coin.version,
+            current,
+        }],
+        client.read_api().validate_input_objects(&data).await?
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_gas_coins_sorted() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    // Split a coin so the values differ.
+    let coins = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data;
+    let data = client
+        .transaction_builder()
+        .split_coin(
+            address,
+            coins[0].coin_object_id,
+            vec![1000, 5000],
+            Some(coins[1].coin_object_id),
+            10000,
+        )
+        .await?;
+    let signature = test_cluster.sign_transaction(&address, &data);
+    client
+        .quorum_driver()
+        .execute_transaction(
+            Transaction::from_data(data, Intent::default(), vec![signature]).verify()?,
+            SuiTransactionResponseOptions::new(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+
+    let total_balance = client
+        .coin_read_api()
+        .get_balance(address, None)
+        .await?
+        .total_balance;
+
+    let ascending = client
+        .read_api()
+        .get_gas_coins_sorted(address, false)
+        .await?;
+    assert!(ascending.windows(2).all(|w| w[0].1 <= w[1].1));
+    let sum: u128 = ascending.iter().map(|(_, value)| *value as u128).sum();
+    assert_eq!(total_balance, sum);
+
+    let descending = client
+        .read_api()
+        .get_gas_coins_sorted(address, true)
+        .await?;
+    assert!(descending.windows(2).all(|w| w[0].1 >= w[1].1));
+    assert_eq!(ascending.len(), descending.len());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_object_at_version() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let coins = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data;
+    let coin = coins[0].coin_object_id;
+    let old_ref = coins[0].object_ref();
+
+    // Mutate the coin so that a newer version exists.
+    let data = client
+        .transaction_builder()
+        .split_coin(
+            address,
+            coin,
+            vec![1000],
+            Some(coins[1].coin_object_id),
+            10000,
+        )
+        .await?;
+    let signature = test_cluster.sign_transaction(&address, &data);
+    client
+        .quorum_driver()
+        .execute_transaction(
+            Transaction::from_data(data, Intent::default(), vec![signature]).verify()?,
+            SuiTransactionResponseOptions::new(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    let new_ref = client
+        .read_api()
+        .get_object_with_options(coin, SuiObjectDataOptions::new())
+        .await?
+        .into_object()?
+        .object_ref();
+    assert!(new_ref.1 > old_ref.1);
+
+    for object_ref in [old_ref, new_ref] {
+        let object = client
+            .read_api()
+            .get_object_at_version(coin, object_ref.1, SuiObjectDataOptions::new())
+            .await?
+            .into_object()?;
+        assert_eq!(object_ref, object.object_ref());
+    }
+
+    // Versions past the latest one cannot be read.
+    let mut too_high = new_ref.1;
+    too_high.increment();
+    let result = client
+        .read_api()
+        .get_object_at_version(coin, too_high, SuiObjectDataOptions::new())
+        .await;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_transactions_for_address() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    // Both addresses send and receive, so their histories interleave the two indexes.
+    let mut digests = vec![];
+    for (sender, receiver) in [
+        (address, recipient),
+        (recipient, address),
+        (address, recipient),
+    ] {
+        // Pay with the largest coin, not one of the small coins received earlier.
+        let coin = client
+            .coin_read_api()
+            .get_coins(sender, None, None, None)
+            .await?
+            .data
+            .into_iter()
+            .max_by_key(|coin| coin.balance)
+            .unwrap()
+            .coin_object_id;
+        let data = client
+            .transaction_builder()
+            .transfer_sui(sender, coin, 10000, receiver, Some(1000))
+            .await?;
+        let signature = test_cluster.sign_transaction(&sender, &data);
+        let response = client
+            .quorum_driver()
+            .execute_transaction(
+                Transaction::from_data(data, Intent::default(), vec![signature]).verify()?,
+                SuiTransactionResponseOptions::new(),
+                Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+            )
+            .await?;
+        digests.push(response.digest);
+    }
+
+    for owner in [address, recipient] {
+        for descending in [true, false] {
+            let all = client
+                .read_api()
+                .get_transactions_for_address(owner, None, None, descending)
+                .await?
+                .data
+                .into_iter()
+                .map(|tx| tx.digest)
+                .collect::<Vec<_>>();
+            for digest in &digests {
+                assert!(all.contains(digest));
+            }
+
+            // Walking the history one transaction at a time gives the same transactions.
+            let mut walked = vec![];
+            let mut cursor = None;
+            loop {
+                let page = client
+                    .read_api()
+                    .get_transactions_for_address(owner, cursor, Some(1), descending)
+                    .await?;
+                walked.extend(page.data.iter().map(|tx| tx.digest));
+                if !page.has_next_page {
+                    break;
+                }
+                cursor = page.next_cursor;
+            }
+            assert_eq!(all, walked);
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_dynamic_fields() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/dynamic_fields");
+    let modules = BuildConfig::new_for_testing()
+        .build(path)?
+        .get_package_bytes(/* with_unpublished_deps */ false);
+    let package = publish_package_with_wallet(&test_cluster.wallet, address, modules).await;
+
+    let response = submit_move_transaction(
+        &test_cluster.wallet,
+        "dynamic_fields",
+        "create",
+        package.0,
+        vec![SuiJsonValue::new(json!("10"))?],
+        address,
+        None,
+    )
+    .await;
+    let parent = response
+        .object_changes
+        .unwrap()
+        .into_iter()
+        .find_map(|change| match change {
+            ObjectChange::Created {
+                object_id,
+                object_type,
+                ..
+            } if object_type.to_string().contains("::dynamic_fields::Parent") => Some(object_id),
+            _ => None,
+        })
+        .unwrap();
+
+    // Walk the pages by following `next_cursor`.
+    let mut fields = vec![];
+    let mut cursor = None;
+    loop {
+        let page = client
+            .read_api()
+            .get_dynamic_fields(parent, cursor, Some(3))
+            .await?;
+        fields.extend(page.data);
+        if !page.has_next_page {
+            break;
+        }
+        cursor = page.next_cursor;
+    }
+    assert_eq!(10, fields.len());
+
+    let streamed = client
+        .read_api()
+        .get_dynamic_fields_stream(parent, None)
+        .map_ok(|field| field.object_id)
+        .try_collect::<Vec<_>>()
+        .await?;
+    assert_eq!(
+        fields
+            .iter()
+            .map(|field| field.object_id)
+            .collect::<Vec<_>>(),
+        streamed
+    );
+
+    for i in 0..10u64 {
+        let field = fields
+            .iter()
+            .find(|field| field.bcs_name == bcs::to_bytes(&i).unwrap())
+            .unwrap();
+        let object = client
+            .read_api()
+            .get_dynamic_field_object(
+                parent,
+                DynamicFieldName {
+                    type_: TypeTag::U64,
+                    value: json!(i.to_string()),
+                },
+            )
+            .await?
+            .into_object()?;
+        assert_eq!(field.object_id, object.object_id);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_dynamic_fields_stream_errors() -> Result<(), anyhow::Error> {
+    // A mock fullnode that serves a first page of dynamic fields, and fails to serve the next.
+    let (object_id, version, digest) = random_object_ref();
+    let field = DynamicFieldInfo {
+        name: DynamicFieldName {
+            type_: TypeTag::U64,
+            value: json!("0"),
+        },
+        bcs_name: bcs::to_bytes(&0u64)?,
+        type_: DynamicFieldType::DynamicField,
+        object_type: "u64".to_string(),
+        object_id,
+        version,
+        digest,
+    };
+    let mut module = RpcModule::new(field);
+    module.register_method("rpc.discover", |_, _| {
+        Ok(json!({ "info": { "version": env!("CARGO_PKG_VERSION") }, "methods": [] }))
+    })?;
+    module.register_method("sui_getDynamicFields", |params, field| {
+        let mut params = params.sequence();
+        let _parent: ObjectID = params.next()?;
+        let cursor: Option<ObjectID> = params.optional_next()?;
+        match cursor {
+            None => Ok(DynamicFieldPage {
+                data: vec![field.clone()],
+                next_cursor: Some(field.object_id),
+                has_next_page: true,
+            }),
+            Some(_) => Err(CallError::Failed(anyhow!("Database is unavailable")).into()),
+        }
+    })?;
+    let server = ServerBuilder::default().build("127.0.0.1:0").await?;
+    let rpc_url = format!("http://{}", server.local_addr()?);
+    let _handle = server.start(module)?;
+
+    // The error is yielded after the first page, and ends the stream.
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+    let streamed = client
+        .read_api()
+        .get_dynamic_fields_stream(ObjectID::random(), None)
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(2, streamed.len());
+    assert_eq!(object_id, streamed[0].as_ref().unwrap().object_id);
+    assert!(matches!(streamed[1], Err(Error::RpcError(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_wait_for_balance() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = SuiAddress::random_for_testing_only();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    // Nothing has been sent to the recipient yet.
+    let err = client
+        .read_api()
+        .wait_for_balance(recipient, None, 1, Duration::from_secs(1))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::WaitForBalanceTimeout { balance: 0, .. }
+    ));
+
+    let coin = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data[0]
+        .coin_object_id;
+    let data = client
+        .transaction_builder()
+        .transfer_sui(address, coin, 10000, recipient, Some(50000))
+        .await?;
+    let signature = test_cluster.sign_transaction(&address, &data);
+    client
+        .quorum_driver()
+        .execute_transaction(
+            Transaction::from_data(data, Intent::default(), vec![signature]).verify()?,
+            SuiTransactionResponseOptions::new(),
+            Some(ExecuteTransactionRequestType::WaitForEffectsCert),
+        )
+        .await?;
+
+    let balance = client
+        .read_api()
+        .wait_for_balance(recipient, None, 50000, Duration::from_secs(30))
+        .await?;
+    assert_eq!(50000, balance);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_wait_for_epoch() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new()
+        .with_epoch_duration_ms(5000)
+        .build()
+        .await?;
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let epoch = client
+        .governance_api()
+        .get_latest_sui_system_state()
+        .await?
+        .epoch;
+    // The cluster has just started, the next epoch is seconds away.
+    let err = client
+        .read_api()
+        .wait_for_epoch(epoch + 1, Duration::from_millis(500))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::WaitForEpochTimeout { target, .. } if target == epoch + 1
+    ));
+
+    let reached = client
+        .read_api()
+        .wait_for_epoch(epoch + 1, Duration::from_secs(60))
+        .await?;
+    assert!(reached > epoch);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reference_gas_price_cache() -> Result<(), anyhow::Error> {
+    // Serve the reference gas price from a mock fullnode that counts how often it is queried.
+    let configs = test_authority_configs();
+    let system_state =
+        get_sui_system_state(&configs.genesis.objects())?.into_sui_system_state_summary();
+    let reference_gas_price = system_state.reference_gas_price;
+
+    let requests = Arc::new(AtomicUsize::new(0));
+    let price = Arc::new(AtomicU64::new(reference_gas_price));
+    let mut module = RpcModule::new((requests.clone(), price.clone()));
+    module.register_method("rpc.discover", |_, _| {
+        Ok(json!({ "info": { "version": env!("CARGO_PKG_VERSION") }, "methods": [] }))
+    })?;
+    module.register_method("sui_getReferenceGasPrice", |_, (requests, price)| {
+        requests.fetch_add(1, Ordering::SeqCst);
+        Ok(price.load(Ordering::SeqCst))
+    })?;
+    module.register_method("sui_getLatestSuiSystemState", move |_, _| {
+        Ok(system_state.clone())
+    })?;
+    let server = ServerBuilder::default().build("127.0.0.1:0").await?;
+    let rpc_url = format!("http://{}", server.local_addr()?);
+    let _handle = server.start(module)?;
+
+    let ttl = Duration::from_secs(2);
+    let client = SuiClientBuilder::default()
+        .gas_price_cache_ttl(ttl)
+        .build(rpc_url)
+        .await?;
+    assert_eq!(
+        reference_gas_price,
+        client.read_api().get_reference_gas_price().await?
+    );
+    assert_eq!(
+        reference_gas_price,
+        client.read_api().get_reference_gas_price().await?
+    );
+    assert_eq!(1, requests.load(Ordering::SeqCst));
+
+    // Seeing the network reach an epoch refreshes the price once.
+    client
+        .read_api()
+        .wait_for_epoch(0, Duration::from_secs(10))
+        .await?;
+    client.read_api().get_reference_gas_price().await?;
+    client.read_api().get_reference_gas_price().await?;
+    assert_eq!(2, requests.load(Ordering::SeqCst));
+
+    client.read_api().invalidate_gas_price_cache();
+    client.read_api().get_reference_gas_price().await?;
+    assert_eq!(3, requests.load(Ordering::SeqCst));
+
+    // An epoch change the client doesn't see is picked up once the cached price expires.
+    price.store(reference_gas_price + 1, Ordering::SeqCst);
+    tokio::time::sleep(ttl).await;
+    assert_eq!(
+        reference_gas_price + 1,
+        client.read_api().get_reference_gas_price().await?
+    );
+    assert_eq!(4, requests.load(Ordering::SeqCst));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_owned_objects_clamps_limit() -> Result<(), anyhow::Error> {
+    // Record the limits received by a mock fullnode.
+    let limits = Arc::new(Mutex::new(vec![]));
+    let mut module = RpcModule::new(limits.clone());
+    module.register_method("rpc.discover", |_, _| {
+        Ok(json!({ "info": { "version": env!("CARGO_PKG_VERSION") }, "methods": [] }))
+    })?;
+    module.register_method("sui_getOwnedObjects", |params, limits| {
+        let params: Vec<Value> = params.parse()?;
+        limits.lock().unwrap().push(params[3].clone());
+        Ok(json!({ "data": [], "nextCursor": null, "hasNextPage": false }))
+    })?;
+    let server = ServerBuilder::default().build("127.0.0.1:0").await?;
+    let rpc_url = format!("http://{}", server.local_addr()?);
+    let _handle = server.start(module)?;
+
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+    let address = SuiAddress::random_for_testing_only();
+    for limit in [Some(10_000), Some(10), None] {
+        client
+            .read_api()
+            .get_owned_objects(address, None, None, limit, None)
+            .await?;
+    }
+    assert_eq!(
+        vec![json!(MAX_GET_OWNED_OBJECT_LIMIT), json!(10), Value::Null],
+        *limits.lock().unwrap()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_subscribe_checkpoints() -> Result<(), anyhow::Error> {
+    // Short epochs make sure new checkpoints keep being produced.
+    let test_cluster = TestClusterBuilder::new()
+        .with_epoch_duration_ms(5000)
+        .build()
+        .await?;
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    // Starting from genesis, historical checkpoints are fetched before new ones are awaited.
+    let checkpoints = tokio::time::timeout(
+        Duration::from_secs(60),
+        client
+            .read_api()
+            .subscribe_checkpoints(Some(0))
+            .take(5)
+            .collect::<Vec<_>>(),
+    )
+    .await?;
+    let sequence_numbers = checkpoints
+        .into_iter()
+        .map(|checkpoint| checkpoint.map(|checkpoint| checkpoint.sequence_number))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(vec![0, 1, 2, 3, 4], sequence_numbers);
+
+    // Without a start, the stream begins at the latest checkpoint.
+    let latest = client
+        .read_api()
+        .get_latest_checkpoint_sequence_number()
+        .await?;
+    let mut stream = Box::pin(client.read_api().subscribe_checkpoints(None));
+    let first = stream.next().await.unwrap()?.sequence_number;
+    let second = stream.next().await.unwrap()?.sequence_number;
+    assert!(first >= latest);
+    assert_eq!(first + 1, second);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_latest_checkpoint_sequence_number() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let before = client
+        .read_api()
+        .get_latest_checkpoint_sequence_number()
+        .await?;
+    let coin = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data[0]
+        .coin_object_id;
+    let data = client
+        .transaction_builder()
+        .transfer_sui(
+            address,
+            coin,
+            10000,
+            SuiAddress::random_for_testing_only(),
+            Some(1000),
+        )
+        .await?;
+    let signature = test_cluster.sign_transaction(&address, &data);
+    let digest = client
+        .quorum_driver()
+        .execute_transaction(
+            Transaction::from_data(data, Intent::default(), vec![signature]).verify()?,
+            SuiTransactionResponseOptions::new(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?
+        .digest;
+
+    // Once the transaction is checkpointed, the tip has moved past where it was.
+    let checkpoint = tokio::time::timeout(Duration::from_secs(60), async {
+        loop {
+            let response = client
+                .read_api()
+                .get_transaction_with_options(digest, SuiTransactionResponseOptions::new())
+                .await
+                .unwrap();
+            if let Some(checkpoint) = response.checkpoint {
+                return checkpoint;
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+    })
+    .await?;
+    let after = client
+        .read_api()
+        .get_latest_checkpoint_sequence_number()
+        .await?;
+    assert!(checkpoint > before);
+    assert!(after >= checkpoint);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_pruning_watermark() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let watermark = client.read_api().get_pruning_watermark().await?;
+    let latest = client
+        .read_api()
+        .get_latest_checkpoint_sequence_number()
+        .await?;
+    assert!(watermark <= latest);
+    // The watermark itself can still be read.
+    let checkpoint = client
+        .read_api()
+        .get_checkpoint(CheckpointId::SequenceNumber(watermark))
+        .await?;
+    assert_eq!(watermark, checkpoint.sequence_number);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_pruning_watermark_errors() -> Result<(), anyhow::Error> {
+    // A mock fullnode that has pruned the checkpoints before 5, and fails to read `broken`.
+    let broken = Arc::new(Mutex::new(None));
+    let mut module = RpcModule::new(broken.clone());
+    module.register_method("rpc.discover", |_, _| {
+        Ok(json!({ "info": { "version": env!("CARGO_PKG_VERSION") }, "methods": [] }))
+    })?;
+    module.register_method("sui_getLatestCheckpointSequenceNumber", |_, _| Ok(9u64))?;
+    module.register_method("sui_getCheckpoint", |params, broken| {
+        let sequence_number = match params.one()? {
+            CheckpointId::SequenceNumber(sequence_number) => sequence_number,
+            CheckpointId::Digest(_) => unreachable!(),
+        };
+        if *broken.lock().unwrap() == Some(sequence_number) {
+            return Err(CallError::Failed(anyhow!("Database is unavailable")).into());
+        }
+        if sequence_number < 5 {
+            return Err(CallError::Failed(anyhow!(
+                "{CHECKPOINT_NOT_FOUND_MSG_PREFIX} for sequence number {sequence_number}"
+            ))
+            .into());
+        }
+        Ok(Checkpoint {
+            epoch: 0,
+            sequence_number,
+            digest: CheckpointDigest::random(),
+            network_total_transactions: 0,
+            previous_digest: None,
+            epoch_rolling_gas_cost_summary: Default::default(),
+            timestamp_ms: 0,
+            end_of_epoch_data: None,
+            transactions: vec![],
+            checkpoint_commitments: vec![],
+        })
+    })?;
+    let server = ServerBuilder::default().build("127.0.0.1:0").await?;
+    let rpc_url = format!("http://{}", server.local_addr()?);
+    let _handle = server.start(module)?;
+
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+    assert_eq!(5, client.read_api().get_pruning_watermark().await?);
+
+    // Failing to read a checkpoint doesn't mean it has been pruned.
+    *broken.lock().unwrap() = Some(4);
+    assert!(client.read_api().get_pruning_watermark().await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_normalized_move_modules_by_package() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../sui_programmability/examples/fungible_tokens");
+    let modules = BuildConfig::new_for_testing()
+        .build(path)?
+        .get_package_bytes(/* with_unpublished_deps */ false);
+    let package = publish_package_with_wallet(&test_cluster.wallet, address, modules).await;
+
+    let modules = client
+        .read_api()
+        .get_normalized_move_modules_by_package(package.0)
+        .await?;
+    assert!(modules.contains_key("managed"));
+
+    let mint = &modules["managed"].exposed_functions["mint"];
+    assert!(mint.is_entry);
+    assert!(matches!(mint.visibility, SuiMoveVisibility::Public));
+    assert!(mint.return_.is_empty());
+    assert_eq!(4, mint.parameters.len());
+    let SuiMoveNormalizedType::MutableReference(treasury_cap) = &mint.parameters[0] else {
+        panic!("Expected a mutable reference, got {:?}", mint.parameters[0]);
+    };
+    assert!(matches!(
+        treasury_cap.as_ref(),
+        SuiMoveNormalizedType::Struct { name, .. } if name == "TreasuryCap"
+    ));
+    assert!(matches!(mint.parameters[1], SuiMoveNormalizedType::U64));
+    assert!(matches!(mint.parameters[2], SuiMoveNormalizedType::Address));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_subscribe_balance_changes_by_coin_type() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = SuiAddress::random_for_testing_only();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let start = client
+        .read_api()
+        .get_latest_checkpoint_sequence_number()
+        .await?;
+
+    // The recipient first receives a custom coin, then SUI.
+    let (package, _) = publish_and_mint_managed(&test_cluster, recipient, 1000).await?;
+    let coin = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data[0]
+        .coin_object_id;
+    let data = client
+        .transaction_builder()
+        .transfer_sui(address, coin, 10000, recipient, Some(50000))
+        .await?;
+    let signature = test_cluster.sign_transaction(&address, &data);
+    let transfer = client
+        .quorum_driver()
+        .execute_transaction(
+            Transaction::from_data(data, Intent::default(), vec![signature]).verify()?,
+            SuiTransactionResponseOptions::new(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+
+    let changes = tokio::time::timeout(
+        Duration::from_secs(60),
+        client
+            .read_api()
+            .subscribe_balance_changes(recipient, None, Some(start))
+            .take(2)
+            .collect::<Vec<_>>(),
+    )
+    .await?
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        parse_sui_type_tag(&format!("{package}::managed::MANAGED"))?,
+        changes[0].1.coin_type
+    );
+    assert_eq!(1000, changes[0].1.amount);
+    assert_eq!(transfer.digest, changes[1].0);
+
+    // Filtered on SUI, the custom coin is skipped.
+    let (digest, change) = tokio::time::timeout(
+        Duration::from_secs(60),
+        Box::pin(client.read_api().subscribe_balance_changes(
+            recipient,
+            Some(GAS::type_tag()),
+            Some(start),
+        ))
+        .next(),
+    )
+    .await?
+    .unwrap()?;
+    assert_eq!(transfer.digest, digest);
+    assert_eq!(GAS::type_tag(), change.coin_type);
+    assert_eq!(50000, change.amount);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_estimate_created_objects() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let coin = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data[0]
+        .object_ref();
+    let recipients = (0..3)
+        .map(|_| SuiAddress::random_for_testing_only())
+        .collect::<Vec<_>>();
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder.pay(vec![coin], recipients, vec![100, 200, 300])?;
+    let kind = TransactionKind::programmable(builder.finish());
+
+    // One new coin is split off for each recipient.
+    let created = client
+        .read_api()
+        .estimate_created_objects(kind, address)
+        .await?;
+    assert_eq!(3, created);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_net_worth() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    // MANAGED has 2 decimals, so this mints 10 whole coins.
+    publish_and_mint_managed(&test_cluster, address, 1000).await?;
+
+    let sui = Decimal::from_i128_with_scale(
+        client
+            .coin_read_api()
+            .get_balance(address, None)
+            .await?
+            .total_balance as i128,
+        9,
+    );
+    let net_worth = client
+        .read_api()
+        .get_net_worth(address, |coin_type| {
+            (coin_type != GAS::type_tag()).then(|| Decimal::TWO)
+        })
+        .await?;
+    assert_eq!(sui + Decimal::from(20), net_worth);
+
+    // Coins without a price don't count.
+    let net_worth = client.read_api().get_net_worth(address, |_| None).await?;
+    assert_eq!(sui, net_worth);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stream_balance_changes() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = SuiAddress::random_for_testing_only();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let start = client
+        .read_api()
+        .get_latest_checkpoint_sequence_number()
+        .await?;
+    for amount in [1000, 2000, 3000] {
+        let coin = client
+            .coin_read_api()
+            .get_coins(address, None, None, None)
+            .await?
+            .data[0]
+            .coin_object_id;
+        let data = client
+            .transaction_builder()
+            .transfer_sui(address, coin, 10000, recipient, Some(amount))
+            .await?;
+        let signature = test_cluster.sign_transaction(&address, &data);
+        client
+            .quorum_driver()
+            .execute_transaction(
+                Transaction::from_data(data, Intent::default(), vec![signature]).verify()?,
+                SuiTransactionResponseOptions::new(),
+                Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+            )
+            .await?;
+    }
+
+    // The recipient's balance history, read in one batch.
+    let history = client
+        .read_api()
+        .query_transactions(
+            SuiTransactionResponseQuery::new(
+                Some(TransactionFilter::ToAddress(recipient)),
+                Some(SuiTransactionResponseOptions::new().with_balance_changes()),
+            ),
+            None,
+            None,
+            false,
+        )
+        .await?
+        .data
+        .into_iter()
+        .flat_map(|response| response.balance_changes.unwrap_or_default())
+        .filter(|change| change.address_owner() == Some(recipient))
+        .collect::<Vec<_>>();
+    assert_eq!(3, history.len());
+
+    let streamed = tokio::time::timeout(
+        Duration::from_secs(60),
+        client
+            .read_api()
+            .stream_balance_changes(recipient, Some(GAS::type_tag()), start)
+            .take(history.len())
+            .collect::<Vec<_>>(),
+    )
+    .await?
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()?;
+    assert!(streamed.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+    assert!(streamed.iter().all(|(seq, _)| *seq >= start));
+    let total = streamed
+        .iter()
+        .map(|(_, change)| change.amount)
+        .sum::<i128>();
+    assert_eq!(
+        history.iter().map(|change| change.amount).sum::<i128>(),
+        total
+    );
+    assert_eq!(6000, total);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_coin_type() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let (package, _) = publish_and_mint_managed(&test_cluster, address, 1000).await?;
+
+    let managed = parse_sui_type_tag(&format!("{package}::managed::MANAGED"))?;
+    let coins = client
+        .coin_read_api()
+        .get_all_coins(address, None, None)
+        .await?
+        .data;
+    let managed_coin = coins
+        .iter()
+        .find(|coin| coin.coin_type.ends_with("::managed::MANAGED"))
+        .unwrap();
+    let sui_coin = coins
+        .iter()
+        .find(|coin| coin.coin_type.ends_with("::sui::SUI"))
+        .unwrap();
+    assert_eq!(
+        managed,
+        client
+            .read_api()
+            .get_coin_type(managed_coin.coin_object_id)
+            .await?
+    );
+    assert_eq!(
+        GAS::type_tag(),
+        client
+            .read_api()
+            .get_coin_type(sui_coin.coin_object_id)
+            .await?
+    );
+
+    // The treasury cap is typed by the coin, but isn't a coin itself.
+    assert!(matches!(
+        client.read_api().get_coin_type(treasury_cap).await,
+        Err(Error::DataError(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_estimate_storage_rebate() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let rpc_url = test_cluster.rpc_url();
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+
+    let coins = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data;
+    let (coin, gas) = (coins[0].coin_object_id, coins[1].coin_object_id);
+    let execute = |data| {
+        let signature = test_cluster.sign_transaction(&address, &data);
+        client.quorum_driver().execute_transaction(
+            Transaction::from_data(data, Intent::default(), vec![signature])
+                .verify()
+                .unwrap(),
+            SuiTransactionResponseOptions::new().with_effects(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+    };
+
+    let data = client
+        .transaction_builder()
+        .split_coin(address, coin, vec![1000], Some(gas), 10000)
+        .await?;
+    let effects = execute(data).await?.effects.unwrap();
+    let created = effects.created()[0].reference.object_id;
+
+    let rebate = client
+        .read_api()
+        .estimate_storage_rebate(vec![created])
+        .await?;
+    assert!(rebate > 0);
+
+    // Deleting the object by merging it back rebates at least the estimate, the other objects
+    // mutated by the merge are rebated as well.
+    let data = client
+        .transaction_builder()
+        .merge_coins(address, coin, created, Some(gas), 10000)
+        .await?;
+    let effects = execute(data).await?.effects.unwrap();
+    assert!(effects.deleted().iter().any(|o| o.object_id == created));
+    assert!(effects.gas_used().storage_rebate >= rebate);
+
+    Ok(())
+}
+
+/// Publish the fungible_tokens example from the first address of `test_cluster` and mint
+/// `amount` of its MANAGED coin to `recipient`, returning the package and the treasury cap.
+async fn publish_and_mint_managed(
+    test_cluster: &TestCluster,
+    recipient: SuiAddress,
+    amount: u64,
+) -> Result<(ObjectID, ObjectID), anyhow::Error> {
+    let address = test_cluster.get_address_0();
+    let client = test_cluster.wallet.get_client().await?;
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../sui_programmability/examples/fungible_tokens");
+    let modules = BuildConfig::new_for_testing()
+        .build(path)?
+        .get_package_bytes(/* with_unpublished_deps */ false);
+    let package = publish_package_with_wallet(&test_cluster.wallet, address, modules)
+        .await
+        .0;
+    let treasury_cap = client
+        .read_api()
+        .get_owned_objects(
+            address,
+            Some(SuiObjectDataOptions::new().with_type()),
+            None,
+            None,
+            None,
+        )
+        .await?
+        .data
+        .into_iter()
+        .filter_map(|object| object.into_object().ok())
+        .find(|object| {
+            object
+                .type_
+                .as_ref()
+                .map_or(false, |t| t.to_string().contains("::coin::TreasuryCap<"))
+        })
+        .unwrap()
+        .object_id;
+    submit_move_transaction(
+        &test_cluster.wallet,
+        "managed",
+        "mint",
+        package,
+        vec![
+            SuiJsonValue::from_object_id(treasury_cap),
+            SuiJsonValue::new(json!(amount.to_string()))?,
+            SuiJsonValue::new(json!(recipient.to_string()))?,
+        ],
+        address,
+        None,
+    )
+    .await;
+    Ok((package, treasury_cap))
+}
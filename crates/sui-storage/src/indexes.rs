@@ -5,6 +5,7 @@
 //! The main user of this data is the explorer.
 
 use std::cmp::{max, min};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
 
@@ -12,10 +13,10 @@ use anyhow::anyhow;
 use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::{ModuleId, StructTag};
 use serde::{de::DeserializeOwned, Serialize};
-use tracing::debug;
+use tracing::{debug, info};
 
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest, TxSequenceNumber};
-use sui_types::base_types::{ObjectInfo, ObjectRef};
+use sui_types::base_types::{ObjectInfo, ObjectRef, ObjectType};
 use sui_types::digests::TransactionEventsDigest;
 use sui_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName};
 use sui_types::error::{SuiError, SuiResult};
@@ -31,6 +32,7 @@ use typed_store_derive::DBMapUtils;
 
 type OwnerIndexKey = (SuiAddress, ObjectID);
 type DynamicFieldKey = (ObjectID, ObjectID);
+type TypeIndexKey = (StructTag, ObjectID);
 type EventId = (TxSequenceNumber, usize);
 type EventIndex = (TransactionEventsDigest, TransactionDigest, u64);
 
@@ -38,6 +40,9 @@ pub const MAX_TX_RANGE_SIZE: u64 = 4096;
 
 pub const MAX_GET_OWNED_OBJECT_SIZE: usize = 256;
 
+// Number of owner index entries written to the type index per batch when backfilling it.
+const TYPE_INDEX_BACKFILL_BATCH_SIZE: usize = 10_000;
+
 pub struct ObjectIndexChanges {
     pub deleted_owners: Vec<OwnerIndexKey>,
     pub deleted_dynamic_fields: Vec<DynamicFieldKey>,
@@ -97,6 +102,12 @@ pub struct IndexStoreTables {
     #[default_options_override_fn = "dynamic_field_index_table_default_config"]
     dynamic_field_index: DBMap<DynamicFieldKey, DynamicFieldInfo>,
 
+    /// This is an index of currently existing address owned objects, indexed by the composite
+    /// key of their struct type and object ID. It allows listing all objects of a given type
+    /// regardless of their owner.
+    #[default_options_override_fn = "type_index_table_default_config"]
+    type_index: DBMap<TypeIndexKey, ObjectInfo>,
+
     #[default_options_override_fn = "index_table_default_config"]
     event_order: DBMap<EventId, EventIndex>,
     #[default_options_override_fn = "index_table_default_config"]
@@ -145,6 +156,9 @@ fn owner_index_table_default_config() -> DBOptions {
 fn dynamic_field_index_table_default_config() -> DBOptions {
     default_db_options()
 }
+fn type_index_table_default_config() -> DBOptions {
+    default_db_options()
+}
 fn index_table_default_config() -> DBOptions {
     default_db_options()
 }
@@ -162,10 +176,36 @@ impl IndexStore {
             .unwrap_or(0)
             .into();
 
-        Self {
+        let store = Self {
             tables,
             next_sequence_number,
+        };
+        store
+            .backfill_type_index()
+            .expect("Failed to backfill the type index");
+        store
+    }
+
+    // Nodes that indexed objects before the type index was introduced have an empty type index,
+    // while the owner index holds the same objects. Populate the former from the latter, so that
+    // objects created before the upgrade are also listed by type.
+    fn backfill_type_index(&self) -> SuiResult {
+        if !self.tables.type_index.is_empty() || self.tables.owner_index.is_empty() {
+            return Ok(());
+        }
+        info!("Backfilling the type index from the owner index");
+        let mut entries = self.tables.owner_index.iter().peekable();
+        while entries.peek().is_some() {
+            let chunk = entries
+                .by_ref()
+                .take(TYPE_INDEX_BACKFILL_BATCH_SIZE)
+                .collect::<Vec<_>>();
+            let batch = self.tables.type_index.batch();
+            let batch =
+                batch.insert_batch(&self.tables.type_index, Self::type_index_entries(&chunk))?;
+            batch.write()?;
         }
+        Ok(())
     }
 
     pub fn index_tx(
@@ -235,6 +275,14 @@ impl IndexStore {
             std::iter::once((*digest, timestamp_ms)),
         )?;
 
+        // Type index, the types of deleted entries are looked up before the owner index is updated
+        let deleted_types = self.deleted_type_index_keys(&object_index_changes.deleted_owners)?;
+        let batch = batch.delete_batch(&self.tables.type_index, deleted_types.into_iter())?;
+        let batch = batch.insert_batch(
+            &self.tables.type_index,
+            Self::type_index_entries(&object_index_changes.new_owners),
+        )?;
+
         // Owner index
         let batch = batch.delete_batch(
             &self.tables.owner_index,
@@ -351,6 +399,9 @@ impl IndexStore {
             Some(TransactionFilter::ToAddress(address)) => {
                 self.get_transactions_to_addr(address, cursor, limit, reverse)?
             }
+            Some(TransactionFilter::FromOrToAddress(address)) => {
+                self.get_transactions_from_or_to_addr(address, cursor, limit, reverse)?
+            }
             None => {
                 let iter = self.tables.transaction_order.iter();
 
@@ -460,15 +511,33 @@ impl IndexStore {
         limit: Option<usize>,
         reverse: bool,
     ) -> SuiResult<Vec<TransactionDigest>> {
+        Ok(
+            Self::get_transactions_with_seq_from_index(index, key, cursor, limit, reverse)?
+                .into_iter()
+                .map(|(_, digest)| digest)
+                .collect(),
+        )
+    }
+
+    fn get_transactions_with_seq_from_index<
+        KeyT: Clone + Serialize + DeserializeOwned + PartialEq,
+    >(
+        index: &DBMap<(KeyT, TxSequenceNumber), TransactionDigest>,
+        key: KeyT,
+        cursor: Option<TxSequenceNumber>,
+        limit: Option<usize>,
+        reverse: bool,
+    ) -> SuiResult<Vec<(TxSequenceNumber, TransactionDigest)>> {
         Ok(if reverse {
             let iter = index
                 .iter()
                 .skip_prior_to(&(key.clone(), cursor.unwrap_or(TxSequenceNumber::MAX)))?
                 .reverse()
-                // skip one more if exclusive cursor is Some
-                .skip(usize::from(cursor.is_some()))
+                // The cursor is exclusive, skip it if it is in this index. An index merged with
+                // others may not have the cursor, in which case the first entry is kept.
+                .skip_while(|((_, seq), _)| Some(*seq) == cursor)
                 .take_while(|((id, _), _)| *id == key)
-                .map(|(_, digest)| digest);
+                .map(|((_, seq), digest)| (seq, digest));
             if let Some(limit) = limit {
                 iter.take(limit).collect()
             } else {
@@ -478,10 +547,10 @@ impl IndexStore {
             let iter = index
                 .iter()
                 .skip_to(&(key.clone(), cursor.unwrap_or(TxSequenceNumber::MIN)))?
-                // skip one more if exclusive cursor is Some
-                .skip(usize::from(cursor.is_some()))
+                // The cursor is exclusive, skip it if it is in this index.
+                .skip_while(|((_, seq), _)| Some(*seq) == cursor)
                 .take_while(|((id, _), _)| *id == key)
-                .map(|(_, digest)| digest);
+                .map(|((_, seq), digest)| (seq, digest));
             if let Some(limit) = limit {
                 iter.take(limit).collect()
             } else {
@@ -612,6 +681,40 @@ impl IndexStore {
         )
     }
 
+    /// Transactions sent by or to `addr`, in sequence order. Transactions an address sends
+    /// to itself are only returned once.
+    pub fn get_transactions_from_or_to_addr(
+        &self,
+        addr: SuiAddress,
+        cursor: Option<TxSequenceNumber>,
+        limit: Option<usize>,
+        reverse: bool,
+    ) -> SuiResult<Vec<TransactionDigest>> {
+        // Each index is read up to `limit`, which is enough to fill a page of the merged result.
+        let from = Self::get_transactions_with_seq_from_index(
+            &self.tables.transactions_from_addr,
+            addr,
+            cursor,
+            limit,
+            reverse,
+        )?;
+        let to = Self::get_transactions_with_seq_from_index(
+            &self.tables.transactions_to_addr,
+            addr,
+            cursor,
+            limit,
+            reverse,
+        )?;
+        let merged = from.into_iter().chain(to).collect::<BTreeMap<_, _>>();
+        let iter = merged.into_values();
+        Ok(match (reverse, limit) {
+            (true, Some(limit)) => iter.rev().take(limit).collect(),
+            (true, None) => iter.rev().collect(),
+            (false, Some(limit)) => iter.take(limit).collect(),
+            (false, None) => iter.collect(),
+        })
+    }
+
     pub fn get_transaction_seq(
         &self,
         digest: &TransactionDigest,
@@ -884,8 +987,59 @@ impl IndexStore {
             .map(|(_, object_info)| object_info))
     }
 
+    /// Iterate over address owned objects of type `type_`, starting after `cursor`.
+    pub fn get_objects_by_type_iterator(
+        &self,
+        type_: StructTag,
+        cursor: Option<ObjectID>,
+    ) -> SuiResult<impl Iterator<Item = ObjectInfo> + '_> {
+        debug!(?type_, ?cursor, "get_objects_by_type");
+        Ok(self
+            .tables
+            .type_index
+            .iter()
+            // The object id 0 is the smallest possible
+            .skip_to(&(type_.clone(), cursor.unwrap_or(ObjectID::ZERO)))?
+            // skip an extra b/c the cursor is exclusive
+            .skip(usize::from(cursor.is_some()))
+            .take_while(move |((object_type, _), _)| (object_type == &type_))
+            .map(|(_, object_info)| object_info))
+    }
+
+    fn deleted_type_index_keys(
+        &self,
+        deleted_owners: &[OwnerIndexKey],
+    ) -> SuiResult<Vec<TypeIndexKey>> {
+        let mut keys = vec![];
+        for key in deleted_owners {
+            if let Some(ObjectInfo {
+                type_: ObjectType::Struct(type_),
+                ..
+            }) = self.tables.owner_index.get(key)?
+            {
+                keys.push((type_.into(), key.1));
+            }
+        }
+        Ok(keys)
+    }
+
+    fn type_index_entries(
+        new_owners: &[(OwnerIndexKey, ObjectInfo)],
+    ) -> impl Iterator<Item = (TypeIndexKey, ObjectInfo)> + '_ {
+        new_owners.iter().filter_map(|(_, info)| match &info.type_ {
+            ObjectType::Struct(type_) => {
+                Some(((type_.clone().into(), info.object_id), info.clone()))
+            }
+            ObjectType::Package => None,
+        })
+    }
+
     pub fn insert_genesis_objects(&self, object_index_changes: ObjectIndexChanges) -> SuiResult {
         let batch = self.tables.owner_index.batch();
+        let batch = batch.insert_batch(
+            &self.tables.type_index,
+            Self::type_index_entries(&object_index_changes.new_owners),
+        )?;
         let batch = batch.insert_batch(
             &self.tables.owner_index,
             object_index_changes.new_owners.into_iter(),
@@ -902,3 +1056,38 @@ impl IndexStore {
         self.tables.owner_index.is_empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::gas_coin::GasCoin;
+    use sui_types::object::Object;
+
+    #[test]
+    fn test_type_index_backfill() {
+        let dir = tempfile::tempdir().unwrap();
+        let owner = SuiAddress::random_for_testing_only();
+        let object = Object::new_gas_with_balance_and_owner_for_testing(100, owner);
+        let info = ObjectInfo::new(&object.compute_object_reference(), &object);
+
+        let store = IndexStore::new(dir.path().to_path_buf());
+        store
+            .insert_genesis_objects(ObjectIndexChanges {
+                deleted_owners: vec![],
+                deleted_dynamic_fields: vec![],
+                new_owners: vec![((owner, object.id()), info.clone())],
+                new_dynamic_fields: vec![],
+            })
+            .unwrap();
+        // Objects indexed before the type index was introduced are only in the owner index.
+        store.tables.type_index.clear().unwrap();
+        drop(store);
+
+        let store = IndexStore::new(dir.path().to_path_buf());
+        let objects = store
+            .get_objects_by_type_iterator(GasCoin::type_(), None)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(vec![info], objects);
+    }
+}
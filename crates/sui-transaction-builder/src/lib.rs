@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::marker::PhantomData;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -12,7 +12,7 @@ use futures::future::join_all;
 use anyhow::{anyhow, ensure, Ok};
 use move_binary_format::file_format::SignatureToken;
 use move_core_types::identifier::Identifier;
-use move_core_types::language_storage::TypeTag;
+use move_core_types::language_storage::{StructTag, TypeTag};
 use std::result::Result;
 use sui_adapter::adapter::{resolve_and_type_check, CheckCallArg};
 use sui_adapter::execution_mode::ExecutionMode;
@@ -23,18 +23,24 @@ use sui_json_rpc_types::{
 };
 use sui_protocol_config::ProtocolConfig;
 use sui_types::base_types::{ObjectID, ObjectRef, ObjectType, SuiAddress};
+use sui_types::committee::EpochId;
 use sui_types::error::UserInputError;
 use sui_types::gas_coin::GasCoin;
-use sui_types::governance::{ADD_STAKE_MUL_COIN_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
+use sui_types::governance::{
+    ADD_STAKE_MUL_COIN_FUN_NAME, MIN_STAKE_AMOUNT_MIST, WITHDRAW_STAKE_FUN_NAME,
+};
 use sui_types::messages::{
-    Argument, CallArg, Command, InputObjectKind, ObjectArg, TransactionData, TransactionKind,
+    Argument, CallArg, Command, InputObjectKind, ObjectArg, TransactionData, TransactionExpiration,
+    TransactionKind,
 };
 use sui_types::move_package::MovePackage;
 use sui_types::object::{Object, Owner};
-use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::programmable_transaction_builder::{
+    check_merge_arguments, ProgrammableTransactionBuilder,
+};
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
 use sui_types::{
-    coin, fp_ensure, SUI_FRAMEWORK_OBJECT_ID, SUI_SYSTEM_STATE_OBJECT_ID,
+    coin, fp_ensure, parse_sui_type_tag, SUI_FRAMEWORK_OBJECT_ID, SUI_SYSTEM_STATE_OBJECT_ID,
     SUI_SYSTEM_STATE_OBJECT_SHARED_VERSION,
 };
 
@@ -69,7 +75,36 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         Self(data_reader, PhantomData)
     }
 
-    async fn select_gas(
+    /// Set `data` to expire after `epoch`: validators will not sign it once the network has
+    /// moved past that epoch.
+    pub fn with_expiration(&self, data: TransactionData, epoch: EpochId) -> TransactionData {
+        data.with_expiration(TransactionExpiration::Epoch(epoch))
+    }
+
+    /// Set the gas price of `data` to `gas_price`, e.g. to pay above the reference gas price for
+    /// priority. Fails with `UserInputError::GasPriceUnderRGP` if it is under the reference gas
+    /// price. The gas payment of `data` still has to cover the budget at the new price.
+    pub async fn with_gas_price(
+        &self,
+        data: TransactionData,
+        gas_price: u64,
+    ) -> anyhow::Result<TransactionData> {
+        let reference_gas_price = self.0.get_reference_gas_price().await?;
+        fp_ensure!(
+            gas_price >= reference_gas_price,
+            UserInputError::GasPriceUnderRGP {
+                gas_price,
+                reference_gas_price,
+            }
+            .into()
+        );
+        Ok(data.with_gas_price(gas_price))
+    }
+
+    /// Select the gas coin paying `budget` at `gas_price` for a transaction signed by `signer`.
+    /// `input_gas` is used when provided, otherwise the first gas coin of `signer` that can
+    /// cover the budget is selected. Either way the gas coin can't be one of `input_objects`.
+    pub async fn select_gas(
         &self,
         signer: SuiAddress,
         input_gas: Option<ObjectID>,
@@ -77,8 +112,22 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         input_objects: Vec<ObjectID>,
         gas_price: u64,
     ) -> Result<ObjectRef, anyhow::Error> {
+        let required_gas_amount = (budget as u128) * (gas_price as u128);
         if let Some(gas) = input_gas {
-            self.get_object_ref(gas).await
+            ensure!(
+                !input_objects.contains(&gas),
+                "Gas coin [{gas}] is also an input of the transaction."
+            );
+            let (gas_ref, gas_balance) = self.get_gas_coin(gas).await?;
+            fp_ensure!(
+                gas_balance as u128 >= required_gas_amount,
+                UserInputError::GasBalanceTooLow {
+                    gas_balance: gas_balance as u128,
+                    needed_gas_amount: required_gas_amount,
+                }
+                .into()
+            );
+            Ok(gas_ref)
         } else {
             let objs = self
                 .0
@@ -97,35 +146,39 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
                     _ => false,
                 }
             });
-            let required_gas_amount = (budget as u128) * (gas_price as u128);
 
             for obj in gas_objs {
-                let response = self
-                    .0
-                    .get_object_with_options(
-                        obj.clone().into_object()?.object_id,
-                        SuiObjectDataOptions::new().with_bcs(),
-                    )
+                let (gas_ref, gas_balance) = self
+                    .get_gas_coin(obj.clone().into_object()?.object_id)
                     .await?;
-                let obj = response.object()?;
-                let gas: GasCoin = bcs::from_bytes(
-                    &obj.bcs
-                        .as_ref()
-                        .ok_or_else(|| anyhow!("bcs field is unexpectedly empty"))?
-                        .try_as_move()
-                        .ok_or_else(|| anyhow!("Cannot parse move object to gas object"))?
-                        .bcs_bytes,
-                )?;
-                if !input_objects.contains(&obj.object_id)
-                    && (gas.value() as u128) >= required_gas_amount
+                if !input_objects.contains(&gas_ref.0)
+                    && (gas_balance as u128) >= required_gas_amount
                 {
-                    return Ok(obj.object_ref());
+                    return Ok(gas_ref);
                 }
             }
             Err(anyhow!("Cannot find gas coin for signer address [{signer}] with amount sufficient for the required gas amount [{required_gas_amount}]."))
         }
     }
 
+    /// Resolve the reference and the balance of the gas coin `gas`.
+    async fn get_gas_coin(&self, gas: ObjectID) -> anyhow::Result<(ObjectRef, u64)> {
+        let response = self
+            .0
+            .get_object_with_options(gas, SuiObjectDataOptions::new().with_bcs())
+            .await?;
+        let obj = response.object()?;
+        let gas_coin: GasCoin = bcs::from_bytes(
+            &obj.bcs
+                .as_ref()
+                .ok_or_else(|| anyhow!("bcs field is unexpectedly empty"))?
+                .try_as_move()
+                .ok_or_else(|| anyhow!("Cannot parse move object to gas object"))?
+                .bcs_bytes,
+        )?;
+        Ok((obj.object_ref(), gas_coin.value()))
+    }
+
     pub async fn transfer_object(
         &self,
         signer: SuiAddress,
@@ -151,6 +204,49 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         ))
     }
 
+    /// Same as `transfer_object`, but checks that `object_ref` is owned by `signer` before
+    /// building the transaction, returning `UserInputError::NotOwned` otherwise.
+    pub async fn transfer_object_checked(
+        &self,
+        signer: SuiAddress,
+        object_ref: ObjectRef,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+        recipient: SuiAddress,
+    ) -> anyhow::Result<TransactionData> {
+        let object_id = object_ref.0;
+        let owner = self
+            .0
+            .get_object_with_options(object_id, SuiObjectDataOptions::new().with_owner())
+            .await?
+            .into_object()?
+            .owner;
+        fp_ensure!(
+            owner == Some(Owner::AddressOwner(signer)),
+            UserInputError::NotOwned {
+                object_id,
+                sender: signer,
+                owner,
+            }
+            .into()
+        );
+
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.transfer_object(recipient, object_ref)?;
+        let gas_price = self.0.get_reference_gas_price().await?;
+        let gas = self
+            .select_gas(signer, gas, gas_budget, vec![object_id], gas_price)
+            .await?;
+
+        Ok(TransactionData::new(
+            TransactionKind::programmable(builder.finish()),
+            signer,
+            gas,
+            gas_budget,
+            gas_price,
+        ))
+    }
+
     async fn single_transfer_object(
         &self,
         builder: &mut ProgrammableTransactionBuilder,
@@ -191,14 +287,9 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             }
         }
 
-        let handles: Vec<_> = input_coins
-            .iter()
-            .map(|id| self.get_object_ref(*id))
-            .collect();
-        let coin_refs = join_all(handles)
-            .await
-            .into_iter()
-            .collect::<anyhow::Result<Vec<ObjectRef>>>()?;
+        let coin_refs = self.get_coin_refs(&input_coins, None).await?;
+        let needed = amounts.iter().map(|amount| *amount as u128).sum();
+        self.ensure_sufficient_funds(&input_coins, needed).await?;
         let gas_price = self.0.get_reference_gas_price().await?;
         let gas = self
             .select_gas(signer, gas, gas_budget, input_coins, gas_price)
@@ -222,17 +313,16 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             UserInputError::EmptyInputCoins.into()
         );
 
-        let handles: Vec<_> = input_coins
-            .into_iter()
-            .map(|id| self.get_object_ref(id))
-            .collect();
-        let mut coin_refs = join_all(handles)
-            .await
-            .into_iter()
-            .collect::<anyhow::Result<Vec<ObjectRef>>>()?;
+        let mut coin_refs = self
+            .get_coin_refs(&input_coins, Some(GasCoin::type_()))
+            .await?;
         // [0] is safe because input_coins is non-empty and coins are of same length as input_coins.
         let gas_object_ref = coin_refs.remove(0);
         let gas_price = self.0.get_reference_gas_price().await?;
+        // The gas is paid out of the same coins as the amounts.
+        let needed = amounts.iter().map(|amount| *amount as u128).sum::<u128>()
+            + gas_budget as u128 * gas_price as u128;
+        self.ensure_sufficient_funds(&input_coins, needed).await?;
         TransactionData::new_pay_sui(
             signer,
             coin_refs,
@@ -256,15 +346,9 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             UserInputError::EmptyInputCoins.into()
         );
 
-        let handles: Vec<_> = input_coins
-            .into_iter()
-            .map(|id| self.get_object_ref(id))
-            .collect();
-
-        let mut coin_refs = join_all(handles)
-            .await
-            .into_iter()
-            .collect::<anyhow::Result<Vec<ObjectRef>>>()?;
+        let mut coin_refs = self
+            .get_coin_refs(&input_coins, Some(GasCoin::type_()))
+            .await?;
         // [0] is safe because input_coins is non-empty and coins are of same length as input_coins.
         let gas_object_ref = coin_refs.remove(0);
         let gas_price = self.0.get_reference_gas_price().await?;
@@ -278,6 +362,81 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         ))
     }
 
+    /// Transfer the last SUI coin of `signer` to `recipient`. The coin pays for its own gas, so
+    /// `recipient` receives what is left of it once gas is charged. Fails if `signer` owns more
+    /// than one SUI coin, use `pay_all_sui` instead, or with `UserInputError::GasBalanceTooLow`
+    /// if the coin can't cover `gas_budget`.
+    pub async fn transfer_last_coin(
+        &self,
+        signer: SuiAddress,
+        recipient: SuiAddress,
+        gas_budget: u64,
+    ) -> anyhow::Result<TransactionData> {
+        let mut coins = vec![];
+        let mut cursor = None;
+        loop {
+            let page = self
+                .0
+                .get_owned_objects(
+                    signer,
+                    Some(SuiObjectDataOptions::new().with_type()),
+                    cursor,
+                    None,
+                    None,
+                )
+                .await?;
+            coins.extend(
+                page.data
+                    .into_iter()
+                    .filter_map(|object| object.into_object().ok())
+                    .filter(|object| object.type_.as_ref().map_or(false, ObjectType::is_gas_coin)),
+            );
+            if !page.has_next_page {
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+        let coin = match coins.as_slice() {
+            [coin] => coin.object_id,
+            [] => return Err(anyhow!("Address [{signer}] owns no SUI coin")),
+            coins => {
+                return Err(anyhow!(
+                    "Address [{signer}] owns {} SUI coins, use pay_all_sui to transfer them all",
+                    coins.len()
+                ))
+            }
+        };
+
+        let object: Object = self
+            .0
+            .get_object_with_options(coin, SuiObjectDataOptions::bcs_lossless())
+            .await?
+            .into_object()?
+            .try_into()?;
+        let gas_balance = coin::Coin::extract_balance_if_coin(&object)?
+            .ok_or_else(|| anyhow!("Object [{coin}] is not a coin"))?
+            as u128;
+        let gas_price = self.0.get_reference_gas_price().await?;
+        let needed_gas_amount = gas_budget as u128 * gas_price as u128;
+        fp_ensure!(
+            gas_balance >= needed_gas_amount,
+            UserInputError::GasBalanceTooLow {
+                gas_balance,
+                needed_gas_amount,
+            }
+            .into()
+        );
+
+        Ok(TransactionData::new_pay_all_sui(
+            signer,
+            vec![],
+            recipient,
+            object.compute_object_reference(),
+            gas_budget,
+            gas_price,
+        ))
+    }
+
     pub async fn move_call(
         &self,
         signer: SuiAddress,
@@ -562,6 +721,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         gas: Option<ObjectID>,
         gas_budget: u64,
     ) -> anyhow::Result<TransactionData> {
+        check_merge_arguments(primary_coin, &[coin_to_merge])?;
         let coin = self
             .0
             .get_object_with_options(primary_coin, SuiObjectDataOptions::bcs_lossless())
@@ -598,6 +758,126 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         )
     }
 
+    /// Build a transaction that merges and splits the coins of `coin_type` owned by `owner` so
+    /// that a coin of exactly `value` exists once it is executed. Returns the transaction and
+    /// the id of the new coin, which is known before execution since it is derived from the
+    /// transaction digest.
+    pub async fn prepare_exact_coin(
+        &self,
+        owner: SuiAddress,
+        coin_type: String,
+        value: u64,
+        gas_budget: u64,
+    ) -> anyhow::Result<(TransactionData, ObjectID)> {
+        let coin_type = coin::Coin::type_(parse_sui_type_tag(&coin_type)?);
+        let mut coins = vec![];
+        let mut cursor = None;
+        loop {
+            let page = self
+                .0
+                .get_owned_objects(
+                    owner,
+                    Some(SuiObjectDataOptions::new().with_type().with_bcs()),
+                    cursor,
+                    None,
+                    None,
+                )
+                .await?;
+            for obj in page.data {
+                let obj = obj.into_object()?;
+                let ObjectType::Struct(type_) = obj.object_type()? else {
+                    continue;
+                };
+                if StructTag::from(type_) != coin_type {
+                    continue;
+                }
+                let coin = coin::Coin::from_bcs_bytes(
+                    &obj.bcs
+                        .as_ref()
+                        .ok_or_else(|| anyhow!("bcs field is unexpectedly empty"))?
+                        .try_as_move()
+                        .ok_or_else(|| anyhow!("Cannot parse move object to coin object"))?
+                        .bcs_bytes,
+                )?;
+                coins.push((obj.object_ref(), coin.value()));
+            }
+            if !page.has_next_page {
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+
+        // Use the largest coins first, to keep the number of merged coins low.
+        coins.sort_by(|(_, a), (_, b)| b.cmp(a));
+        let mut total = 0u128;
+        let coins = coins
+            .into_iter()
+            .take_while(|(_, balance)| {
+                let needed = total < value as u128;
+                total += *balance as u128;
+                needed
+            })
+            .map(|(oref, _)| oref)
+            .collect::<Vec<_>>();
+        ensure!(
+            total >= value as u128,
+            "Insufficient balance of [{coin_type}] owned by [{owner}] to prepare a coin of {value}, found {total}."
+        );
+
+        let gas_price = self.0.get_reference_gas_price().await?;
+        let gas = self
+            .select_gas(
+                owner,
+                None,
+                gas_budget,
+                coins.iter().map(|(id, _, _)| *id).collect(),
+                gas_price,
+            )
+            .await?;
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.pay(coins, vec![owner], vec![value])?;
+        let data = TransactionData::new(
+            TransactionKind::programmable(builder.finish()),
+            owner,
+            gas,
+            gas_budget,
+            gas_price,
+        );
+        // The split coin is the only object created by the transaction.
+        let coin_id = ObjectID::derive_id(data.digest(), 0);
+        Ok((data, coin_id))
+    }
+
+    /// Build a transaction that splits `count` SUI coins of `each_value` off `source_coin`, to
+    /// be used as gas by later transactions. `source_coin` also pays for the gas, so it must
+    /// cover the new coins and the gas budget.
+    pub async fn prepare_gas_pool(
+        &self,
+        owner: SuiAddress,
+        source_coin: ObjectID,
+        count: u64,
+        each_value: u64,
+        gas_budget: u64,
+    ) -> anyhow::Result<TransactionData> {
+        ensure!(count > 0, "A gas pool needs at least one coin.");
+        let source = self
+            .get_coin_refs(&[source_coin], Some(GasCoin::type_()))
+            .await?
+            .remove(0);
+        let gas_price = self.0.get_reference_gas_price().await?;
+        let needed = count as u128 * each_value as u128 + gas_budget as u128 * gas_price as u128;
+        self.ensure_sufficient_funds(&[source_coin], needed).await?;
+        TransactionData::new_pay_sui(
+            owner,
+            vec![],
+            vec![owner; count as usize],
+            vec![each_value; count as usize],
+            source,
+            gas_budget,
+            gas_price,
+        )
+    }
+
     pub async fn batch_transaction(
         &self,
         signer: SuiAddress,
@@ -661,12 +941,25 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         mut coins: Vec<ObjectID>,
         amount: Option<u64>,
         validator: SuiAddress,
+        gas_owner: Option<SuiAddress>,
         gas: Option<ObjectID>,
         gas_budget: u64,
     ) -> anyhow::Result<TransactionData> {
+        if let Some(amount) = amount {
+            fp_ensure!(
+                amount >= MIN_STAKE_AMOUNT_MIST,
+                UserInputError::StakeBelowMinimum {
+                    amount,
+                    min: MIN_STAKE_AMOUNT_MIST,
+                }
+                .into()
+            );
+        }
+        // Gas is paid by the signer unless a sponsor is provided.
+        let gas_owner = gas_owner.unwrap_or(signer);
         let gas_price = self.0.get_reference_gas_price().await?;
         let gas = self
-            .select_gas(signer, gas, gas_budget, coins.clone(), gas_price)
+            .select_gas(gas_owner, gas, gas_budget, coins.clone(), gas_price)
             .await?;
 
         let mut obj_vec = vec![];
@@ -720,12 +1013,13 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             ));
             builder.finish()
         };
-        Ok(TransactionData::new_programmable(
+        Ok(TransactionData::new_programmable_allow_sponsor(
             signer,
             vec![gas],
             pt,
             gas_budget,
             gas_price,
+            gas_owner,
         ))
     }
 
@@ -768,6 +1062,67 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             .map(|(oref, _)| oref)
     }
 
+    /// Resolve the references of `coins`, checking that they are all of the same type, and of
+    /// `coin_type` when it is specified.
+    async fn get_coin_refs(
+        &self,
+        coins: &[ObjectID],
+        coin_type: Option<StructTag>,
+    ) -> anyhow::Result<Vec<ObjectRef>> {
+        let handles: Vec<_> = coins
+            .iter()
+            .map(|id| self.get_object_ref_and_type(*id))
+            .collect();
+        let coins = join_all(handles)
+            .await
+            .into_iter()
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let mut coin_types = coins
+            .iter()
+            .map(|(_, type_)| type_.to_string())
+            .collect::<BTreeSet<_>>();
+        if let Some(coin_type) = coin_type {
+            coin_types.insert(coin_type.to_string());
+        }
+        fp_ensure!(
+            coin_types.len() <= 1,
+            UserInputError::MixedCoinTypes {
+                coin_types: coin_types.into_iter().collect(),
+            }
+            .into()
+        );
+        Ok(coins.into_iter().map(|(oref, _)| oref).collect())
+    }
+
+    /// Check that `coins` hold at least `needed` in total, returning
+    /// `UserInputError::InsufficientFunds` otherwise.
+    async fn ensure_sufficient_funds(
+        &self,
+        coins: &[ObjectID],
+        needed: u128,
+    ) -> anyhow::Result<()> {
+        let handles: Vec<_> = coins
+            .iter()
+            .map(|id| {
+                self.0
+                    .get_object_with_options(*id, SuiObjectDataOptions::bcs_lossless())
+            })
+            .collect();
+        let mut available = 0u128;
+        for response in join_all(handles).await {
+            let coin: Object = response?.into_object()?.try_into()?;
+            available += coin::Coin::extract_balance_if_coin(&coin)?
+                .ok_or_else(|| anyhow!("Object [{}] is not a coin", coin.id()))?
+                as u128;
+        }
+        fp_ensure!(
+            available >= needed,
+            UserInputError::InsufficientFunds { needed, available }.into()
+        );
+        Ok(())
+    }
+
     async fn get_object_ref_and_type(
         &self,
         object_id: ObjectID,
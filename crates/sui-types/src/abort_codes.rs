@@ -0,0 +1,63 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Human readable reasons for the abort codes of the Move stdlib and Sui framework modules, so
+//! that a failed transaction can be explained to users instead of showing a raw abort code.
+
+use move_core_types::language_storage::ModuleId;
+
+use crate::{MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS};
+
+#[cfg(test)]
+#[path = "unit_tests/abort_codes_tests.rs"]
+mod abort_codes_tests;
+
+/// Return the reason for abort `code` raised in `module`, if it is a known abort of a Move
+/// stdlib or Sui framework module.
+pub fn explain_abort(module: &ModuleId, code: u64) -> Option<String> {
+    let name = module.name().as_str();
+    let reason = if *module.address() == MOVE_STDLIB_ADDRESS {
+        stdlib_abort(name, code)
+    } else if *module.address() == SUI_FRAMEWORK_ADDRESS {
+        framework_abort(name, code)
+    } else {
+        None
+    };
+    reason.map(|reason| reason.to_string())
+}
+
+fn stdlib_abort(module: &str, code: u64) -> Option<&'static str> {
+    Some(match (module, code) {
+        ("ascii", 0x10000) => "Invalid ASCII character",
+        ("fixed_point32", 0x10001) => "The denominator provided was zero",
+        ("fixed_point32", 0x20002) => "The quotient value is too large to be held in a u64",
+        ("fixed_point32", 0x20003) => "The multiplied value is too large to be held in a u64",
+        ("fixed_point32", 0x10004) => "Division by zero",
+        ("fixed_point32", 0x20005) => "The ratio is not representable as a FixedPoint32",
+        ("option", 0x40000) => "The option is set while it should be empty",
+        ("option", 0x40001) => "The option is empty while it should be set",
+        ("string", 1) => "Invalid UTF8 encoding",
+        ("string", 2) => "String index out of range",
+        ("vector", 0x20000) => "Vector index out of bounds",
+        _ => return None,
+    })
+}
+
+fn framework_abort(module: &str, code: u64) -> Option<&'static str> {
+    Some(match (module, code) {
+        ("balance", 0) => "Cannot destroy a non-zero balance",
+        ("balance", 1) => "Supply overflow",
+        ("balance", 2) => "Insufficient balance",
+        ("coin", 0) => "The type passed to create a currency is not a one-time witness",
+        ("coin", 1) => "Invalid argument",
+        ("coin", 2) => "Insufficient coin balance for the split",
+        ("dynamic_field", 0) => "The dynamic field already exists",
+        ("dynamic_field", 1) => "The dynamic field does not exist",
+        ("dynamic_field", 2) => "The dynamic field value has a different type",
+        ("dynamic_field", 3) => "Failed to serialize the dynamic field name",
+        ("pay", 0) => "No coins were provided",
+        ("transfer", 0) => "Only objects created in the same transaction can be shared",
+        ("tx_context", 0) => "Invalid transaction hash length",
+        _ => return None,
+    })
+}
@@ -418,6 +418,21 @@ impl SuiAddress {
     pub fn to_inner(self) -> [u8; SUI_ADDRESS_LENGTH] {
         self.0
     }
+
+    /// Parse an address from hex with or without the `0x` prefix, in any case. Short forms are
+    /// zero-padded to the full width, e.g. `0x2` is `0x00..02`.
+    pub fn normalize(s: &str) -> anyhow::Result<SuiAddress> {
+        let hex = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .unwrap_or(s);
+        if hex.is_empty() || hex.len() > SUI_ADDRESS_LENGTH * 2 {
+            return Err(anyhow!("Invalid address length: {s}"));
+        }
+        let bytes = Hex::decode(&format!("{hex:0>width$}", width = SUI_ADDRESS_LENGTH * 2))
+            .map_err(|e| anyhow!("Invalid address {s}: {e}"))?;
+        Ok(Self::try_from(bytes)?)
+    }
 }
 
 impl From<ObjectID> for SuiAddress {
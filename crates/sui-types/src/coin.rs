@@ -34,6 +34,8 @@ pub const COIN_MODULE_NAME: &IdentStr = ident_str!("coin");
 pub const COIN_STRUCT_NAME: &IdentStr = ident_str!("Coin");
 pub const COIN_METADATA_STRUCT_NAME: &IdentStr = ident_str!("CoinMetadata");
 pub const COIN_TREASURE_CAP_NAME: &IdentStr = ident_str!("TreasuryCap");
+pub const COIN_MINT_AND_TRANSFER_FUNC_NAME: &IdentStr = ident_str!("mint_and_transfer");
+pub const COIN_BURN_FUNC_NAME: &IdentStr = ident_str!("burn");
 
 pub const PAY_MODULE_NAME: &IdentStr = ident_str!("pay");
 pub const PAY_JOIN_FUNC_NAME: &IdentStr = ident_str!("join");
@@ -28,6 +28,7 @@ use typed_store::rocks::TypedStoreError;
 
 pub const TRANSACTION_NOT_FOUND_MSG_PREFIX: &str = "Could not find the referenced transaction";
 pub const TRANSACTIONS_NOT_FOUND_MSG_PREFIX: &str = "Could not find the referenced transactions";
+pub const CHECKPOINT_NOT_FOUND_MSG_PREFIX: &str = "Verified checkpoint not found";
 
 #[macro_export]
 macro_rules! fp_bail {
@@ -183,6 +184,48 @@ pub enum UserInputError {
 
     #[error("Feature is not yet supported: {0}")]
     Unsupported(String),
+
+    #[error(
+        "Input coins must all be of the same coin type, found: {:?}",
+        coin_types
+    )]
+    MixedCoinTypes { coin_types: Vec<String> },
+
+    #[error("Object {object_id} is not owned by {sender}, its owner is {owner:?}")]
+    NotOwned {
+        object_id: ObjectID,
+        sender: SuiAddress,
+        owner: Option<Owner>,
+    },
+
+    #[error("Input coins hold {available}, which is less than the needed amount {needed}")]
+    InsufficientFunds { needed: u128, available: u128 },
+
+    #[error("Coin {coin} cannot be merged into itself or merged more than once")]
+    InvalidMergeArguments { coin: ObjectID },
+
+    #[error("Recipient {recipient} appears more than once in the payment")]
+    DuplicateRecipient { recipient: SuiAddress },
+
+    #[error("Stake amount {amount} is below the minimum stake amount {min}")]
+    StakeBelowMinimum { amount: u64, min: u64 },
+
+    #[error("Input index {index} is out of bounds, the transaction has {len} inputs")]
+    InputIndexOutOfBounds { index: usize, len: usize },
+    #[error("Input at index {index} is not a {expected} input")]
+    UnexpectedInputKind { index: usize, expected: String },
+    #[error("Pure input at index {index} is {expected} bytes, got {actual} bytes")]
+    PureInputSizeMismatch {
+        index: usize,
+        expected: usize,
+        actual: usize,
+    },
+
+    #[error("Malformed transaction data: {error}")]
+    MalformedTransactionData { error: String },
+
+    #[error("Transaction has no effect, it would only be charged gas")]
+    NoOpTransaction,
 }
 
 /// Custom error type for Sui.
@@ -141,6 +141,13 @@ impl GasCostSummary {
         self.gas_used() as i64 - self.storage_rebate as i64
     }
 
+    /// Net SUI charged to the gas owner: computation and storage costs minus the storage rebate.
+    /// The sender's SUI balance change is the amount transferred out plus this value, so it can
+    /// be smaller than expected when the rebate outweighs the costs.
+    pub fn net_change(&self) -> i128 {
+        self.computation_cost as i128 + self.storage_cost as i128 - self.storage_rebate as i128
+    }
+
     pub fn new_from_txn_effects<'a>(
         transactions: impl Iterator<Item = &'a TransactionEffects>,
     ) -> GasCostSummary {
@@ -1,12 +1,15 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::{anyhow, ensure};
 use move_core_types::{
     ident_str,
     identifier::IdentStr,
     language_storage::{StructTag, TypeTag},
     value::MoveStructLayout,
 };
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::convert::{TryFrom, TryInto};
 use std::fmt::{Display, Formatter};
@@ -21,15 +24,40 @@ use crate::{
     SUI_FRAMEWORK_ADDRESS,
 };
 
+#[cfg(test)]
+#[path = "unit_tests/gas_coin_tests.rs"]
+mod gas_coin_tests;
+
 /// The number of Mist per Sui token
 pub const MIST_PER_SUI: u64 = 1_000_000_000;
 
+/// Number of decimal places of a Sui amount, i.e. the precision of Mist
+pub const SUI_DECIMALS: u32 = 9;
+
 /// Total supply denominated in Sui
 pub const TOTAL_SUPPLY_SUI: u64 = 10_000_000_000;
 
 /// Total supply denominated in Mist
 pub const TOTAL_SUPPLY_MIST: u64 = TOTAL_SUPPLY_SUI * MIST_PER_SUI;
 
+/// Convert an amount of Sui to Mist. Fails if the amount is negative, more precise than a Mist
+/// or too large to fit in a u64.
+pub fn sui_to_mist(sui: Decimal) -> anyhow::Result<u64> {
+    ensure!(!sui.is_sign_negative(), "Negative amount of SUI: {sui}");
+    ensure!(
+        sui.normalize().scale() <= SUI_DECIMALS,
+        "{sui} SUI has more than {SUI_DECIMALS} decimal places"
+    );
+    sui.checked_mul(Decimal::from(MIST_PER_SUI))
+        .and_then(|mist| mist.to_u64())
+        .ok_or_else(|| anyhow!("{sui} SUI overflows the amount of MIST a u64 can hold"))
+}
+
+/// Convert an amount of Mist to Sui, the conversion is exact.
+pub fn mist_to_sui(mist: u64) -> Decimal {
+    Decimal::from_i128_with_scale(mist as i128, SUI_DECIMALS).normalize()
+}
+
 pub const GAS_MODULE_NAME: &IdentStr = ident_str!("sui");
 pub const GAS_STRUCT_NAME: &IdentStr = ident_str!("SUI");
 
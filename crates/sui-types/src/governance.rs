@@ -18,6 +18,10 @@ use serde::Serialize;
 /// Minimum amount of stake required for a validator to be in the validator set
 pub const MINIMUM_VALIDATOR_STAKE_SUI: u64 = 25_000_000;
 
+/// Minimum amount of MIST that can be staked in a single request, as enforced by
+/// `validator::request_add_stake` in system state version 1.
+pub const MIN_STAKE_AMOUNT_MIST: u64 = 1;
+
 pub const STAKING_POOL_MODULE_NAME: &IdentStr = ident_str!("staking_pool");
 pub const STAKED_SUI_STRUCT_NAME: &IdentStr = ident_str!("StakedSui");
 
@@ -22,6 +22,7 @@ pub use mysten_network::multiaddr;
 #[macro_use]
 pub mod error;
 
+pub mod abort_codes;
 pub mod accumulator;
 pub mod balance;
 pub mod base_types;
@@ -626,6 +626,52 @@ impl ProgrammableTransaction {
             .collect())
     }
 
+    /// Return a copy of the transaction with the pure input at `index` replaced by `bytes`.
+    /// The new value must have the same BCS size as the one it replaces.
+    pub fn remap_pure_input(&self, index: usize, bytes: Vec<u8>) -> UserInputResult<Self> {
+        let mut pt = self.clone();
+        let len = pt.inputs.len();
+        match pt.inputs.get_mut(index) {
+            Some(CallArg::Pure(old)) => {
+                fp_ensure!(
+                    old.len() == bytes.len(),
+                    UserInputError::PureInputSizeMismatch {
+                        index,
+                        expected: old.len(),
+                        actual: bytes.len(),
+                    }
+                );
+                *old = bytes;
+            }
+            Some(CallArg::Object(_)) => {
+                return Err(UserInputError::UnexpectedInputKind {
+                    index,
+                    expected: "pure".to_string(),
+                })
+            }
+            None => return Err(UserInputError::InputIndexOutOfBounds { index, len }),
+        }
+        Ok(pt)
+    }
+
+    /// Return a copy of the transaction with the owned object input at `index` replaced by
+    /// `object_ref`.
+    pub fn remap_object_input(&self, index: usize, object_ref: ObjectRef) -> UserInputResult<Self> {
+        let mut pt = self.clone();
+        let len = pt.inputs.len();
+        match pt.inputs.get_mut(index) {
+            Some(CallArg::Object(ObjectArg::ImmOrOwnedObject(old))) => *old = object_ref,
+            Some(_) => {
+                return Err(UserInputError::UnexpectedInputKind {
+                    index,
+                    expected: "owned object".to_string(),
+                })
+            }
+            None => return Err(UserInputError::InputIndexOutOfBounds { index, len }),
+        }
+        Ok(pt)
+    }
+
     fn validity_check(&self, config: &ProtocolConfig) -> UserInputResult {
         let ProgrammableTransaction { inputs, commands } = self;
         fp_ensure!(
@@ -934,6 +980,16 @@ pub enum TransactionExpiration {
     Epoch(EpochId),
 }
 
+impl TransactionExpiration {
+    /// Whether a transaction with this expiration can no longer be signed in `epoch`.
+    pub fn is_expired(&self, epoch: EpochId) -> bool {
+        match self {
+            TransactionExpiration::None => false,
+            TransactionExpiration::Epoch(expiration) => *expiration < epoch,
+        }
+    }
+}
+
 #[enum_dispatch(TransactionDataAPI)]
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub enum TransactionData {
@@ -1069,6 +1125,31 @@ impl TransactionData {
         })
     }
 
+    pub fn with_expiration(mut self, expiration: TransactionExpiration) -> Self {
+        *self.expiration_mut() = expiration;
+        self
+    }
+
+    pub fn with_gas_price(mut self, gas_price: u64) -> Self {
+        match &mut self {
+            TransactionData::V1(data) => data.gas_data.price = gas_price,
+        }
+        self
+    }
+
+    /// The digest the network will assign to this transaction once it is signed, so that it can
+    /// be displayed for confirmation before signing.
+    pub fn digest(&self) -> TransactionDigest {
+        TransactionDigest::new(default_hash(self))
+    }
+
+    /// Decode transaction data serialized with BCS, e.g. by an offline signer.
+    pub fn from_bcs(bytes: &[u8]) -> UserInputResult<Self> {
+        bcs::from_bytes(bytes).map_err(|e| UserInputError::MalformedTransactionData {
+            error: e.to_string(),
+        })
+    }
+
     pub fn new_move_call_with_dummy_gas_price(
         sender: SuiAddress,
         package: ObjectID,
@@ -1351,6 +1432,27 @@ impl TransactionData {
         Self::new_with_gas_coins(kind, sender, gas_payment, gas_budget, gas_price)
     }
 
+    pub fn new_programmable_allow_sponsor(
+        sender: SuiAddress,
+        gas_payment: Vec<ObjectRef>,
+        pt: ProgrammableTransaction,
+        gas_budget: u64,
+        gas_price: u64,
+        sponsor: SuiAddress,
+    ) -> Self {
+        let kind = TransactionKind::ProgrammableTransaction(pt);
+        Self::new_with_gas_data(
+            kind,
+            sender,
+            GasData {
+                price: gas_price,
+                owner: sponsor,
+                payment: gas_payment,
+                budget: gas_budget,
+            },
+        )
+    }
+
     pub fn execution_parts(&self) -> (TransactionKind, SuiAddress, Vec<ObjectRef>) {
         (
             self.kind().clone(),
@@ -1679,7 +1781,7 @@ impl Message for SenderSignedData {
     const SCOPE: IntentScope = IntentScope::SenderSignedTransaction;
 
     fn digest(&self) -> Self::DigestType {
-        TransactionDigest::new(default_hash(&self.intent_message().value))
+        self.intent_message().value.digest()
     }
 
     fn verify(&self, _sig_epoch: Option<EpochId>) -> SuiResult {
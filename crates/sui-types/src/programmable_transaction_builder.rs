@@ -4,20 +4,30 @@
 //! Utility for generating programmable transactions, either by specifying a command or for
 //! migrating legacy transactions
 
+use std::collections::BTreeSet;
+
 use anyhow::Context;
 use indexmap::IndexMap;
 use move_core_types::{ident_str, identifier::Identifier, language_storage::TypeTag};
 use serde::Serialize;
+use sui_protocol_config::ProtocolConfig;
 
 use crate::{
-    base_types::{ObjectID, ObjectRef, SuiAddress},
+    base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress},
+    coin::{COIN_BURN_FUNC_NAME, COIN_MODULE_NAME},
+    error::{UserInputError, UserInputResult},
     messages::{
         Argument, CallArg, Command, ObjectArg, ProgrammableMoveCall, ProgrammableTransaction,
+        TransactionData, TransactionDataAPI, TransactionKind,
     },
     move_package::PACKAGE_MODULE_NAME,
-    SUI_FRAMEWORK_OBJECT_ID,
+    SUI_CLOCK_OBJECT_ID, SUI_CLOCK_OBJECT_SHARED_VERSION, SUI_FRAMEWORK_OBJECT_ID,
 };
 
+/// Prefix of the pure input carrying a transaction memo, see
+/// [ProgrammableTransactionBuilder::with_memo].
+pub const MEMO_PREFIX: &[u8] = b"sui::memo\0";
+
 #[derive(PartialEq, Eq, Hash)]
 enum BuilderArg {
     Object(ObjectID),
@@ -29,6 +39,7 @@ enum BuilderArg {
 pub struct ProgrammableTransactionBuilder {
     inputs: IndexMap<BuilderArg, CallArg>,
     commands: Vec<Command>,
+    strict_recipients: bool,
 }
 
 impl ProgrammableTransactionBuilder {
@@ -37,11 +48,101 @@ impl ProgrammableTransactionBuilder {
     }
 
     pub fn finish(self) -> ProgrammableTransaction {
-        let Self { inputs, commands } = self;
+        let Self {
+            inputs, commands, ..
+        } = self;
         let inputs = inputs.into_values().collect();
         ProgrammableTransaction { inputs, commands }
     }
 
+    /// Check the transaction built so far against the size limits of `config`, so that an
+    /// oversized transaction can be rejected before it is signed and submitted.
+    pub fn validate_limits(&self, config: &ProtocolConfig) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.commands.len() < config.max_programmable_tx_commands() as usize,
+            UserInputError::SizeLimitExceeded {
+                limit: "maximum commands in a programmable transaction".to_string(),
+                value: config.max_programmable_tx_commands().to_string()
+            }
+        );
+        let input_objects = self
+            .inputs
+            .keys()
+            .filter(|arg| matches!(arg, BuilderArg::Object(_)))
+            .count();
+        anyhow::ensure!(
+            input_objects <= config.max_input_objects() as usize,
+            UserInputError::SizeLimitExceeded {
+                limit: "maximum input objects in a transaction".to_string(),
+                value: config.max_input_objects().to_string()
+            }
+        );
+        let inputs = self.inputs.values().collect::<Vec<_>>();
+        let size = bcs::serialized_size(&(inputs, &self.commands))?;
+        anyhow::ensure!(
+            size as u64 <= config.max_tx_size_bytes(),
+            UserInputError::SizeLimitExceeded {
+                limit: "maximum transaction size in bytes".to_string(),
+                value: config.max_tx_size_bytes().to_string()
+            }
+        );
+        Ok(())
+    }
+
+    /// Whether the transaction built so far is pointless when sent by `sender`: it has no
+    /// commands, or it only splits zero amounts off coins and transfers them, or objects it
+    /// owns, back to `sender`. This is a structural check that errs on the side of caution,
+    /// any other command, e.g. a move call, is assumed to have an effect.
+    pub fn is_effectively_noop(&self, sender: SuiAddress) -> bool {
+        fn is_zero_coin(zero_coins: &BTreeSet<u16>, arg: &Argument) -> bool {
+            matches!(arg, Argument::Result(i) if zero_coins.contains(i))
+        }
+        let input = |arg: &Argument| match arg {
+            Argument::Input(i) => self.inputs.get_index(*i as usize).map(|(_, input)| input),
+            _ => None,
+        };
+        let is_pure = |arg: &Argument, bytes: &[u8]| match input(arg) {
+            Some(CallArg::Pure(pure)) => pure == bytes,
+            _ => false,
+        };
+        let is_owned_object = |arg: &Argument| {
+            matches!(
+                input(arg),
+                Some(CallArg::Object(ObjectArg::ImmOrOwnedObject(_)))
+            )
+        };
+        let zero = bcs::to_bytes(&0u64).unwrap();
+        let sender = bcs::to_bytes(&sender).unwrap();
+
+        let mut zero_coins = BTreeSet::new();
+        for (i, command) in self.commands.iter().enumerate() {
+            match command {
+                Command::SplitCoin(_, amount) if is_pure(amount, &zero) => {
+                    zero_coins.insert(i as u16);
+                }
+                Command::MergeCoins(_, coins)
+                    if coins.iter().all(|coin| is_zero_coin(&zero_coins, coin)) => {}
+                Command::TransferObjects(objects, recipient)
+                    if is_pure(recipient, &sender)
+                        && objects.iter().all(|object| {
+                            is_zero_coin(&zero_coins, object) || is_owned_object(object)
+                        }) => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    /// Reject the transaction built so far if it is effectively a no-op when sent by `sender`,
+    /// see [Self::is_effectively_noop].
+    pub fn ensure_not_noop(&self, sender: SuiAddress) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            !self.is_effectively_noop(sender),
+            UserInputError::NoOpTransaction
+        );
+        Ok(())
+    }
+
     fn pure_bytes(&mut self, bytes: Vec<u8>, force_separate: bool) -> Argument {
         let arg = if force_separate {
             BuilderArg::ForcedNonUniquePure(self.inputs.len())
@@ -59,6 +160,18 @@ impl ProgrammableTransactionBuilder {
         ))
     }
 
+    /// Add a `vector<T>` pure argument. The values are BCS encoded after their ULEB128 length,
+    /// as Move expects for vector arguments.
+    pub fn pure_vec<T: Serialize>(&mut self, values: Vec<T>) -> anyhow::Result<Argument> {
+        self.pure(values)
+    }
+
+    /// Add an `Option<T>` pure argument. Move represents options as vectors of zero or one
+    /// element, the value is encoded as such.
+    pub fn pure_option<T: Serialize>(&mut self, value: Option<T>) -> anyhow::Result<Argument> {
+        self.pure_vec(value.into_iter().collect())
+    }
+
     /// Like pure but forces a separate input entry
     pub fn force_separate_pure<T: Serialize>(&mut self, value: T) -> anyhow::Result<Argument> {
         Ok(self.pure_bytes(
@@ -67,6 +180,22 @@ impl ProgrammableTransactionBuilder {
         ))
     }
 
+    /// Attach `memo` to the transaction as a pure input that no command uses, so that it can be
+    /// read back from the transaction data with [extract_memo]. The memo only lives in the
+    /// transaction itself: it creates no object and so has no storage cost, but it counts
+    /// towards the transaction size limit and, with [MEMO_PREFIX], must fit in the protocol's
+    /// `max_pure_argument_size`.
+    pub fn with_memo(&mut self, memo: &[u8]) {
+        self.pure_bytes([MEMO_PREFIX, memo].concat(), /* force separate */ true);
+    }
+
+    /// Make [Self::pay] and [Self::pay_sui] fail with [UserInputError::DuplicateRecipient] when
+    /// a recipient appears more than once. By default, the amounts of a repeated recipient are
+    /// all transferred to it.
+    pub fn strict_recipients(&mut self) {
+        self.strict_recipients = true;
+    }
+
     pub fn obj(&mut self, obj_arg: ObjectArg) -> anyhow::Result<Argument> {
         let id = obj_arg.id();
         let obj_arg = if let Some(old_value) = self.inputs.get(&BuilderArg::Object(id)) {
@@ -115,6 +244,44 @@ impl ProgrammableTransactionBuilder {
         Ok(Argument::Input(i as u16))
     }
 
+    /// Add the singleton `Clock` object as an input. The clock can only be taken by immutable
+    /// reference in user transactions.
+    pub fn clock_arg(&mut self) -> anyhow::Result<Argument> {
+        self.shared_object_arg(
+            SUI_CLOCK_OBJECT_ID,
+            SUI_CLOCK_OBJECT_SHARED_VERSION,
+            /* mutable */ false,
+        )
+    }
+
+    /// Add a shared object as an input. `mutable` must be true if any command takes the object
+    /// by `&mut` or by value, execution fails otherwise. Objects only taken by `&` should be
+    /// added read-only, and some, like the `Clock`, can't be taken mutably by user transactions.
+    /// An object added more than once is mutable if any of its uses is.
+    pub fn shared_object_arg(
+        &mut self,
+        id: ObjectID,
+        initial_shared_version: SequenceNumber,
+        mutable: bool,
+    ) -> anyhow::Result<Argument> {
+        self.obj(ObjectArg::SharedObject {
+            id,
+            initial_shared_version,
+            mutable,
+        })
+    }
+
+    /// Ids of the shared objects added as inputs so far, in input order.
+    pub fn shared_object_inputs(&self) -> Vec<ObjectID> {
+        self.inputs
+            .values()
+            .filter_map(|arg| match arg {
+                CallArg::Object(ObjectArg::SharedObject { id, .. }) => Some(*id),
+                CallArg::Object(ObjectArg::ImmOrOwnedObject(_)) | CallArg::Pure(_) => None,
+            })
+            .collect()
+    }
+
     pub fn input(&mut self, call_arg: CallArg) -> anyhow::Result<Argument> {
         match call_arg {
             CallArg::Pure(bytes) => Ok(self.pure_bytes(bytes, /* force separate */ false)),
@@ -130,7 +297,17 @@ impl ProgrammableTransactionBuilder {
             .into_iter()
             .map(|obj| self.obj(obj))
             .collect::<Result<_, _>>()?;
-        Ok(self.command(Command::MakeMoveVec(None, make_vec_args)))
+        Ok(self.make_move_vec(None, make_vec_args))
+    }
+
+    /// Construct a vector from `elements`, which can be objects or pure values. The element type
+    /// must be given if the elements are not objects, or if there are none.
+    pub fn make_move_vec(
+        &mut self,
+        type_tag: Option<TypeTag>,
+        elements: Vec<Argument>,
+    ) -> Argument {
+        self.command(Command::MakeMoveVec(type_tag, elements))
     }
 
     pub fn command(&mut self, command: Command) -> Argument {
@@ -247,6 +424,77 @@ impl ProgrammableTransactionBuilder {
         self.command(Command::TransferObjects(vec![Argument::GasCoin], rec_arg));
     }
 
+    /// Transfer all the SUI of the gas coin to `recipient`, except for `reserve` which is split
+    /// off into a new coin kept by `sender`. Gas is paid from the transferred remainder.
+    /// Will fail to generate if `gas_balance`, the value of the gas coin, can't cover both
+    /// `reserve` and `gas_budget` gas units at `gas_price`
+    pub fn pay_all_sui_except(
+        &mut self,
+        sender: SuiAddress,
+        recipient: SuiAddress,
+        reserve: u64,
+        gas_balance: u64,
+        gas_budget: u64,
+        gas_price: u64,
+    ) -> anyhow::Result<()> {
+        let needed_gas_amount = reserve as u128 + gas_budget as u128 * gas_price as u128;
+        anyhow::ensure!(
+            needed_gas_amount <= gas_balance as u128,
+            UserInputError::GasBalanceTooLow {
+                gas_balance: gas_balance as u128,
+                needed_gas_amount,
+            }
+        );
+        let amt_arg = self.pure(reserve)?;
+        let reserved = self.command(Command::SplitCoin(Argument::GasCoin, amt_arg));
+        self.transfer_arg(sender, reserved);
+        self.pay_all_sui(recipient);
+        Ok(())
+    }
+
+    /// Split `split_amount` off `source` into a new coin, then merge the rest of `source` into
+    /// `merge_target`, returning the new coin for the caller to use.
+    /// Will fail to generate if `split_amount` exceeds `source_balance`, the value of `source`
+    pub fn split_then_merge(
+        &mut self,
+        source: ObjectRef,
+        source_balance: u64,
+        split_amount: u64,
+        merge_target: ObjectRef,
+    ) -> anyhow::Result<Argument> {
+        anyhow::ensure!(
+            split_amount <= source_balance,
+            "Cannot split {split_amount} from coin {} with balance {source_balance}",
+            source.0
+        );
+        let source_arg = self.obj(ObjectArg::ImmOrOwnedObject(source))?;
+        let target_arg = self.obj(ObjectArg::ImmOrOwnedObject(merge_target))?;
+        let amt_arg = self.pure(split_amount)?;
+        let split = self.command(Command::SplitCoin(source_arg, amt_arg));
+        self.command(Command::MergeCoins(target_arg, vec![source_arg]));
+        Ok(split)
+    }
+
+    /// Burn `coin`, a coin of `coin_type`, using the `treasury_cap` of that coin type
+    pub fn burn_coin(
+        &mut self,
+        treasury_cap: ObjectRef,
+        coin: ObjectRef,
+        coin_type: TypeTag,
+    ) -> anyhow::Result<()> {
+        let cap_arg = self.obj(ObjectArg::ImmOrOwnedObject(treasury_cap))?;
+        let coin_arg = self.obj(ObjectArg::ImmOrOwnedObject(coin))?;
+        // The burnt amount returned by `coin::burn` is dropped.
+        self.programmable_move_call(
+            SUI_FRAMEWORK_OBJECT_ID,
+            COIN_MODULE_NAME.to_owned(),
+            COIN_BURN_FUNC_NAME.to_owned(),
+            vec![coin_type],
+            vec![cap_arg, coin_arg],
+        );
+        Ok(())
+    }
+
     /// Will fail to generate if recipients and amounts do not have the same lengths
     pub fn pay_sui(
         &mut self,
@@ -269,6 +517,8 @@ impl ProgrammableTransactionBuilder {
         else {
             anyhow::bail!("coins vector is empty");
         };
+        let merged = coins.as_slice().iter().map(|c| c.0).collect::<Vec<_>>();
+        check_merge_arguments(coin.0, &merged)?;
         let coin_arg = self.obj(ObjectArg::ImmOrOwnedObject(coin))?;
         let merge_args: Vec<_> = coins
             .map(|c| self.obj(ObjectArg::ImmOrOwnedObject(c)))
@@ -293,6 +543,18 @@ impl ProgrammableTransactionBuilder {
             )
         }
 
+        if self.strict_recipients {
+            let mut seen = BTreeSet::new();
+            for recipient in &recipients {
+                anyhow::ensure!(
+                    seen.insert(*recipient),
+                    UserInputError::DuplicateRecipient {
+                        recipient: *recipient
+                    }
+                );
+            }
+        }
+
         // collect recipients in the case where they are non-unique in order
         // to minimize the number of transfers that must be performed
         let mut recipient_map = IndexMap::new();
@@ -314,3 +576,30 @@ impl ProgrammableTransactionBuilder {
         Ok(())
     }
 }
+
+/// Check that the coins merged into `primary_coin` are distinct and do not include it, since a
+/// coin cannot be merged into itself or merged twice.
+pub fn check_merge_arguments(
+    primary_coin: ObjectID,
+    coins_to_merge: &[ObjectID],
+) -> UserInputResult<()> {
+    let mut seen = BTreeSet::from([primary_coin]);
+    for coin in coins_to_merge {
+        fp_ensure!(
+            seen.insert(*coin),
+            UserInputError::InvalidMergeArguments { coin: *coin }
+        );
+    }
+    Ok(())
+}
+
+/// Return the memo attached to `tx` with [ProgrammableTransactionBuilder::with_memo], if any.
+pub fn extract_memo(tx: &TransactionData) -> Option<&[u8]> {
+    let TransactionKind::ProgrammableTransaction(pt) = tx.kind() else {
+        return None;
+    };
+    pt.inputs.iter().find_map(|input| match input {
+        CallArg::Pure(bytes) => bytes.strip_prefix(MEMO_PREFIX),
+        CallArg::Object(_) => None,
+    })
+}
@@ -24,4 +24,6 @@ pub enum TransactionFilter {
     FromAddress(SuiAddress),
     /// Query by recipient address.
     ToAddress(SuiAddress),
+    /// Query by sender or recipient address.
+    FromOrToAddress(SuiAddress),
 }
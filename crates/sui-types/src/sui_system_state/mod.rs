@@ -23,6 +23,8 @@ pub mod sui_system_state_inner_v1;
 pub mod sui_system_state_summary;
 
 const SUI_SYSTEM_STATE_WRAPPER_STRUCT_NAME: &IdentStr = ident_str!("SuiSystemState");
+const SUI_SYSTEM_STATE_INNER_MODULE_NAME: &IdentStr = ident_str!("sui_system_state_inner");
+const SYSTEM_EPOCH_INFO_EVENT_STRUCT_NAME: &IdentStr = ident_str!("SystemEpochInfoEvent");
 
 pub const SUI_SYSTEM_MODULE_NAME: &IdentStr = ident_str!("sui_system");
 pub const ADVANCE_EPOCH_FUNCTION_NAME: &IdentStr = ident_str!("advance_epoch");
@@ -56,6 +58,35 @@ impl SuiSystemStateWrapper {
     }
 }
 
+/// Rust version of the Move sui::sui_system_state_inner::SystemEpochInfoEvent type, emitted by
+/// the system state when it advances to `epoch`.
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct SystemEpochInfoEvent {
+    pub epoch: u64,
+    pub protocol_version: u64,
+    pub reference_gas_price: u64,
+    pub total_stake: u64,
+    pub storage_fund_reinvestment: u64,
+    pub storage_charge: u64,
+    pub storage_rebate: u64,
+    pub storage_fund_balance: u64,
+    pub stake_subsidy_amount: u64,
+    pub total_gas_fees: u64,
+    pub total_stake_rewards_distributed: u64,
+    pub leftover_storage_fund_inflow: u64,
+}
+
+impl SystemEpochInfoEvent {
+    pub fn type_() -> StructTag {
+        StructTag {
+            address: SUI_FRAMEWORK_ADDRESS,
+            name: SYSTEM_EPOCH_INFO_EVENT_STRUCT_NAME.to_owned(),
+            module: SUI_SYSTEM_STATE_INNER_MODULE_NAME.to_owned(),
+            type_params: vec![],
+        }
+    }
+}
+
 /// This is the standard API that all inner system state object type should implement.
 #[enum_dispatch]
 pub trait SuiSystemStateTrait {
@@ -0,0 +1,29 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::account_address::AccountAddress;
+use move_core_types::ident_str;
+use move_core_types::language_storage::ModuleId;
+
+use super::explain_abort;
+use crate::{MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS};
+
+#[test]
+fn test_explain_framework_abort() {
+    let balance = ModuleId::new(SUI_FRAMEWORK_ADDRESS, ident_str!("balance").to_owned());
+    assert_eq!(
+        Some("Insufficient balance".to_string()),
+        explain_abort(&balance, 2)
+    );
+    assert_eq!(None, explain_abort(&balance, 42));
+
+    let option = ModuleId::new(MOVE_STDLIB_ADDRESS, ident_str!("option").to_owned());
+    assert_eq!(
+        Some("The option is empty while it should be set".to_string()),
+        explain_abort(&option, 0x40001)
+    );
+
+    // Modules published by users are unknown, even if they share a framework module's name.
+    let user_module = ModuleId::new(AccountAddress::random(), ident_str!("balance").to_owned());
+    assert_eq!(None, explain_abort(&user_module, 2));
+}
@@ -262,6 +262,31 @@ fn test_address_display() {
     assert_eq!(format!("{:#X}", id), format!("0x{upper_hex}"));
 }
 
+#[test]
+fn test_address_normalize() {
+    let address =
+        SuiAddress::from_str("00000000000000000000000000000000000000000000000000000000000ec11a")
+            .unwrap();
+    for s in [
+        "0x00000000000000000000000000000000000000000000000000000000000ec11a",
+        "00000000000000000000000000000000000000000000000000000000000ec11a",
+        "0x00000000000000000000000000000000000000000000000000000000000EC11A",
+        "0X00000000000000000000000000000000000000000000000000000000000Ec11A",
+        "0xec11a",
+        "ec11a",
+        "0xEC11a",
+    ] {
+        assert_eq!(address, SuiAddress::normalize(s).unwrap(), "{s}");
+    }
+
+    SuiAddress::normalize("").unwrap_err();
+    SuiAddress::normalize("0x").unwrap_err();
+    SuiAddress::normalize("0xec11g").unwrap_err();
+    // Too long
+    SuiAddress::normalize("0x100000000000000000000000000000000000000000000000000000000000ec11a")
+        .unwrap_err();
+}
+
 #[test]
 fn test_address_serde_not_human_readable() {
     let address = SuiAddress::random_for_testing_only();
@@ -0,0 +1,39 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+
+use super::{mist_to_sui, sui_to_mist, MIST_PER_SUI, TOTAL_SUPPLY_MIST};
+
+#[test]
+fn test_sui_mist_round_trip() {
+    let amounts = [
+        0,
+        1,
+        50000,
+        1000000,
+        MIST_PER_SUI,
+        TOTAL_SUPPLY_MIST,
+        u64::MAX,
+    ];
+    for mist in amounts {
+        assert_eq!(mist, sui_to_mist(mist_to_sui(mist)).unwrap());
+    }
+    assert_eq!(Decimal::from_str("0.00005").unwrap(), mist_to_sui(50000));
+    assert_eq!(Decimal::ONE, mist_to_sui(MIST_PER_SUI));
+    assert_eq!(
+        1_500_000_000,
+        sui_to_mist(Decimal::from_str("1.500000000").unwrap()).unwrap()
+    );
+}
+
+#[test]
+fn test_sui_to_mist_rejects_invalid_amounts() {
+    // More precise than a MIST.
+    assert!(sui_to_mist(Decimal::from_str("0.0000000001").unwrap()).is_err());
+    assert!(sui_to_mist(Decimal::from_str("-1").unwrap()).is_err());
+    // Overflows u64.
+    assert!(sui_to_mist(Decimal::from(u64::MAX)).is_err());
+}
@@ -13,6 +13,7 @@ use test_utils::messages::MAX_GAS;
 
 use super::*;
 use crate::base_types::random_object_ref;
+use crate::coin::{COIN_BURN_FUNC_NAME, COIN_MODULE_NAME};
 use crate::crypto::bcs_signable_test::{get_obligation_input, Foo};
 use crate::crypto::Secp256k1SuiSignature;
 use crate::crypto::SuiKeyPair;
@@ -24,6 +25,8 @@ use crate::crypto::{
     AuthoritySignInfoTrait, SuiAuthoritySignature,
 };
 use crate::object::Owner;
+use crate::programmable_transaction_builder::{check_merge_arguments, extract_memo};
+use crate::SUI_FRAMEWORK_OBJECT_ID;
 
 #[test]
 fn test_signed_values() {
@@ -875,6 +878,50 @@ fn test_sponsored_transaction_validity_check() {
         .unwrap();
 }
 
+#[test]
+fn test_programmable_transaction_builder_limits() {
+    let config = ProtocolConfig::get_for_max_version();
+    let mut builder = ProgrammableTransactionBuilder::new();
+    let recipient = builder.pure(SuiAddress::random_for_testing_only()).unwrap();
+    for _ in 1..config.max_programmable_tx_commands() {
+        builder.command(Command::TransferObjects(vec![], recipient));
+    }
+    builder.validate_limits(&config).unwrap();
+
+    builder.command(Command::TransferObjects(vec![], recipient));
+    let err = builder.validate_limits(&config).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("maximum commands in a programmable transaction"));
+}
+
+#[test]
+fn test_programmable_transaction_builder_noop() {
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    assert!(ProgrammableTransactionBuilder::new().is_effectively_noop(sender));
+
+    // Sending nothing to yourself is a no-op, and can be rejected.
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder.transfer_sui(sender, Some(0));
+    assert!(builder.is_effectively_noop(sender));
+    let err = builder.ensure_not_noop(sender).unwrap_err();
+    assert_eq!(
+        Some(&UserInputError::NoOpTransaction),
+        err.downcast_ref::<UserInputError>()
+    );
+
+    // Sending nothing to someone else, or something to yourself, creates a coin for them.
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder.transfer_sui(recipient, Some(0));
+    assert!(!builder.is_effectively_noop(sender));
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder.transfer_sui(recipient, Some(1000));
+    assert!(!builder.is_effectively_noop(sender));
+    builder.ensure_not_noop(sender).unwrap();
+}
+
 #[test]
 fn verify_sender_signature_correctly_with_flag() {
     // set up authorities
@@ -1344,3 +1391,142 @@ fn check_approx_effects_components_size() {
         "Update APPROX_SIZE_OF_EXECUTION_STATUS constant"
     );
 }
+
+#[test]
+fn test_memo_round_trip() {
+    let sender = get_new_address::<AccountKeyPair>();
+    let recipient = get_new_address::<AccountKeyPair>();
+    let memo = b"exchange-deposit-42";
+
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.transfer_sui(recipient, Some(100));
+        builder.with_memo(memo);
+        builder.finish()
+    };
+    let data = TransactionData::new_programmable(
+        sender,
+        vec![random_object_ref()],
+        pt,
+        10000,
+        DUMMY_GAS_PRICE,
+    );
+    data.validity_check(&ProtocolConfig::get_for_max_version())
+        .unwrap();
+
+    // The memo survives serialization of the transaction data.
+    let data: TransactionData = bcs::from_bytes(&bcs::to_bytes(&data).unwrap()).unwrap();
+    assert_eq!(Some(&memo[..]), extract_memo(&data));
+
+    // Other pure inputs are not mistaken for a memo.
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.transfer_sui(recipient, Some(100));
+        builder.pure(memo.to_vec()).unwrap();
+        builder.finish()
+    };
+    let data = TransactionData::new_programmable(
+        sender,
+        vec![random_object_ref()],
+        pt,
+        10000,
+        DUMMY_GAS_PRICE,
+    );
+    assert_eq!(None, extract_memo(&data));
+}
+
+#[test]
+fn test_invalid_merge_arguments() {
+    let coin = random_object_ref();
+    let other = random_object_ref();
+    let recipient = get_new_address::<AccountKeyPair>();
+
+    // A coin cannot be merged into itself.
+    assert_eq!(
+        Err(UserInputError::InvalidMergeArguments { coin: coin.0 }),
+        check_merge_arguments(coin.0, &[other.0, coin.0])
+    );
+
+    // Nor merged twice.
+    let err = ProgrammableTransactionBuilder::new()
+        .pay(vec![coin, other, other], vec![recipient], vec![100])
+        .unwrap_err();
+    assert_eq!(
+        Some(&UserInputError::InvalidMergeArguments { coin: other.0 }),
+        err.downcast_ref::<UserInputError>()
+    );
+
+    assert!(check_merge_arguments(coin.0, &[other.0]).is_ok());
+}
+
+#[test]
+fn test_burn_coin() {
+    let treasury_cap = random_object_ref();
+    let coin = random_object_ref();
+    let coin_type = TypeTag::Struct(Box::new(StructTag {
+        address: ObjectID::random().into(),
+        module: Identifier::new("managed").unwrap(),
+        name: Identifier::new("MANAGED").unwrap(),
+        type_params: vec![],
+    }));
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder
+        .burn_coin(treasury_cap, coin, coin_type.clone())
+        .unwrap();
+    let pt = builder.finish();
+    assert_eq!(
+        vec![
+            CallArg::Object(ObjectArg::ImmOrOwnedObject(treasury_cap)),
+            CallArg::Object(ObjectArg::ImmOrOwnedObject(coin)),
+        ],
+        pt.inputs
+    );
+    assert_eq!(
+        vec![Command::MoveCall(Box::new(ProgrammableMoveCall {
+            package: SUI_FRAMEWORK_OBJECT_ID,
+            module: COIN_MODULE_NAME.to_owned(),
+            function: COIN_BURN_FUNC_NAME.to_owned(),
+            type_arguments: vec![coin_type],
+            arguments: vec![Argument::Input(0), Argument::Input(1)],
+        }))],
+        pt.commands
+    );
+}
+
+#[test]
+fn test_strict_recipients() {
+    let coin = random_object_ref();
+    let recipient = get_new_address::<AccountKeyPair>();
+    let other = get_new_address::<AccountKeyPair>();
+    let recipients = vec![recipient, other, recipient];
+
+    // Repeated recipients are allowed by default.
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder
+        .pay(vec![coin], recipients.clone(), vec![100, 200, 300])
+        .unwrap();
+    builder
+        .pay_sui(vec![recipient, recipient], vec![100, 200])
+        .unwrap();
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder.strict_recipients();
+    let err = builder
+        .pay(vec![coin], recipients, vec![100, 200, 300])
+        .unwrap_err();
+    assert_eq!(
+        Some(&UserInputError::DuplicateRecipient { recipient }),
+        err.downcast_ref::<UserInputError>()
+    );
+    let err = builder
+        .pay_sui(vec![other, other], vec![100, 200])
+        .unwrap_err();
+    assert_eq!(
+        Some(&UserInputError::DuplicateRecipient { recipient: other }),
+        err.downcast_ref::<UserInputError>()
+    );
+    builder
+        .pay_sui(vec![recipient, other], vec![100, 200])
+        .unwrap();
+}